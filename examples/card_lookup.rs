@@ -0,0 +1,56 @@
+//! Looks a card up by id or name and prints a summary of it:
+//!
+//! ```bash
+//! POKEMON_TCG_API_KEY=KEY_HERE cargo run --example card_lookup -- xy1-1
+//! cargo run --example card_lookup -- "Charizard ex"
+//! ```
+//!
+//! Without `POKEMON_TCG_API_KEY` the API's lower keyless rate limit applies.
+
+use pokemon_tcg_sdk_rs::{ Client, NameMatch };
+use pokemon_tcg_sdk_rs::models::errors::Error;
+use pokemon_tcg_sdk_rs::models::models::{ Card, CardId };
+
+#[tokio::main]
+async fn main() {
+    let Some(query) = std::env::args().nth(1) else {
+        eprintln!("usage: card_lookup <card id or name>, e.g. xy1-1 or \"Charizard ex\"");
+        std::process::exit(2);
+    };
+
+    let api = Client::from_env().unwrap_or_else(|_| Client::without_key());
+    match lookup(&api, &query).await {
+        Ok(cards) if cards.is_empty() => {
+            eprintln!("no card matches {query:?}");
+            std::process::exit(1);
+        },
+        Ok(cards) => cards.iter().for_each(print_summary),
+        Err(e) => {
+            eprintln!("lookup failed: {e}");
+            std::process::exit(1);
+        },
+    }
+}
+
+/// The card with id query, or else the cards named query, by collector number.
+async fn lookup(api: &Client, query: &str) -> Result<Vec<Card>, Error> {
+    if let Ok(id) = query.parse::<CardId>() {
+        if let Some(card) = api.find_card(&id).await? {
+            return Ok(vec![card]);
+        }
+    }
+
+    let mut cards = api.cards_named(query, NameMatch::Exact).await?;
+    cards.sort_by_key(Card::number_sort_key);
+    Ok(cards)
+}
+
+/// Set, rarity, attacks and the rest of the card's text, then its market price.
+fn print_summary(card: &Card) {
+    println!("{}", card.detailed_text());
+    match card.best_market_price() {
+        Some((variant, price)) => println!("Market price: ${price} ({variant})"),
+        None => println!("Market price: none on TCGPlayer"),
+    }
+    println!();
+}
@@ -0,0 +1,24 @@
+//! Card search running in the browser, checked with
+//! `cargo check --target wasm32-unknown-unknown --example wasm`; natively it runs like any binary.
+
+use pokemon_tcg_sdk_rs::{ Client, Q };
+
+async fn search() {
+    let api = Client::without_key();
+
+    match api.search_cards(Q::field("name").eq("pikachu")).await {
+        Ok(cards) => cards.iter().for_each(|card| println!("{card}")),
+        Err(e) => eprintln!("search failed: {e}"),
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn main() {
+    wasm_bindgen_futures::spawn_local(search());
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[tokio::main]
+async fn main() {
+    search().await;
+}
@@ -0,0 +1,394 @@
+//! Synchronous Client for programs without an async runtime, enabled by the `blocking` feature.
+//!
+//! ```ignore
+//! use pokemon_tcg_sdk_rs::blocking::Client;
+//!
+//! let api = Client::from_env()?;
+//! let card: Option<Card> = api.find::<Card>("xy1-1")?;
+//! ```
+
+use std::collections::{ HashMap, HashSet };
+use std::fmt::Debug;
+use std::io::Write;
+use std::sync::Arc;
+
+use reqwest::StatusCode;
+use serde::de::DeserializeOwned;
+
+use crate::{ check_content_type, check_status, count_args, decode, normalized_query, trace, transport_error, validate_args, with_bulk_order, with_page_size, ClientBuilder, Container, Identifiable, OrderBy, Page, Resource, RetryPolicy, VecContainer, API_KEY_ENV_VAR };
+use crate::cassette::Cassette;
+use crate::hooks::Hooks;
+use crate::retry::Operation;
+use crate::models::errors::{ Error, ResponseMeta };
+use crate::models::models::{ Card, CardId, Set, SetId };
+use crate::throttle::RateLimiter;
+
+/// Blocking counterpart of [`crate::Client`], with the same Result-based methods. Responses are
+/// not cached.
+#[derive(Clone, Debug)]
+pub struct Client {
+    pub(crate) client: reqwest::blocking::Client,
+    pub(crate) key: Option<String>,
+    pub(crate) base_url: String,
+    pub(crate) retry: RetryPolicy,
+    pub(crate) allow_unknown_args: bool,
+    pub(crate) page_size: u32,
+    pub(crate) hooks: Hooks,
+    pub(crate) cassette: Option<Cassette>,
+    pub(crate) rate_limiter: Option<RateLimiter>,
+    pub(crate) max_response_size: u64,
+    /// Retry budget and circuit breaker of the bulk fetch this Client was cloned for.
+    pub(crate) operation: Option<Arc<Operation>>,
+    pub(crate) bulk_order_by: OrderBy,
+}
+
+impl Client {
+    /// Creates a new instance with provided API Key and the default configuration.
+    ///
+    /// # Panics
+    /// Like `reqwest::blocking::Client::new`, panics when the TLS backend cannot be initialized or
+    /// when called from within an async runtime; use `ClientBuilder::build_blocking` to handle
+    /// the former instead.
+    pub fn new(key: String) -> Self {
+        ClientBuilder::default().api_key(&key).build_blocking().expect("failed to initialize the HTTP client")
+    }
+
+    /// Creates a new instance that sends no API Key, subject to the API's keyless rate limit.
+    ///
+    /// # Panics
+    /// Same as `Client::new`.
+    pub fn without_key() -> Self {
+        ClientBuilder::default().build_blocking().expect("failed to initialize the HTTP client")
+    }
+
+    /// Creates a new instance with the API Key found in the `POKEMON_TCG_API_KEY` env variable.
+    pub fn from_env() -> Result<Self, Error> {
+        let key = std::env::var(API_KEY_ENV_VAR).map_err(|_| Error::ApiKeyNotFound)?;
+        ClientBuilder::default().api_key(&key).build_blocking()
+    }
+
+    /// Creates a new instance configured by the `POKEMON_TCG_*` env variables, see
+    /// `ClientBuilder::from_env_config`.
+    pub fn from_env_config() -> Result<Self, Error> {
+        ClientBuilder::from_env_config()?.build_blocking()
+    }
+
+    /// Sends a GET request to url, retrying as the retry policy allows like the async Client,
+    /// within the retry budget and circuit breaker of a bulk fetch.
+    fn send(&self, url: &str, query: &HashMap<String, String>) -> Result<reqwest::blocking::Response, Error> {
+        let operation = self.operation.as_deref();
+        let mut attempt = 0;
+
+        loop {
+            if let Some(operation) = operation {
+                operation.check()?;
+            }
+
+            let mut request = self.client.get(url).query(&normalized_query(query));
+            if let Some(key) = &self.key {
+                request = request.header("X-Api-Key", key.as_str());
+            }
+
+            let mut request = request.build().map_err(|e| transport_error(url, e))?;
+            self.hooks.on_request(&mut request);
+
+            if let Some(wait) = self.rate_limiter.as_ref().map(RateLimiter::reserve) {
+                std::thread::sleep(wait);
+            }
+
+            let can_retry = || attempt < self.retry.max_retries && operation.is_none_or(Operation::take_retry);
+            let sent = std::time::Instant::now();
+            let resp = match self.client.execute(request) {
+                Ok(resp) => {
+                    self.hooks.on_response(resp.url(), resp.status(), resp.headers(), sent.elapsed());
+                    resp
+                },
+                Err(e) => {
+                    if let Some(operation) = operation {
+                        operation.failure()?;
+                    }
+                    if !((e.is_connect() || e.is_timeout()) && self.retry.retry_transient && can_retry()) {
+                        return Err(transport_error(url, e));
+                    }
+
+                    trace::debug!(error = %e, attempt, "retrying after transient error");
+                    std::thread::sleep(self.retry.backoff(attempt));
+                    attempt += 1;
+                    continue;
+                },
+            };
+
+            let status = resp.status();
+            if status == StatusCode::TOO_MANY_REQUESTS {
+                let retry_after = self.retry.rate_limit_delay(resp.headers(), attempt);
+                if !self.retry.retry_on_rate_limit || !can_retry() {
+                    return Err(Error::RateLimited { retry_after, meta: ResponseMeta::new(resp.url().as_str(), status, resp.headers()) });
+                }
+
+                trace::debug!(wait_ms = retry_after.as_millis() as u64, "rate limited, retrying");
+                std::thread::sleep(retry_after);
+            } else if status.is_server_error() {
+                if let Some(operation) = operation {
+                    operation.failure()?;
+                }
+                if !self.retry.retry_transient || !can_retry() {
+                    return Err(Error::ServerError { status: status.as_u16(), meta: ResponseMeta::new(resp.url().as_str(), status, resp.headers()) });
+                }
+
+                trace::debug!(status = status.as_u16(), attempt, "retrying after server error");
+                std::thread::sleep(self.retry.backoff(attempt));
+            } else {
+                if let Some(operation) = operation {
+                    operation.success();
+                }
+                return Ok(resp);
+            }
+
+            attempt += 1;
+        }
+    }
+
+    fn fetch_body(&self, url: &str, query: &HashMap<String, String>) -> Result<(ResponseMeta, String), Error> {
+        #[cfg(feature = "tracing")]
+        let _span = crate::trace::request_span(url, query).entered();
+
+        if let Some(recorded) = self.cassette.as_ref().map(|c| c.replay(url, query)).transpose()?.flatten() {
+            return Ok(recorded);
+        }
+
+        let resp = self.send(url, query)?;
+        let meta = ResponseMeta::new(resp.url().as_str(), resp.status(), resp.headers());
+        let body = read_body(resp, url, self.max_response_size)?;
+        check_content_type(&meta, &body)?;
+
+        if let Some(cassette) = &self.cassette {
+            cassette.record(url, query, meta.status, &body)?;
+        }
+
+        Ok((meta, body))
+    }
+
+    fn fetch_page<T: Resource + DeserializeOwned>(&self, query: &HashMap<String, String>) -> Result<VecContainer<T>, Error> {
+        validate_args(query, self.allow_unknown_args)?;
+        let url = format!("{}/{}", self.base_url, T::path());
+        let (meta, body) = self.fetch_body(&url, query)?;
+        check_status(&meta, &body)?;
+        decode(&url, &body)
+    }
+
+    fn fetch_all<T: Resource + DeserializeOwned>(&self, args: HashMap<String, String>) -> Result<Vec<T>, Error> {
+        let client = Client { operation: self.retry.operation().map(Arc::new), ..self.clone() };
+        let mut args = with_bulk_order(with_page_size(args, self.page_size), &T::path(), &self.bulk_order_by);
+        let mut page: u32 = args.get("page").and_then(|p| p.parse().ok()).unwrap_or(1);
+        let mut res = Vec::<T>::new();
+        let mut seen = HashSet::new();
+
+        loop {
+            args.insert(String::from("page"), format!("{page}"));
+            let container = client.fetch_page::<T>(&args)?;
+            if let Some(operation) = &client.operation {
+                operation.page_fetched();
+            }
+            let last_page = container.is_last_page();
+            // pages shifting mid-download may repeat an item, see `BulkMeta::duplicates_dropped`
+            res.extend(container.data.into_iter().filter(|item| item.bulk_id().is_none_or(|id| seen.insert(id.to_owned()))));
+
+            if last_page {
+                break;
+            }
+            page += 1;
+        }
+
+        Ok(res)
+    }
+
+    /// Attempts to find a Type T by id; only available for identifiable resources (cards and sets).
+    /// Returns Ok(None) when the API does not know the id.
+    pub fn find<T: Identifiable + DeserializeOwned + Clone>(&self, id: &str) -> Result<Option<T>, Error> {
+        let url = format!("{}/{}/{id}", self.base_url, T::path());
+        let (meta, body) = self.fetch_body(&url, &HashMap::new())?;
+
+        if meta.status == StatusCode::NOT_FOUND.as_u16() {
+            return Ok(None);
+        }
+        check_status(&meta, &body)?;
+
+        let container: Container<T> = decode(&url, &body)?;
+        Ok(Some(container.data))
+    }
+
+    /// Searches for T candidates that match args HashMap<String, String> and returns a Vec<T>.
+    pub fn query<T: Resource + DeserializeOwned + Clone + Debug>(&self, args: HashMap<String, String>) -> Result<Vec<T>, Error> {
+        self.fetch_all::<T>(args)
+    }
+
+    /// Former name of `query`.
+    #[deprecated(note = "renamed to `query`")]
+    pub fn _where<T: Resource + DeserializeOwned + Clone + Debug>(&self, args: HashMap<String, String>) -> Result<Vec<T>, Error> {
+        self.query(args)
+    }
+
+    /// Returns a Vec<T> containing all expected members of type T within PokemonTCG API V2.
+    pub fn all<T: Resource + DeserializeOwned + Clone + Debug>(&self) -> Result<Vec<T>, Error> {
+        self.fetch_all::<T>(HashMap::new())
+    }
+
+    /// Fetches the single page of T matching args, along with the paging metadata from the API.
+    pub fn search_page<T: Resource + DeserializeOwned + Clone + Debug>(&self, args: HashMap<String, String>) -> Result<Page<T>, Error> {
+        self.fetch_page::<T>(&args).map(Page::from)
+    }
+
+    /// Amount of T matching the search query q, every T when q is empty.
+    pub fn count<T: Resource + DeserializeOwned + Clone + Debug>(&self, q: &str) -> Result<u64, Error> {
+        let page = self.fetch_page::<T>(&count_args(q))?;
+        Ok(page.total_count.map(u64::from).unwrap_or(page.data.len() as u64))
+    }
+
+    pub fn find_card(&self, id: &CardId) -> Result<Option<Card>, Error> {
+        self.find::<Card>(id)
+    }
+
+    pub fn find_set(&self, id: &SetId) -> Result<Option<Set>, Error> {
+        self.find::<Set>(id)
+    }
+
+    /// Every card matching the search query `q`, either a raw string or a [`crate::Q`].
+    pub fn search_cards(&self, q: impl Into<String>) -> Result<Vec<Card>, Error> {
+        self.query::<Card>(HashMap::from([(String::from("q"), q.into())]))
+    }
+
+    /// Every set matching the search query `q`, either a raw string or a [`crate::Q`].
+    pub fn search_sets(&self, q: impl Into<String>) -> Result<Vec<Set>, Error> {
+        self.query::<Set>(HashMap::from([(String::from("q"), q.into())]))
+    }
+}
+
+/// Blocking counterpart of `read_body` of the async Client.
+fn read_body(resp: reqwest::blocking::Response, url: &str, limit: u64) -> Result<String, Error> {
+    if resp.content_length().is_some_and(|len| len > limit) {
+        return Err(Error::ResponseTooLarge { limit, url: url.into() });
+    }
+
+    let mut resp = resp;
+    let mut body = LimitedBody { bytes: Vec::new(), limit, exceeded: false };
+    if let Err(e) = resp.copy_to(&mut body) {
+        return Err(match body.exceeded {
+            true => Error::ResponseTooLarge { limit, url: url.into() },
+            false => transport_error(url, e),
+        });
+    }
+    Ok(String::from_utf8_lossy(&body.bytes).into_owned())
+}
+
+/// Body being read, refusing the bytes past limit.
+struct LimitedBody {
+    bytes: Vec<u8>,
+    limit: u64,
+    exceeded: bool,
+}
+
+impl Write for LimitedBody {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if (self.bytes.len() + buf.len()) as u64 > self.limit {
+            self.exceeded = true;
+            return Err(std::io::Error::other("response body over the limit"));
+        }
+        self.bytes.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wiremock::{ Mock, MockServer, ResponseTemplate };
+    use wiremock::matchers::{ method, path, query_param };
+
+    use super::*;
+
+    #[test]
+    fn test_blocking_find_and_paging() {
+        let cwd = std::env::current_dir().unwrap();
+        let card = std::fs::read_to_string(cwd.join("src/mock/xy1-1.json")).unwrap();
+        let sets = std::fs::read_to_string(cwd.join("src/mock/sets.json")).unwrap();
+
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let server = rt.block_on(async {
+            let server = MockServer::start().await;
+            Mock::given(method("GET"))
+                .and(path("/cards/xy1-1"))
+                .respond_with(ResponseTemplate::new(200).set_body_raw(card, "application/json"))
+                .mount(&server)
+                .await;
+            Mock::given(method("GET"))
+                .and(path("/sets"))
+                .respond_with(ResponseTemplate::new(200).set_body_raw(sets, "application/json"))
+                .mount(&server)
+                .await;
+            server
+        });
+
+        let api = ClientBuilder::default().base_url(server.uri()).build_blocking().unwrap();
+
+        let card = api.find_card(&"xy1-1".parse().unwrap()).unwrap().unwrap();
+        assert!(card.name == Some("Venusaur-EX".into()));
+        assert!(api.find_card(&"xy1-999".parse().unwrap()).unwrap().is_none());
+        assert!(api.all::<Set>().unwrap().len() == 2);
+    }
+
+    #[test]
+    fn test_blocking_response_limits() {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let server = rt.block_on(async {
+            let server = MockServer::start().await;
+            Mock::given(method("GET"))
+                .and(path("/cards/xy1-1"))
+                .respond_with(ResponseTemplate::new(200).set_body_raw(include_str!("mock/xy1-1.json"), "application/json"))
+                .mount(&server)
+                .await;
+            Mock::given(method("GET"))
+                .and(path("/sets"))
+                .respond_with(ResponseTemplate::new(200).set_body_raw("<html>Proxy error</html>", "text/html"))
+                .mount(&server)
+                .await;
+            server
+        });
+
+        let api = ClientBuilder::default().base_url(server.uri()).max_response_size(100).build_blocking().unwrap();
+        assert!(matches!(api.find_card(&"xy1-1".parse().unwrap()), Err(Error::ResponseTooLarge { limit: 100, .. })));
+        assert!(matches!(api.all::<Set>(), Err(Error::NotJson { body_snippet, .. }) if body_snippet == "<html>Proxy error</html>"));
+    }
+
+    #[test]
+    fn test_blocking_circuit_breaker() {
+        let page = |page: u32| serde_json::json!({ "data": [{ "id": format!("c-{page}") }], "page": page, "pageSize": 1, "count": 1, "totalCount": 3 });
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let server = rt.block_on(async {
+            let server = MockServer::start().await;
+            Mock::given(method("GET"))
+                .and(path("/cards"))
+                .and(query_param("page", "1"))
+                .respond_with(ResponseTemplate::new(200).set_body_json(page(1)))
+                .mount(&server)
+                .await;
+            Mock::given(method("GET"))
+                .and(path("/cards"))
+                .respond_with(ResponseTemplate::new(503))
+                .mount(&server)
+                .await;
+            server
+        });
+
+        let api = ClientBuilder::default()
+            .base_url(server.uri())
+            .retry_policy(RetryPolicy::exponential(10, std::time::Duration::from_millis(1)))
+            .circuit_breaker(2)
+            .build_blocking()
+            .unwrap();
+        assert!(matches!(api.all::<Card>(), Err(Error::CircuitOpen { failures: 2, pages_fetched: 1 })));
+        assert!(rt.block_on(server.received_requests()).unwrap().len() == 3);
+    }
+}
@@ -0,0 +1,189 @@
+use std::collections::HashMap;
+
+use rand::{ Rng, RngExt };
+
+use crate::{ Client, Q };
+use crate::models::errors::Error;
+use crate::models::models::{ Card, Rarity };
+
+/// Cards of a pack drawn the same way, e.g. the 6 commons, with each card's rarity picked among
+/// `pulls` with probability proportional to its weight.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Slot {
+    pub count: usize,
+    pub pulls: Vec<(Rarity, f64)>,
+}
+
+/// Composition of a booster pack, as slots drawn in order.
+///
+/// ```ignore
+/// // a 5 card pack: 4 commons and a rare that is holo a third of the time
+/// let template = PackTemplate::new()
+///     .slot(4, &[("Common", 1.0)])
+///     .slot(1, &[("Rare", 2.0), ("Rare Holo", 1.0)]);
+/// let pack = api.open_booster_with("base1", &template, &mut rand::rng()).await?;
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PackTemplate {
+    pub slots: Vec<Slot>,
+}
+
+impl PackTemplate {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends a slot of count cards of the given rarities and weights.
+    pub fn slot(mut self, count: usize, pulls: &[(&str, f64)]) -> Self {
+        let pulls = pulls.iter().map(|(rarity, weight)| (Rarity((*rarity).into()), *weight)).collect();
+        self.slots.push(Slot { count, pulls });
+        self
+    }
+
+    /// 6 commons, 3 uncommons and 1 rare or better, with the rarer pulls of every era from the
+    /// plain "Rare" to the "Hyper Rare" of Scarlet & Violet. Rarities the set doesn't have are
+    /// left out, which spreads their weight over the others.
+    pub fn standard() -> Self {
+        Self::new()
+            .slot(6, &[("Common", 1.0)])
+            .slot(3, &[("Uncommon", 1.0)])
+            .slot(1, &[
+                ("Rare", 60.0),
+                ("Rare Holo", 25.0),
+                ("Double Rare", 15.0),
+                ("Rare Holo EX", 8.0),
+                ("Rare Holo GX", 8.0),
+                ("Rare Holo V", 8.0),
+                ("Rare Holo VMAX", 4.0),
+                ("Rare Ultra", 4.0),
+                ("Ultra Rare", 4.0),
+                ("Illustration Rare", 4.0),
+                ("Rare Rainbow", 1.0),
+                ("Rare Secret", 1.0),
+                ("Special Illustration Rare", 1.0),
+                ("Hyper Rare", 0.5),
+            ])
+    }
+}
+
+impl Client {
+    /// Simulated booster of the set with id set_id, following `PackTemplate::standard`.
+    pub async fn open_booster(&self, set_id: &str, rng: &mut impl Rng) -> Result<Vec<Card>, Error> {
+        self.open_booster_with(set_id, &PackTemplate::standard(), rng).await
+    }
+
+    /// Simulated booster of the set with id set_id, for sets whose packs don't follow the
+    /// standard composition. The set's cards are fetched once, a pack never holds the same card
+    /// twice.
+    ///
+    /// # Errors
+    /// `InvalidArgument` when the set runs out of cards of a slot's rarities.
+    pub async fn open_booster_with(&self, set_id: &str, template: &PackTemplate, rng: &mut impl Rng) -> Result<Vec<Card>, Error> {
+        let cards = self.search_cards(Q::field("set.id").eq(set_id)).await?;
+        draw(cards, template, rng)
+    }
+}
+
+fn draw(cards: Vec<Card>, template: &PackTemplate, rng: &mut impl Rng) -> Result<Vec<Card>, Error> {
+    let mut by_rarity = HashMap::<String, Vec<Card>>::new();
+    for card in cards {
+        if let Some(rarity) = &card.rarity {
+            by_rarity.entry(rarity.clone()).or_default().push(card);
+        }
+    }
+
+    let mut pack = Vec::new();
+    for slot in &template.slots {
+        for _ in 0..slot.count {
+            let available: Vec<&(Rarity, f64)> = slot.pulls
+                .iter()
+                .filter(|(rarity, weight)| *weight > 0.0 && by_rarity.get(&rarity.0).is_some_and(|pool| !pool.is_empty()))
+                .collect();
+            let total: f64 = available.iter().map(|(_, weight)| weight).sum();
+            if available.is_empty() {
+                let rarities: Vec<&str> = slot.pulls.iter().map(|(rarity, _)| rarity.0.as_str()).collect();
+                return Err(Error::InvalidArgument { arg: format!("not enough cards of rarity {}", rarities.join(", ")) });
+            }
+
+            let mut roll = rng.random_range(0.0..total);
+            let mut pick = available[available.len() - 1];
+            for pull in &available {
+                if roll < pull.1 {
+                    pick = pull;
+                    break;
+                }
+                roll -= pull.1;
+            }
+
+            let pool = by_rarity.get_mut(&pick.0.0).expect("available rarities have a pool");
+            let index = rng.random_range(0..pool.len());
+            pack.push(pool.swap_remove(index));
+        }
+    }
+
+    Ok(pack)
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+    use wiremock::{ Mock, ResponseTemplate };
+    use wiremock::matchers::{ method, path, query_param };
+
+    use crate::testing::MockApi;
+
+    use super::*;
+
+    fn set_cards() -> Vec<Card> {
+        let rarities = [("Common", 30), ("Uncommon", 20), ("Rare", 5), ("Rare Holo", 3), ("Rare Secret", 1)];
+        rarities.iter()
+            .flat_map(|(rarity, amount)| (0..*amount).map(move |i| (rarity, i)))
+            .enumerate()
+            .map(|(number, (rarity, _))| serde_json::from_value(serde_json::json!({
+                "id": format!("base1-{}", number + 1),
+                "rarity": rarity,
+            })).unwrap())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_open_booster_follows_standard_template() {
+        let body = serde_json::json!({ "data": set_cards(), "page": 1, "pageSize": 250, "count": 59, "totalCount": 59 });
+        let mock = MockApi::start().await;
+        Mock::given(method("GET"))
+            .and(path("/cards"))
+            .and(query_param("q", "set.id:base1"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(body))
+            .with_priority(1)
+            .mount(mock.server())
+            .await;
+
+        let pack = mock.client().open_booster("base1", &mut StdRng::seed_from_u64(7)).await.unwrap();
+        let rarities: Vec<&str> = pack.iter().filter_map(|c| c.rarity.as_deref()).collect();
+
+        assert!(pack.len() == 10);
+        assert!(rarities[..6].iter().all(|r| *r == "Common"));
+        assert!(rarities[6..9].iter().all(|r| *r == "Uncommon"));
+        assert!(["Rare", "Rare Holo", "Rare Secret"].contains(&rarities[9]));
+
+        let mut ids: Vec<&str> = pack.iter().filter_map(|c| c.id.as_deref()).collect();
+        ids.sort();
+        ids.dedup();
+        assert!(ids.len() == 10);
+    }
+
+    #[test]
+    fn test_draw_with_custom_template() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let only_secrets = PackTemplate::new().slot(1, &[("Rare Secret", 1.0), ("Rare Holo", 0.0)]);
+        assert!(draw(set_cards(), &only_secrets, &mut rng).unwrap()[0].rarity == Some("Rare Secret".into()));
+
+        let too_many = PackTemplate::new().slot(4, &[("Rare Holo", 1.0)]);
+        assert!(matches!(draw(set_cards(), &too_many, &mut rng), Err(Error::InvalidArgument { .. })));
+
+        let unknown = PackTemplate::new().slot(1, &[("Promo", 1.0)]);
+        assert!(matches!(draw(set_cards(), &unknown, &mut rng), Err(Error::InvalidArgument { .. })));
+        assert!(draw(set_cards(), &PackTemplate::new(), &mut rng).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,569 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::{ Client, OrderBy, Progress, API_KEY_ENV_VAR, BASE_URL_ENV_VAR, CACHE_DIR_ENV_VAR, DEFAULT_MAX_RESPONSE_SIZE, MAX_PAGE_SIZE, PAGE_SIZE_ENV_VAR, POKEMON_TCG_URL, TIMEOUT_ENV_VAR };
+use crate::cache::{ DiskCache, MemoryCache, OfflineMode, DEFAULT_CACHE_MAX_ENTRIES };
+use crate::cassette::{ Cassette, RecordMode };
+use crate::hooks::{ Hooks, RequestParts, ResponseParts };
+use crate::models::errors::Error;
+use crate::progress::ProgressCallback;
+use crate::retry::RetryPolicy;
+use crate::set_codes::SetCodes;
+use crate::throttle::RateLimiter;
+
+/// Env variables read by `ClientBuilder::from_env_config`.
+const ENV_CONFIG_VARS: [&str; 5] = [API_KEY_ENV_VAR, BASE_URL_ENV_VAR, TIMEOUT_ENV_VAR, PAGE_SIZE_ENV_VAR, CACHE_DIR_ENV_VAR];
+
+/// User-Agent sent when none is configured.
+pub const DEFAULT_USER_AGENT: &str = "Mozilla/5.0";
+
+/// Configures a [`Client`] before creating it; obtained through `Client::builder()`.
+///
+/// ```ignore
+/// let api = Client::builder()
+///                  .api_key(&key)
+///                  .base_url("http://localhost:8080/v2")
+///                  .timeout(Duration::from_secs(30))
+///                  .user_agent("my-deck-builder/1.0")
+///                  .max_retries(3)
+///                  .build()?;
+/// ```
+///
+/// On wasm32 the browser handles timeouts, compression, proxies and certificates, so these
+/// settings don't apply there, and neither the on-disk cache nor cassettes are available.
+#[derive(Debug)]
+#[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+pub struct ClientBuilder {
+    key: Option<String>,
+    base_url: String,
+    timeout: Option<Duration>,
+    /// None until `user_agent` is called, `DEFAULT_USER_AGENT` being sent then.
+    user_agent: Option<String>,
+    retry: RetryPolicy,
+    cache_ttl: Option<Duration>,
+    cache_max_entries: usize,
+    cache_dir: Option<PathBuf>,
+    offline: OfflineMode,
+    max_concurrent_pages: usize,
+    compression: bool,
+    progress: Option<ProgressCallback>,
+    allow_unknown_args: bool,
+    page_size: u32,
+    hooks: Hooks,
+    cassette: Option<(PathBuf, RecordMode)>,
+    proxy: Option<(String, Option<(String, String)>)>,
+    root_certificates: Vec<Vec<u8>>,
+    http_client: Option<reqwest::Client>,
+    rate_limit: Option<f64>,
+    set_codes: SetCodes,
+    max_response_size: u64,
+    bulk_order_by: OrderBy,
+}
+
+impl Default for ClientBuilder {
+    fn default() -> Self {
+        Self {
+            key: None,
+            base_url: POKEMON_TCG_URL.into(),
+            timeout: None,
+            user_agent: None,
+            retry: RetryPolicy::default(),
+            cache_ttl: None,
+            cache_max_entries: DEFAULT_CACHE_MAX_ENTRIES,
+            cache_dir: None,
+            offline: OfflineMode::default(),
+            max_concurrent_pages: 1,
+            compression: true,
+            progress: None,
+            allow_unknown_args: false,
+            page_size: MAX_PAGE_SIZE,
+            hooks: Hooks::default(),
+            cassette: None,
+            proxy: None,
+            root_certificates: Vec::new(),
+            http_client: None,
+            rate_limit: None,
+            set_codes: SetCodes::default(),
+            max_response_size: DEFAULT_MAX_RESPONSE_SIZE,
+            bulk_order_by: OrderBy::asc(OrderBy::ID),
+        }
+    }
+}
+
+impl ClientBuilder {
+    /// Builder configured by the env variables that are set, for deployments configured from
+    /// their environment; the builder methods called afterwards override them.
+    ///
+    /// | Variable | Setting |
+    /// |---|---|
+    /// | `POKEMON_TCG_API_KEY` | `api_key` |
+    /// | `POKEMON_TCG_BASE_URL` | `base_url` |
+    /// | `POKEMON_TCG_TIMEOUT_SECS` | `timeout`, in whole seconds |
+    /// | `POKEMON_TCG_PAGE_SIZE` | `page_size`, 1 to 250 |
+    /// | `POKEMON_TCG_CACHE_DIR` | `cache_dir` |
+    ///
+    /// Empty variables count as unset. Fails with `Error::InvalidArgument` naming the variable
+    /// whose value can't be used, e.g. a timeout that isn't a number.
+    ///
+    /// ```ignore
+    /// let api = ClientBuilder::from_env_config()?.user_agent("my-deck-builder/1.0").build()?;
+    /// ```
+    pub fn from_env_config() -> Result<Self, Error> {
+        for name in ENV_CONFIG_VARS {
+            if let Err(std::env::VarError::NotUnicode(_)) = std::env::var(name) {
+                return Err(Error::InvalidArgument { arg: format!("{name} is not valid unicode") });
+            }
+        }
+        Self::from_lookup(|name| std::env::var(name).ok())
+    }
+
+    /// Same as `from_env_config`, reading the variables through get, None for unset ones.
+    fn from_lookup(get: impl Fn(&str) -> Option<String>) -> Result<Self, Error> {
+        let env_var = |name: &str| get(name).filter(|value| !value.trim().is_empty());
+
+        let mut builder = Self::default();
+        if let Some(key) = env_var(API_KEY_ENV_VAR) {
+            builder = builder.api_key(&key);
+        }
+        if let Some(url) = env_var(BASE_URL_ENV_VAR) {
+            reqwest::Url::parse(&url).map_err(|e| invalid_env_var(BASE_URL_ENV_VAR, &url, &e.to_string()))?;
+            builder = builder.base_url(url);
+        }
+        if let Some(secs) = env_var(TIMEOUT_ENV_VAR) {
+            let timeout = secs.trim().parse().map_err(|_| invalid_env_var(TIMEOUT_ENV_VAR, &secs, "expected a whole number of seconds"))?;
+            builder = builder.timeout(Duration::from_secs(timeout));
+        }
+        if let Some(size) = env_var(PAGE_SIZE_ENV_VAR) {
+            let page_size = size.trim().parse().ok().filter(|s| (1..=MAX_PAGE_SIZE).contains(s));
+            let page_size = page_size.ok_or_else(|| invalid_env_var(PAGE_SIZE_ENV_VAR, &size, &format!("expected a number from 1 to {MAX_PAGE_SIZE}")))?;
+            builder = builder.page_size(page_size);
+        }
+        if let Some(dir) = env_var(CACHE_DIR_ENV_VAR) {
+            builder.cache_dir = Some(dir.into());
+        }
+        Ok(builder)
+    }
+
+    /// API Key sent along every request; without one the API applies its lower keyless rate limit.
+    pub fn api_key(mut self, key: &str) -> Self {
+        self.key = Some(key.into());
+        self
+    }
+
+    /// Root of the API routes, `https://api.pokemontcg.io/v2` by default; useful to point the
+    /// Client at a mock server.
+    pub fn base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').into();
+        self
+    }
+
+    /// Total time allowed for each request, from connecting until the body has been read.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// User-Agent header identifying your application, `DEFAULT_USER_AGENT` unless set.
+    pub fn user_agent(mut self, user_agent: &str) -> Self {
+        self.user_agent = Some(user_agent.into());
+        self
+    }
+
+    /// Replaces the whole retry policy, e.g. `RetryPolicy::exponential(3, Duration::from_millis(250))`.
+    pub fn retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Maximum amount of times a rejected request is sent again before giving up.
+    pub fn max_retries(mut self, max_retries: u32) -> Self {
+        self.retry.max_retries = max_retries;
+        self
+    }
+
+    /// Longest wait before a retry, capping both the backoff and the wait a 429 response asks for,
+    /// e.g. through an `X-RateLimit-Reset` hours away; 60 seconds by default.
+    pub fn max_retry_delay(mut self, max_delay: Duration) -> Self {
+        self.retry.max_delay = max_delay;
+        self
+    }
+
+    /// Most retries across every request of a single `all`, `query` or `bulk` fetch, e.g. 20, on
+    /// top of the `max_retries` of each request; once spent, the next rejected request fails the
+    /// fetch. Unlimited unless set.
+    pub fn retry_budget(mut self, retries: u32) -> Self {
+        self.retry.retry_budget = Some(retries);
+        self
+    }
+
+    /// Stops an `all`, `query` or `bulk` fetch with `Error::CircuitOpen` once consecutive_failures
+    /// attempts in a row failed with a connection error, timeout or 5xx response, instead of
+    /// sending further requests to an API that is down. Disabled unless set.
+    pub fn circuit_breaker(mut self, consecutive_failures: u32) -> Self {
+        self.retry.circuit_breaker = Some(consecutive_failures.max(1));
+        self
+    }
+
+    /// Whether 429 Too Many Requests responses are retried after the wait requested by the API.
+    pub fn retry_on_rate_limit(mut self, enabled: bool) -> Self {
+        self.retry.retry_on_rate_limit = enabled;
+        self
+    }
+
+    /// Keeps successful responses in memory for ttl, so repeated lookups don't reach the API.
+    /// Disabled unless set.
+    pub fn cache_ttl(mut self, ttl: Duration) -> Self {
+        self.cache_ttl = Some(ttl);
+        self
+    }
+
+    /// Maximum amount of responses kept in the cache, the oldest ones are dropped first.
+    pub fn cache_max_entries(mut self, max_entries: usize) -> Self {
+        self.cache_max_entries = max_entries;
+        self
+    }
+
+    /// Directory where successful responses are written as JSON files, read back according to
+    /// the offline mode. Entries don't expire; delete the directory to refresh them.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn cache_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.cache_dir = Some(dir.into());
+        self
+    }
+
+    /// How the on-disk cache is used, `OfflineMode::Prefer` by default.
+    pub fn offline_mode(mut self, offline: OfflineMode) -> Self {
+        self.offline = offline;
+        self
+    }
+
+    /// Amount of pages requested at the same time by `all` and `query` once the first page told
+    /// how many there are; 1, the default, fetches them one after the other. Each request is still
+    /// retried on its own when rate limited.
+    pub fn max_concurrent_pages(mut self, max_concurrent_pages: usize) -> Self {
+        self.max_concurrent_pages = max_concurrent_pages.max(1);
+        self
+    }
+
+    /// Whether to ask for gzip, brotli or deflate compressed responses, which shrinks full card
+    /// pages several times over; on by default. Responses are decompressed before being decoded.
+    pub fn compression(mut self, enabled: bool) -> Self {
+        self.compression = enabled;
+        self
+    }
+
+    /// Called after every page received by `all`, `query`, `stream` and the helpers built on
+    /// them, e.g. to render a progress bar; the clones of the Client share it.
+    ///
+    /// ```ignore
+    /// let api = Client::builder()
+    ///                  .on_progress(|p| println!("{}/{:?} cards", p.fetched, p.total_count))
+    ///                  .build()?;
+    /// ```
+    pub fn on_progress(mut self, callback: impl FnMut(Progress) + Send + 'static) -> Self {
+        self.progress = Some(ProgressCallback::new(callback));
+        self
+    }
+
+    /// Sends query parameters this crate doesn't know as they are instead of failing with
+    /// `Error::InvalidArgument`, e.g. for parameters added to the API since.
+    pub fn allow_unknown_args(mut self, allow: bool) -> Self {
+        self.allow_unknown_args = allow;
+        self
+    }
+
+    /// Amount of items per page requested by `all`, `query` and `stream` when the call doesn't
+    /// set `pageSize` itself; 250, the most the API allows, by default. Clamped to 1..=250.
+    pub fn page_size(mut self, page_size: u32) -> Self {
+        self.page_size = page_size.clamp(1, MAX_PAGE_SIZE);
+        self
+    }
+
+    /// Order in which `all`, `query`, `bulk` and `BulkDownload` request the pages of cards and
+    /// sets when the arguments don't set `orderBy`; `OrderBy::asc(OrderBy::ID)` by default, so
+    /// that pages don't shift between requests. `OrderBy::default()` sends none.
+    pub fn bulk_order_by(mut self, order: OrderBy) -> Self {
+        self.bulk_order_by = order;
+        self
+    }
+
+    /// Most bytes of a response body read before failing with `Error::ResponseTooLarge`, so that
+    /// a huge page from a misconfigured proxy can't exhaust the memory; 20 MB by default.
+    pub fn max_response_size(mut self, bytes: u64) -> Self {
+        self.max_response_size = bytes;
+        self
+    }
+
+    /// Called before every request the Client sends, each page of a bulk fetch and each retry
+    /// included, e.g. to add the header a proxy expects. Hooks run in the order they were added.
+    ///
+    /// ```ignore
+    /// let api = Client::builder()
+    ///                  .with_request_hook(|req| { req.headers.insert("x-proxy-token", token.clone()); })
+    ///                  .build()?;
+    /// ```
+    pub fn with_request_hook(mut self, hook: impl Fn(&mut RequestParts) + Send + Sync + 'static) -> Self {
+        self.hooks.add_request(hook);
+        self
+    }
+
+    /// Called with every response the Client receives, retried ones included, before its body
+    /// is read; e.g. to record timings or the rate limit headers.
+    pub fn with_response_hook(mut self, hook: impl Fn(&ResponseParts) + Send + Sync + 'static) -> Self {
+        self.hooks.add_response(hook);
+        self
+    }
+
+    /// Records the API responses to the JSON file at path and replays them, matched on URL and
+    /// query parameters, as mode says; e.g. tests recording their requests once, then running
+    /// without network. A request missing from a replayed cassette fails with
+    /// `Error::CassetteMiss`. Only the JSON routes go through it, images don't.
+    ///
+    /// # Errors
+    /// `build` fails with `FailedOpeningFile` when a cassette to replay doesn't exist and with
+    /// `FailedParsingFile` when it is corrupt.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn with_cassette(mut self, path: impl Into<PathBuf>, mode: RecordMode) -> Self {
+        self.cassette = Some((path.into(), mode));
+        self
+    }
+
+    /// Sends every request through the proxy at url, e.g. `http://proxy.corp:3128`, logging in
+    /// with the username and password of credentials when given. Without it, reqwest follows the
+    /// `HTTP_PROXY` and `HTTPS_PROXY` env variables.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn proxy(mut self, url: &str, credentials: Option<(&str, &str)>) -> Self {
+        self.proxy = Some((url.into(), credentials.map(|(user, password)| (user.into(), password.into()))));
+        self
+    }
+
+    /// Trusts the PEM encoded certificate on top of the system ones, e.g. the private CA of a
+    /// proxy inspecting TLS traffic. Can be called once per certificate.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn add_root_certificate(mut self, cert_pem: &[u8]) -> Self {
+        self.root_certificates.push(cert_pem.to_vec());
+        self
+    }
+
+    /// Keeps the Client and its clones under requests_per_second, e.g. `1000.0 / 3600.0` for the
+    /// API's documented 1000 requests an hour, by delaying requests instead of waiting for 429
+    /// responses. Bursts of up to one second worth of requests go through at once. Each retry also
+    /// waits its turn, without that wait counting against the retry policy. Disabled unless set;
+    /// a rate that isn't positive disables it as well.
+    pub fn rate_limit(mut self, requests_per_second: f64) -> Self {
+        self.rate_limit = Some(requests_per_second).filter(|rate| *rate > 0.0 && rate.is_finite());
+        self
+    }
+
+    /// Mapping of set codes used by `Client::card_by_set_code`, e.g. the one of another Client so
+    /// both fetch the sets a single time. Each Client builds a mapping of its own unless set.
+    pub fn set_codes(mut self, codes: SetCodes) -> Self {
+        self.set_codes = codes;
+        self
+    }
+
+    /// Sends the requests through http instead of a client of its own, e.g. one shared with the
+    /// rest of an application for its connection pool limits or middleware. The API Key is still
+    /// added to every request, and so is the User-Agent when `user_agent` was called; otherwise
+    /// http sends its own. The timeout, compression, proxy and root certificates are those
+    /// configured on http; the builder's ones are ignored. Only `build`
+    /// uses it, `build_blocking` creates its own client.
+    pub fn with_http_client(mut self, http: reqwest::Client) -> Self {
+        self.http_client = Some(http);
+        self
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn reqwest_proxy(&self) -> Result<Option<reqwest::Proxy>, Error> {
+        let Some((url, credentials)) = &self.proxy else {
+            return Ok(None);
+        };
+
+        let proxy = reqwest::Proxy::all(url).map_err(|e| Error::FailedBuildingClient { reason: e.to_string() })?;
+        Ok(Some(match credentials {
+            Some((user, password)) => proxy.basic_auth(user, password),
+            None => proxy,
+        }))
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn reqwest_certificates(&self) -> Result<Vec<reqwest::Certificate>, Error> {
+        self.root_certificates.iter()
+                              .map(|pem| reqwest::Certificate::from_pem(pem).map_err(|e| Error::FailedBuildingClient { reason: e.to_string() }))
+                              .collect()
+    }
+
+    /// Creates the Client; fails when the underlying HTTP client cannot be initialized, the proxy
+    /// URL is invalid or a root certificate isn't PEM.
+    pub fn build(mut self) -> Result<Client, Error> {
+        let (client, user_agent) = match self.http_client.take() {
+            Some(http) => {
+                let user_agent = self.user_agent
+                                     .as_deref()
+                                     .map(|ua| ua.parse().map_err(|_| Error::FailedBuildingClient { reason: format!("invalid User-Agent {ua:?}") }))
+                                     .transpose()?;
+                (http, user_agent)
+            },
+            None => (self.reqwest_client()?, None),
+        };
+
+        Ok(Client {
+            client,
+            user_agent,
+            key: self.key,
+            base_url: self.base_url,
+            retry: self.retry,
+            cache: self.cache_ttl.map(|ttl| Arc::new(MemoryCache::new(ttl, self.cache_max_entries))),
+            disk_cache: self.cache_dir.map(DiskCache::new),
+            offline: self.offline,
+            bypass_cache: false,
+            max_concurrent_pages: self.max_concurrent_pages,
+            timeout: None,
+            progress: self.progress,
+            allow_unknown_args: self.allow_unknown_args,
+            page_size: self.page_size,
+            hooks: self.hooks,
+            cassette: self.cassette.map(|(path, mode)| Cassette::open(path, mode)).transpose()?,
+            rate_limiter: self.rate_limit.map(RateLimiter::new),
+            set_codes: self.set_codes,
+            max_response_size: self.max_response_size,
+            operation: None,
+            bulk_order_by: self.bulk_order_by,
+        })
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn reqwest_client(&self) -> Result<reqwest::Client, Error> {
+        let mut http = reqwest::Client::builder().user_agent(self.user_agent.as_deref().unwrap_or(DEFAULT_USER_AGENT))
+                                                 .gzip(self.compression)
+                                                 .brotli(self.compression)
+                                                 .deflate(self.compression);
+        if let Some(timeout) = self.timeout {
+            http = http.timeout(timeout);
+        }
+        if let Some(proxy) = self.reqwest_proxy()? {
+            http = http.proxy(proxy);
+        }
+        for cert in self.reqwest_certificates()? {
+            http = http.add_root_certificate(cert);
+        }
+
+        http.build().map_err(|e| Error::FailedBuildingClient { reason: e.to_string() })
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn reqwest_client(&self) -> Result<reqwest::Client, Error> {
+        reqwest::Client::builder().user_agent(self.user_agent.as_deref().unwrap_or(DEFAULT_USER_AGENT))
+                                  .build()
+                                  .map_err(|e| Error::FailedBuildingClient { reason: e.to_string() })
+    }
+
+    /// Creates a [`crate::blocking::Client`] with the API Key, base URL, timeout, User-Agent,
+    /// compression, proxy, root certificates and retry policy configured; the cache settings don't
+    /// apply to it.
+    #[cfg(feature = "blocking")]
+    pub fn build_blocking(self) -> Result<crate::blocking::Client, Error> {
+        let mut http = reqwest::blocking::Client::builder().user_agent(self.user_agent.as_deref().unwrap_or(DEFAULT_USER_AGENT))
+                                                           .gzip(self.compression)
+                                                           .brotli(self.compression)
+                                                           .deflate(self.compression);
+        if let Some(timeout) = self.timeout {
+            http = http.timeout(timeout);
+        }
+        if let Some(proxy) = self.reqwest_proxy()? {
+            http = http.proxy(proxy);
+        }
+        for cert in self.reqwest_certificates()? {
+            http = http.add_root_certificate(cert);
+        }
+
+        let client = http.build().map_err(|e| Error::FailedBuildingClient { reason: e.to_string() })?;
+
+        Ok(crate::blocking::Client {
+            client,
+            key: self.key,
+            base_url: self.base_url,
+            retry: self.retry,
+            allow_unknown_args: self.allow_unknown_args,
+            page_size: self.page_size,
+            hooks: self.hooks,
+            cassette: self.cassette.map(|(path, mode)| Cassette::open(path, mode)).transpose()?,
+            rate_limiter: self.rate_limit.map(RateLimiter::new),
+            max_response_size: self.max_response_size,
+            operation: None,
+            bulk_order_by: self.bulk_order_by,
+        })
+    }
+}
+
+fn invalid_env_var(name: &str, value: &str, reason: &str) -> Error {
+    Error::InvalidArgument { arg: format!("{name}={value:?}: {reason}") }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::models::models::{ Card, Set };
+    use crate::testing::MockApi;
+
+    use super::*;
+
+    fn from_vars(vars: &[(&str, &str)]) -> Result<ClientBuilder, Error> {
+        let vars: HashMap<String, String> = vars.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+        ClientBuilder::from_lookup(|name| vars.get(name).cloned())
+    }
+
+    fn invalid(vars: &[(&str, &str)], name: &str) -> bool {
+        matches!(from_vars(vars), Err(Error::InvalidArgument { arg }) if arg.starts_with(name))
+    }
+
+    #[tokio::test]
+    async fn test_builder_from_env_lookup() {
+        let mock = MockApi::start().await;
+        let dir = std::env::temp_dir().join(format!("pokemon_tcg_sdk_env_config_{}", std::process::id()));
+        let vars = [
+            (API_KEY_ENV_VAR, "env-key"), (BASE_URL_ENV_VAR, &mock.uri()), (TIMEOUT_ENV_VAR, " 5"),
+            (PAGE_SIZE_ENV_VAR, "2"), (CACHE_DIR_ENV_VAR, dir.to_str().unwrap()),
+        ];
+
+        let builder = from_vars(&vars).unwrap();
+        assert!(builder.key.as_deref() == Some("env-key") && builder.base_url == mock.uri());
+        assert!(builder.timeout == Some(Duration::from_secs(5)) && builder.page_size == 2 && builder.cache_dir == Some(dir.clone()));
+        assert!(from_vars(&vars).unwrap().page_size(1).timeout(Duration::from_secs(1)).page_size == 1);
+
+        builder.build().unwrap().all::<Card>().await.unwrap();
+        let requests = mock.server().received_requests().await.unwrap();
+        assert!(requests.len() == 3 && requests.iter().all(|r| r.headers.get("X-Api-Key").is_some_and(|k| k == "env-key")));
+        assert!(requests.iter().all(|r| r.url.query_pairs().any(|(k, v)| k == "pageSize" && v == "2")));
+        assert!(std::fs::read_dir(&dir).unwrap().count() == 3);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let unset = from_vars(&[(PAGE_SIZE_ENV_VAR, " "), (BASE_URL_ENV_VAR, "")]).unwrap();
+        assert!(unset.page_size == MAX_PAGE_SIZE && unset.base_url == POKEMON_TCG_URL && unset.key.is_none() && unset.cache_dir.is_none());
+        let _: Vec<Set> = from_vars(&[(BASE_URL_ENV_VAR, &mock.uri())]).unwrap().build().unwrap().all().await.unwrap();
+        let sets = mock.server().received_requests().await.unwrap().into_iter().filter(|r| r.url.path() == "/sets").collect::<Vec<_>>();
+        assert!(sets.len() == 1 && sets[0].url.query_pairs().any(|(k, v)| k == "pageSize" && v == "250"));
+    }
+
+    #[test]
+    fn test_builder_from_env_lookup_names_invalid_variables() {
+        assert!(invalid(&[(TIMEOUT_ENV_VAR, "soon")], TIMEOUT_ENV_VAR));
+        assert!(invalid(&[(TIMEOUT_ENV_VAR, "-1")], TIMEOUT_ENV_VAR));
+        assert!(invalid(&[(PAGE_SIZE_ENV_VAR, "251")], PAGE_SIZE_ENV_VAR));
+        assert!(invalid(&[(PAGE_SIZE_ENV_VAR, "0")], PAGE_SIZE_ENV_VAR));
+        assert!(invalid(&[(BASE_URL_ENV_VAR, "localhost without a scheme")], BASE_URL_ENV_VAR));
+        assert!(from_vars(&[]).is_ok());
+    }
+
+    #[test]
+    fn test_builder_from_env_config() {
+        // only the timeout is set, no other test reads it from the environment
+        std::env::set_var(TIMEOUT_ENV_VAR, "soon");
+        let failed = ClientBuilder::from_env_config();
+        std::env::remove_var(TIMEOUT_ENV_VAR);
+
+        assert!(matches!(failed, Err(Error::InvalidArgument { arg }) if arg.starts_with(TIMEOUT_ENV_VAR)));
+    }
+}
@@ -0,0 +1,422 @@
+use std::collections::{ BTreeMap, HashMap, HashSet };
+use std::marker::PhantomData;
+
+use serde::{ de::DeserializeOwned, Deserialize, Serialize };
+
+use crate::{ with_bulk_order, with_page_size, Client, Resource };
+use crate::models::errors::Error;
+
+/// What a bulk download does when a page fails after others were fetched.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Strictness {
+    /// Fails with the error of the page, like `all` and `query`.
+    #[default]
+    Strict,
+    /// Returns the items of the pages before the failed one, flagged as incomplete.
+    Lenient,
+}
+
+/// Items of a bulk download, with whether every page could be fetched.
+#[derive(Debug)]
+pub struct BulkResult<T> {
+    pub items: Vec<T>,
+    /// False when a page failed; items then only holds the pages before it.
+    pub complete: bool,
+    pub failed_page: Option<u32>,
+    pub error: Option<Error>,
+    pub meta: BulkMeta,
+}
+
+/// How the pages of a bulk download were requested and put together.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BulkMeta {
+    /// `orderBy` sent with every page, the stable order of `ClientBuilder::bulk_order_by` unless
+    /// the arguments had one.
+    pub order_by: Option<String>,
+    /// Items a page held again after an earlier one, left out of items. Not 0 when the results
+    /// shifted during the download, which may then also have missed as many items: running it
+    /// again gets them.
+    pub duplicates_dropped: usize,
+}
+
+impl<T> BulkResult<T> {
+    pub(crate) fn complete(items: Vec<T>) -> Self {
+        Self { items, complete: true, failed_page: None, error: None, meta: BulkMeta::default() }
+    }
+
+    pub(crate) fn partial(items: Vec<T>, failed_page: u32, error: Error) -> Self {
+        Self { items, complete: false, failed_page: Some(failed_page), error: Some(error), meta: BulkMeta::default() }
+    }
+
+    /// Drops the items whose id an earlier item has, counting them in `meta`, for the order_by
+    /// the pages were requested in. Items without an id are kept.
+    pub(crate) fn dedup(mut self, order_by: Option<String>, id: fn(&T) -> Option<&str>) -> Self {
+        let before = self.items.len();
+        let mut seen = HashSet::new();
+        self.items.retain(|item| id(item).is_none_or(|id| seen.insert(id.to_owned())));
+
+        self.meta = BulkMeta { order_by, duplicates_dropped: before - self.items.len() };
+        self
+    }
+
+    /// The items when complete, the error of the failed page otherwise.
+    pub fn into_result(self) -> Result<Vec<T>, Error> {
+        match self.error {
+            Some(error) => Err(error),
+            None => Ok(self.items),
+        }
+    }
+}
+
+impl Client {
+    /// Every T matching args like `query`, except that a page failing midway is reported
+    /// according to strictness instead of always failing the download.
+    ///
+    /// ```ignore
+    /// let cards = api.bulk::<Card>(HashMap::new(), Strictness::Lenient).await?;
+    /// if !cards.complete {
+    ///     eprintln!("stopped at page {:?}: {:?}", cards.failed_page, cards.error);
+    /// }
+    /// ```
+    pub async fn bulk<T: Resource + DeserializeOwned>(&self, args: HashMap<String, String>, strictness: Strictness) -> Result<BulkResult<T>, Error> {
+        self.fetch_bulk(args, strictness).await
+    }
+}
+
+/// Progress of a [`BulkDownload`], for the caller to persist between runs.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Checkpoint {
+    /// Arguments of the download, `pageSize` included so that resuming requests the same pages.
+    pub query: BTreeMap<String, String>,
+    /// Last page whose items were handed out, 0 before the first one.
+    pub last_page: u32,
+    /// `totalCount` reported by the API when the checkpoint was last updated.
+    pub total_count: Option<u32>,
+    /// True once the last page was handed out.
+    pub complete: bool,
+}
+
+/// `totalCount` of the search differing between the checkpoint and the API, so the pages already
+/// downloaded may have shifted: items may be missed or seen twice.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TotalCountChange {
+    pub checkpoint: u32,
+    pub now: u32,
+}
+
+/// Outcome of `BulkDownload::run`.
+#[derive(Debug)]
+pub struct BulkDownloadReport<T> {
+    /// Items of the pages fetched by this run, incomplete when a page failed.
+    pub result: BulkResult<T>,
+    /// Where the next run should resume from.
+    pub checkpoint: Checkpoint,
+    pub total_count_changed: Option<TotalCountChange>,
+}
+
+/// Download of every T matching a query, one page at a time, that can be resumed from a
+/// [`Checkpoint`] after being interrupted instead of starting over from page 1.
+///
+/// ```ignore
+/// let checkpoint: Checkpoint = serde_json::from_str(&std::fs::read_to_string("cards.checkpoint")?)?;
+/// let mut download = BulkDownload::<Card>::new(&api).resume_from(checkpoint);
+///
+/// while let Some(cards) = download.next_page().await? {
+///     save(cards);
+///     std::fs::write("cards.checkpoint", serde_json::to_string(download.checkpoint())?)?;
+/// }
+/// ```
+#[derive(Debug)]
+pub struct BulkDownload<'a, T> {
+    client: &'a Client,
+    checkpoint: Checkpoint,
+    total_count_changed: Option<TotalCountChange>,
+    _resource: PhantomData<T>,
+}
+
+impl<'a, T: Resource + DeserializeOwned> BulkDownload<'a, T> {
+    /// Download of every T, from page 1.
+    pub fn new(client: &'a Client) -> Self {
+        let mut download = Self { client, checkpoint: Checkpoint::default(), total_count_changed: None, _resource: PhantomData };
+        download.checkpoint.query = download.paged(HashMap::new());
+        download
+    }
+
+    /// Downloads the T matching args instead, from their `page` argument when given.
+    pub fn query(mut self, args: HashMap<String, String>) -> Self {
+        let mut args = self.paged(args);
+        let first_page = args.remove("page").and_then(|p| p.parse().ok()).unwrap_or(1_u32);
+        self.checkpoint = Checkpoint { query: args, last_page: first_page.saturating_sub(1), ..Checkpoint::default() };
+        self
+    }
+
+    /// Continues the download the checkpoint was taken from, with the page after its last one.
+    pub fn resume_from(mut self, checkpoint: Checkpoint) -> Self {
+        self.checkpoint = checkpoint;
+        self
+    }
+
+    fn paged(&self, args: HashMap<String, String>) -> BTreeMap<String, String> {
+        let args = with_page_size(args, self.client.page_size);
+        with_bulk_order(args, &T::path(), &self.client.bulk_order_by).into_iter().collect()
+    }
+
+    /// Progress so far, to persist after each page.
+    pub fn checkpoint(&self) -> &Checkpoint {
+        &self.checkpoint
+    }
+
+    /// Set once a page reported a `totalCount` other than the one of the checkpoint.
+    pub fn total_count_changed(&self) -> Option<TotalCountChange> {
+        self.total_count_changed
+    }
+
+    /// Items of the next page, None once the download is complete. The checkpoint only moves past
+    /// a page once it was fetched, so a failed page is requested again by the next call.
+    pub async fn next_page(&mut self) -> Result<Option<Vec<T>>, Error> {
+        if self.checkpoint.complete {
+            return Ok(None);
+        }
+
+        let page = self.checkpoint.last_page + 1;
+        let mut query: HashMap<String, String> = self.checkpoint.query.clone().into_iter().collect();
+        query.insert(String::from("page"), format!("{page}"));
+        let (container, _) = self.client.fetch_page_with_meta::<T>(&T::path(), &query).await?;
+
+        if let (Some(checkpoint), Some(now)) = (self.checkpoint.total_count, container.total_count) {
+            if checkpoint != now && self.total_count_changed.is_none() {
+                self.total_count_changed = Some(TotalCountChange { checkpoint, now });
+            }
+        }
+        self.checkpoint.last_page = page;
+        self.checkpoint.total_count = container.total_count.or(self.checkpoint.total_count);
+        self.checkpoint.complete = container.is_last_page();
+
+        Ok(Some(container.data))
+    }
+
+    /// Fetches the remaining pages, stopping at the first one that fails with the items fetched
+    /// until then.
+    pub async fn run(mut self) -> BulkDownloadReport<T> {
+        let mut items = Vec::new();
+
+        let result = loop {
+            match self.next_page().await {
+                Ok(Some(page)) => items.extend(page),
+                Ok(None) => break BulkResult::complete(items),
+                Err(e) => break BulkResult::partial(items, self.checkpoint.last_page + 1, e),
+            }
+        };
+        let result = result.dedup(self.checkpoint.query.get("orderBy").cloned(), T::bulk_id);
+
+        BulkDownloadReport { result, checkpoint: self.checkpoint, total_count_changed: self.total_count_changed }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use wiremock::{ Mock, MockServer, ResponseTemplate };
+    use wiremock::matchers::{ method, path, query_param };
+
+    use crate::RetryPolicy;
+    use crate::models::models::Card;
+
+    use super::*;
+
+    /// Five pages of two cards, the third one answering 500.
+    async fn failing_server() -> MockServer {
+        paged_server(10, Some(3)).await
+    }
+
+    /// Pages of two of the total_count cards, failed_page answering 500.
+    async fn paged_server(total_count: u32, failed_page: Option<u32>) -> MockServer {
+        let server = MockServer::start().await;
+        for page in 1..=total_count.div_ceil(2) {
+            let response = match page {
+                _ if Some(page) == failed_page => ResponseTemplate::new(500),
+                _ => ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                    "data": [{ "id": format!("c-{}", page * 2 - 1) }, { "id": format!("c-{}", page * 2) }],
+                    "page": page, "pageSize": 2, "count": 2, "totalCount": total_count,
+                })),
+            };
+            Mock::given(method("GET"))
+                .and(path("/cards"))
+                .and(query_param("page", format!("{page}")))
+                .respond_with(response)
+                .mount(&server)
+                .await;
+        }
+        server
+    }
+
+    fn ids(cards: &[Card]) -> Vec<&str> {
+        cards.iter().filter_map(|c| c.id.as_deref()).collect()
+    }
+
+    #[tokio::test]
+    async fn test_bulk_strict_fails_on_middle_page() {
+        let server = failing_server().await;
+        let api = Client::builder().base_url(server.uri()).build().unwrap();
+
+        let strict = api.bulk::<Card>(HashMap::new(), Strictness::Strict).await;
+        assert!(matches!(strict, Err(Error::ServerError { status: 500, .. })));
+        assert!(matches!(api.all::<Card>().await, Err(Error::ServerError { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_bulk_lenient_returns_pages_before_failure() {
+        let server = failing_server().await;
+
+        for concurrency in [1, 4] {
+            let api = Client::builder().base_url(server.uri()).max_concurrent_pages(concurrency).build().unwrap();
+            let partial = api.bulk::<Card>(HashMap::new(), Strictness::Lenient).await.unwrap();
+
+            assert!(!partial.complete && partial.failed_page == Some(3));
+            assert!(ids(&partial.items) == vec!["c-1", "c-2", "c-3", "c-4"]);
+            assert!(matches!(partial.into_result(), Err(Error::ServerError { status: 500, .. })));
+        }
+
+        let api = Client::builder().base_url(server.uri()).build().unwrap();
+        let args = HashMap::from([("page".into(), "4".into())]);
+        let rest = api.bulk::<Card>(args, Strictness::Lenient).await.unwrap();
+        assert!(rest.complete && rest.failed_page.is_none() && rest.error.is_none());
+        assert!(ids(&rest.items) == vec!["c-7", "c-8", "c-9", "c-10"]);
+    }
+
+    #[tokio::test]
+    async fn test_bulk_download_resumes_from_checkpoint() {
+        let server = failing_server().await;
+        let api = Client::builder().base_url(server.uri()).page_size(2).build().unwrap();
+
+        let interrupted = BulkDownload::<Card>::new(&api).run().await;
+        assert!(ids(&interrupted.result.items) == vec!["c-1", "c-2", "c-3", "c-4"]);
+        assert!(interrupted.result.failed_page == Some(3) && interrupted.total_count_changed.is_none());
+        let saved = serde_json::to_string(&interrupted.checkpoint).unwrap();
+
+        // two cards were released meanwhile
+        let server = paged_server(12, None).await;
+        let api = Client::builder().base_url(server.uri()).page_size(2).build().unwrap();
+        let checkpoint: Checkpoint = serde_json::from_str(&saved).unwrap();
+        assert!(checkpoint.last_page == 2 && checkpoint.total_count == Some(10) && checkpoint.query["pageSize"] == "2");
+
+        let resumed = BulkDownload::<Card>::new(&api).resume_from(checkpoint).run().await;
+        assert!(resumed.result.complete);
+        assert!(ids(&resumed.result.items) == vec!["c-5", "c-6", "c-7", "c-8", "c-9", "c-10", "c-11", "c-12"]);
+        assert!(resumed.total_count_changed == Some(TotalCountChange { checkpoint: 10, now: 12 }));
+        assert!(resumed.checkpoint.complete && resumed.checkpoint.last_page == 6);
+
+        let mut done = BulkDownload::<Card>::new(&api).resume_from(resumed.checkpoint);
+        assert!(done.next_page().await.unwrap().is_none());
+
+        let args = HashMap::from([("page".into(), "6".into())]);
+        let last = BulkDownload::<Card>::new(&api).query(args).run().await;
+        assert!(ids(&last.result.items) == vec!["c-11", "c-12"]);
+    }
+
+    /// Responds 500 to from_page and the pages after it of a paged_server, times times in a row
+    /// when set, for good otherwise.
+    async fn fail_pages(server: &MockServer, from_page: u32, last_page: u32, times: Option<u64>) {
+        for page in from_page..=last_page {
+            let mock = Mock::given(method("GET"))
+                .and(path("/cards"))
+                .and(query_param("page", format!("{page}")))
+                .respond_with(ResponseTemplate::new(500))
+                .with_priority(1);
+            match times {
+                Some(times) => mock.up_to_n_times(times).mount(server).await,
+                None => mock.mount(server).await,
+            }
+        }
+    }
+
+    async fn requests_from_page(server: &MockServer, from_page: u32) -> usize {
+        server.received_requests().await.unwrap().iter()
+              .filter(|r| r.url.query_pairs().any(|(k, v)| k == "page" && v.parse::<u32>().is_ok_and(|p| p >= from_page)))
+              .count()
+    }
+
+    #[tokio::test]
+    async fn test_circuit_breaker_stops_failing_bulk_fetch() {
+        // down for good from the third of five pages on
+        let server = paged_server(10, None).await;
+        fail_pages(&server, 3, 5, None).await;
+        let builder = || Client::builder()
+            .base_url(server.uri())
+            .retry_policy(RetryPolicy::exponential(10, Duration::from_millis(1)))
+            .circuit_breaker(3);
+
+        let api = builder().build().unwrap();
+        let partial = api.bulk::<Card>(HashMap::new(), Strictness::Lenient).await.unwrap();
+        assert!(ids(&partial.items) == vec!["c-1", "c-2", "c-3", "c-4"] && partial.failed_page == Some(3));
+        assert!(matches!(partial.error, Some(Error::CircuitOpen { failures: 3, pages_fetched: 2 })));
+        assert!(requests_from_page(&server, 3).await == 3);
+
+        // each fetch gets a breaker of its own
+        assert!(matches!(api.all::<Card>().await, Err(Error::CircuitOpen { failures: 3, pages_fetched: 2 })));
+        assert!(requests_from_page(&server, 3).await == 6);
+
+        // concurrent pages share it; the success of page 2 may interrupt the failures in a row once
+        let api = builder().max_concurrent_pages(4).build().unwrap();
+        assert!(matches!(api.all::<Card>().await, Err(Error::CircuitOpen { failures: 3, .. })));
+        assert!(requests_from_page(&server, 3).await <= 6 + 5);
+    }
+
+    #[tokio::test]
+    async fn test_retry_budget_spans_the_bulk_fetch() {
+        let retrying = || Client::builder().retry_policy(RetryPolicy::exponential(3, Duration::from_millis(1)));
+
+        // every page fails once before answering
+        let server = paged_server(10, None).await;
+        fail_pages(&server, 1, 5, Some(1)).await;
+        let api = retrying().base_url(server.uri()).retry_budget(3).build().unwrap();
+        assert!(matches!(api.all::<Card>().await, Err(Error::ServerError { status: 500, .. })));
+        assert!(server.received_requests().await.unwrap().len() == 7);
+
+        // failures that aren't in a row don't trip the breaker
+        let server = paged_server(10, None).await;
+        fail_pages(&server, 1, 5, Some(1)).await;
+        let api = retrying().base_url(server.uri()).retry_budget(5).circuit_breaker(2).build().unwrap();
+        assert!(api.all::<Card>().await.unwrap().len() == 10);
+    }
+
+    #[tokio::test]
+    async fn test_bulk_orders_by_id_and_drops_shifted_duplicates() {
+        // a card added before c-2 while paging pushed c-2 onto the second page again
+        let server = MockServer::start().await;
+        for (page, ids) in [(1, ["c-1", "c-2"]), (2, ["c-2", "c-3"]), (3, ["c-4", "c-5"])] {
+            Mock::given(method("GET"))
+                .and(path("/cards"))
+                .and(query_param("page", format!("{page}")))
+                .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                    "data": ids.map(|id| serde_json::json!({ "id": id })), "page": page, "pageSize": 2, "count": 2, "totalCount": 6,
+                })))
+                .mount(&server)
+                .await;
+        }
+        let order_bys = || async {
+            let requests = server.received_requests().await.unwrap();
+            requests.iter().map(|r| r.url.query_pairs().find(|(k, _)| k == "orderBy").map(|(_, v)| v.into_owned())).collect::<Vec<_>>()
+        };
+
+        let api = Client::builder().base_url(server.uri()).build().unwrap();
+        let result = api.bulk::<Card>(HashMap::new(), Strictness::Strict).await.unwrap();
+        assert!(ids(&result.items) == vec!["c-1", "c-2", "c-3", "c-4", "c-5"]);
+        assert!(result.meta == BulkMeta { order_by: Some("id".into()), duplicates_dropped: 1 });
+        assert!(order_bys().await == vec![Some(String::from("id")); 3]);
+        assert!(api.all::<Card>().await.unwrap().len() == 5);
+
+        let args = HashMap::from([(String::from("orderBy"), String::from("-name"))]);
+        assert!(api.bulk::<Card>(args, Strictness::Strict).await.unwrap().meta.order_by == Some("-name".into()));
+        assert!(order_bys().await[6..] == vec![Some(String::from("-name")); 3]);
+
+        let unordered = Client::builder().base_url(server.uri()).bulk_order_by(crate::OrderBy::default()).build().unwrap();
+        let result = unordered.bulk::<Card>(HashMap::new(), Strictness::Strict).await.unwrap();
+        assert!(result.meta.order_by.is_none() && result.meta.duplicates_dropped == 1);
+        assert!(order_bys().await[9..] == vec![None; 3]);
+
+        let report = BulkDownload::<Card>::new(&api).run().await;
+        assert!(report.checkpoint.query.get("orderBy") == Some(&String::from("id")) && report.result.meta.duplicates_dropped == 1);
+    }
+}
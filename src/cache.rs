@@ -0,0 +1,225 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::runtime::Instant;
+
+/// Amount of responses kept when no limit is configured.
+pub const DEFAULT_CACHE_MAX_ENTRIES: usize = 1000;
+
+/// Raw JSON bodies of successful responses, keyed by request URL and query, so any T can be
+/// deserialized again from them.
+#[derive(Debug)]
+pub(crate) struct MemoryCache {
+    ttl: Duration,
+    max_entries: usize,
+    entries: Mutex<HashMap<String, CacheEntry>>,
+}
+
+#[derive(Debug)]
+struct CacheEntry {
+    body: String,
+    etag: Option<String>,
+    stored_at: Instant,
+}
+
+impl MemoryCache {
+    pub(crate) fn new(ttl: Duration, max_entries: usize) -> Self {
+        Self {
+            ttl,
+            max_entries,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Body stored under key, unless it is older than the TTL. Expired entries with an ETag are
+    /// kept around for `revalidation`.
+    pub(crate) fn get(&self, key: &str) -> Option<String> {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+
+        match entries.get(key) {
+            Some(entry) if entry.stored_at.elapsed() < self.ttl => Some(entry.body.clone()),
+            Some(entry) => {
+                if entry.etag.is_none() {
+                    entries.remove(key);
+                }
+                None
+            },
+            None => None,
+        }
+    }
+
+    /// ETag and body stored under key whatever their age, to send a conditional request with.
+    pub(crate) fn revalidation(&self, key: &str) -> Option<(String, String)> {
+        let entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        let entry = entries.get(key)?;
+        Some((entry.etag.clone()?, entry.body.clone()))
+    }
+
+    /// Stores body under key, making room by dropping expired entries and then the oldest ones.
+    pub(crate) fn insert(&self, key: String, body: String, etag: Option<String>) {
+        if self.max_entries == 0 {
+            return;
+        }
+
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+
+        if !entries.contains_key(&key) && entries.len() >= self.max_entries {
+            entries.retain(|_, entry| entry.stored_at.elapsed() < self.ttl);
+
+            while entries.len() >= self.max_entries {
+                let Some(oldest) = entries.iter().min_by_key(|(_, entry)| entry.stored_at).map(|(k, _)| k.clone()) else { break };
+                entries.remove(&oldest);
+            }
+        }
+
+        entries.insert(key, CacheEntry { body, etag, stored_at: Instant::now() });
+    }
+
+    pub(crate) fn clear(&self) {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner()).clear();
+    }
+}
+
+/// Whether the Client answers requests from its on-disk cache instead of the network.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OfflineMode {
+    /// Cached responses are used, the network only on a cache miss.
+    #[default]
+    Prefer,
+    /// No request is ever sent; a cache miss fails with `Error::OfflineCacheMiss`.
+    Only,
+    /// Every request goes to the network; responses are still written to disk.
+    Never,
+}
+
+/// Directory holding the raw JSON body of every successful response, one `<hash>.json` file per
+/// request, and its ETag, if any, in `<hash>.etag`. File names are the 64 bit FNV-1a hash of the
+/// cache key, so a warmed directory stays valid across builds and can be shipped along an
+/// application.
+#[derive(Clone, Debug)]
+pub(crate) struct DiskCache {
+    dir: PathBuf,
+}
+
+impl DiskCache {
+    pub(crate) fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{:016x}.json", fnv1a(key.as_bytes())))
+    }
+
+    pub(crate) fn get(&self, key: &str) -> Option<String> {
+        fs::read_to_string(self.path(key)).ok()
+    }
+
+    /// ETag and body stored under key, to send a conditional request with.
+    pub(crate) fn revalidation(&self, key: &str) -> Option<(String, String)> {
+        let etag = fs::read_to_string(self.path(key).with_extension("etag")).ok()?;
+        Some((etag, self.get(key)?))
+    }
+
+    /// Stores body and etag under key. Writing is best effort: a cache that cannot be written
+    /// must not fail the request that was already answered.
+    pub(crate) fn insert(&self, key: &str, body: &str, etag: Option<&str>) {
+        let path = self.path(key);
+        let etag_path = path.with_extension("etag");
+        let tmp = path.with_extension("json.tmp");
+
+        let written = fs::create_dir_all(&self.dir)
+            .and_then(|_| fs::write(&tmp, body))
+            .and_then(|_| fs::rename(&tmp, &path));
+
+        if written.is_err() {
+            let _ = fs::remove_file(&tmp);
+        }
+
+        let _ = match etag {
+            Some(etag) if written.is_ok() => fs::write(etag_path, etag),
+            _ => fs::remove_file(etag_path),
+        };
+    }
+}
+
+/// Outcome of `Client::fetch_if_modified`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Conditional {
+    /// The API answered 304 Not Modified, the copy held for the ETag is still current.
+    NotModified,
+    /// New body, along its ETag to send next time.
+    Modified {
+        body: String,
+        etag: Option<String>,
+    },
+}
+
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, b| (hash ^ *b as u64).wrapping_mul(0x100000001b3))
+}
+
+/// Cache key of a GET request: the URL it is sent to, with its normalized query parameters.
+pub(crate) fn key(url: &str, query: &HashMap<String, String>) -> String {
+    match reqwest::Url::parse_with_params(url, crate::normalized_query(query)) {
+        Ok(url) => url.to_string(),
+        Err(_) => url.into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cache_expires_entries() {
+        let cache = MemoryCache::new(Duration::from_millis(20), 10);
+        cache.insert("a".into(), "{}".into(), None);
+
+        assert!(cache.get("a") == Some("{}".into()));
+        std::thread::sleep(Duration::from_millis(30));
+        assert!(cache.get("a").is_none());
+    }
+
+    #[test]
+    fn test_cache_evicts_oldest_when_full() {
+        let cache = MemoryCache::new(Duration::from_secs(60), 2);
+        cache.insert("a".into(), "1".into(), None);
+        cache.insert("b".into(), "2".into(), None);
+        cache.insert("c".into(), "3".into(), None);
+
+        assert!(cache.get("a").is_none());
+        assert!(cache.get("b") == Some("2".into()));
+        assert!(cache.get("c") == Some("3".into()));
+    }
+
+    #[test]
+    fn test_disk_cache_round_trip() {
+        let dir = std::env::temp_dir().join(format!("pokemon_tcg_sdk_disk_cache_{}", std::process::id()));
+        let cache = DiskCache::new(dir.clone());
+        let key = key("https://api.pokemontcg.io/v2/sets", &HashMap::from([(String::from("page"), String::from("1"))]));
+
+        assert!(cache.get(&key).is_none());
+        cache.insert(&key, "{\"data\": []}", Some("\"v1\""));
+        assert!(cache.get(&key) == Some("{\"data\": []}".into()));
+        assert!(cache.revalidation(&key) == Some(("\"v1\"".into(), "{\"data\": []}".into())));
+        cache.insert(&key, "{\"data\": [1]}", None);
+        assert!(cache.revalidation(&key).is_none());
+        assert!(fnv1a(b"a") == 0xaf63dc4c8601ec8c);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_cache_keeps_expired_entries_with_etag() {
+        let cache = MemoryCache::new(Duration::ZERO, 10);
+        cache.insert("a".into(), "1".into(), Some("\"v1\"".into()));
+        cache.insert("b".into(), "2".into(), None);
+
+        assert!(cache.get("a").is_none() && cache.get("b").is_none());
+        assert!(cache.revalidation("a") == Some(("\"v1\"".into(), "1".into())));
+        assert!(cache.revalidation("b").is_none());
+    }
+}
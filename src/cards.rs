@@ -0,0 +1,85 @@
+//! Set operations over query results keyed on their `id`, e.g. merging the cards of a search by
+//! type with those of a search by name. Results keep the order items were first seen in, and
+//! items without an id are never equal to any other.
+
+use std::collections::HashSet;
+
+use crate::models::models::{ Card, Set };
+
+/// Models with an `id` field.
+pub trait HasId {
+    fn id(&self) -> Option<&str>;
+}
+
+impl HasId for Card {
+    fn id(&self) -> Option<&str> {
+        self.id.as_deref()
+    }
+}
+
+impl HasId for Set {
+    fn id(&self) -> Option<&str> {
+        self.id.as_deref()
+    }
+}
+
+/// Items with the first occurrence of each id only.
+pub fn dedup_by_id<T: HasId>(items: Vec<T>) -> Vec<T> {
+    let mut seen = HashSet::new();
+    items.into_iter()
+         .filter(|item| item.id().is_none_or(|id| seen.insert(id.to_owned())))
+         .collect()
+}
+
+/// Items of a, then those of b whose id isn't in a.
+pub fn union<T: HasId>(a: Vec<T>, b: Vec<T>) -> Vec<T> {
+    dedup_by_id(a.into_iter().chain(b).collect())
+}
+
+/// Items of a whose id is also in b.
+pub fn intersection<T: HasId>(a: Vec<T>, b: &[T]) -> Vec<T> {
+    let ids = ids(b);
+    dedup_by_id(a.into_iter().filter(|item| item.id().is_some_and(|id| ids.contains(id))).collect())
+}
+
+/// Items of a whose id isn't in b.
+pub fn difference<T: HasId>(a: Vec<T>, b: &[T]) -> Vec<T> {
+    let ids = ids(b);
+    dedup_by_id(a.into_iter().filter(|item| !item.id().is_some_and(|id| ids.contains(id))).collect())
+}
+
+fn ids<T: HasId>(items: &[T]) -> HashSet<&str> {
+    items.iter().filter_map(HasId::id).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cards(ids: &[&str]) -> Vec<Card> {
+        ids.iter().map(|id| serde_json::from_value(serde_json::json!({ "id": id })).unwrap()).collect()
+    }
+
+    fn ids_of<T: HasId>(items: &[T]) -> Vec<&str> {
+        items.iter().map(|item| item.id().unwrap_or("?")).collect()
+    }
+
+    #[test]
+    fn test_set_operations_keep_first_seen_order() {
+        let by_type = cards(&["c", "a", "b", "a"]);
+        let by_name = cards(&["d", "b", "c"]);
+
+        assert!(ids_of(&dedup_by_id(by_type.clone())) == vec!["c", "a", "b"]);
+        assert!(ids_of(&union(by_type.clone(), by_name.clone())) == vec!["c", "a", "b", "d"]);
+        assert!(ids_of(&intersection(by_type.clone(), &by_name)) == vec!["c", "b"]);
+        assert!(ids_of(&difference(by_type, &by_name)) == vec!["a"]);
+
+        let mut without_id = cards(&["a"]);
+        without_id.extend(cards(&["a"]).into_iter().map(|mut c| { c.id = None; c }));
+        assert!(ids_of(&union(without_id.clone(), without_id.clone())) == vec!["a", "?", "?"]);
+        assert!(ids_of(&intersection(without_id.clone(), &without_id)) == vec!["a"]);
+
+        let sets: Vec<Set> = serde_json::from_str(r#"[{"id": "xy1"}, {"id": "sv4"}, {"id": "xy1"}]"#).unwrap();
+        assert!(ids_of(&dedup_by_id(sets)) == vec!["xy1", "sv4"]);
+    }
+}
@@ -0,0 +1,143 @@
+use std::collections::{ BTreeMap, HashMap };
+use std::path::PathBuf;
+use std::sync::{ Arc, Mutex };
+
+use serde::{ Deserialize, Serialize };
+
+use crate::cache;
+use crate::models::errors::{ Error, ResponseMeta };
+
+/// How the cassette given to `ClientBuilder::with_cassette` is used.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RecordMode {
+    /// Records every request while the cassette file doesn't exist, replays it afterwards.
+    #[default]
+    Once,
+    /// Only answers from the cassette, a request it doesn't hold fails with `Error::CassetteMiss`.
+    Replay,
+    /// Always reaches the API, recording the responses over those already in the cassette.
+    Record,
+}
+
+/// Request sent and the response it got.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+struct Interaction {
+    method: String,
+    url: String,
+    query: BTreeMap<String, String>,
+    status: u16,
+    body: String,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct CassetteFile {
+    interactions: Vec<Interaction>,
+}
+
+/// Responses recorded to or replayed from a JSON file, matched on URL and query parameters,
+/// shared by the clones of a Client.
+#[derive(Clone, Debug)]
+pub(crate) struct Cassette {
+    path: PathBuf,
+    replaying: bool,
+    interactions: Arc<Mutex<Vec<Interaction>>>,
+}
+
+impl Cassette {
+    pub(crate) fn open(path: PathBuf, mode: RecordMode) -> Result<Self, Error> {
+        let replaying = match mode {
+            RecordMode::Once => path.exists(),
+            RecordMode::Replay => true,
+            RecordMode::Record => false,
+        };
+
+        let file = if path.exists() {
+            let text = std::fs::read_to_string(&path).map_err(|_| Error::FailedOpeningFile)?;
+            serde_json::from_str(&text).map_err(Error::parsing_file)?
+        } else if replaying {
+            return Err(Error::FailedOpeningFile);
+        } else {
+            CassetteFile::default()
+        };
+
+        Ok(Self { path, replaying, interactions: Arc::new(Mutex::new(file.interactions)) })
+    }
+
+    /// Recorded response to a GET of url with query, None while recording.
+    pub(crate) fn replay(&self, url: &str, query: &HashMap<String, String>) -> Result<Option<(ResponseMeta, String)>, Error> {
+        if !self.replaying {
+            return Ok(None);
+        }
+
+        let key = cache::key(url, query);
+        let query = sorted(query);
+        let interactions = self.interactions.lock().unwrap_or_else(|e| e.into_inner());
+        match interactions.iter().find(|i| i.url == url && i.query == query) {
+            Some(found) => Ok(Some((ResponseMeta { status: found.status, ..ResponseMeta::cached(&key) }, found.body.clone()))),
+            None => Err(Error::CassetteMiss { request: format!("GET {key}"), cassette: self.path.display().to_string() }),
+        }
+    }
+
+    /// Stores the response to a GET of url with query, replacing an earlier recording of the same
+    /// request, and writes the cassette.
+    pub(crate) fn record(&self, url: &str, query: &HashMap<String, String>, status: u16, body: &str) -> Result<(), Error> {
+        if self.replaying {
+            return Ok(());
+        }
+
+        let recorded = Interaction { method: "GET".into(), url: url.into(), query: sorted(query), status, body: body.into() };
+        let mut interactions = self.interactions.lock().unwrap_or_else(|e| e.into_inner());
+        match interactions.iter_mut().find(|i| i.url == recorded.url && i.query == recorded.query) {
+            Some(earlier) => *earlier = recorded,
+            None => interactions.push(recorded),
+        }
+
+        let file = CassetteFile { interactions: interactions.clone() };
+        let json = serde_json::to_string_pretty(&file).map_err(|e| Error::FailedWritingFile { reason: e.to_string() })?;
+        std::fs::write(&self.path, json).map_err(|e| Error::FailedWritingFile { reason: e.to_string() })
+    }
+}
+
+fn sorted(query: &HashMap<String, String>) -> BTreeMap<String, String> {
+    crate::normalized_query(query).into_iter().map(|(k, v)| (k.into(), v.into())).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Client;
+    use crate::models::models::{ Card, Set };
+    use crate::testing::MockApi;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_cassette_records_then_replays() {
+        let path = std::env::temp_dir().join(format!("pokemon_tcg_sdk_cassette_{}.json", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let mock = MockApi::start().await;
+        let recording = mock.builder().with_cassette(&path, RecordMode::Once).build().unwrap();
+        assert!(recording.all::<Card>().await.unwrap().len() == 4);
+        assert!(recording.find_card(&"xy1-999".parse().unwrap()).await.unwrap().is_none());
+        let sent = mock.server().received_requests().await.unwrap().len();
+        drop(mock);
+
+        // the mock server is gone, every response comes from the cassette
+        let replaying = Client::builder().base_url(recording.base_url.clone()).with_cassette(&path, RecordMode::Once).build().unwrap();
+        let cards = replaying.all::<Card>().await.unwrap();
+        let missing = replaying.find_card(&"xy1-999".parse().unwrap()).await.unwrap();
+        let miss = replaying.all::<Set>().await.unwrap_err();
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(sent == 4 && cards.len() == 4 && missing.is_none());
+        match miss {
+            Error::CassetteMiss { request, cassette } => {
+                assert!(request.starts_with("GET ") && request.contains("/sets?orderBy=id&page=1&pageSize=250"));
+                assert!(cassette == path.display().to_string());
+            },
+            other => panic!("unexpected {other:?}"),
+        }
+
+        assert!(matches!(Client::builder().with_cassette(&path, RecordMode::Replay).build(), Err(Error::FailedOpeningFile)));
+    }
+}
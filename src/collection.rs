@@ -0,0 +1,214 @@
+use std::collections::HashSet;
+use std::path::Path;
+
+use serde::{ Deserialize, Serialize };
+
+use crate::{ CardDatabase, Client, Q };
+use crate::models::errors::Error;
+use crate::models::models::{ Card, Price, PriceVariantKind };
+
+/// Grading of a physical card, from best to worst.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Condition {
+    Mint,
+    #[default]
+    NearMint,
+    LightlyPlayed,
+    ModeratelyPlayed,
+    HeavilyPlayed,
+    Damaged,
+}
+
+/// Copies of a card owned in the same printing variant and condition.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CollectionEntry {
+    pub card_id: String,
+    pub quantity: u32,
+    pub variant: PriceVariantKind,
+    pub condition: Condition,
+}
+
+/// Worth of a collection, see `Collection::total_value`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Valuation {
+    /// Sum of the TCGPlayer market prices of the priced entries, in US Dollars.
+    pub total: Price,
+    pub priced: Vec<CollectionEntry>,
+    /// Entries whose card or variant has no market price, left out of total.
+    pub unpriced: Vec<CollectionEntry>,
+}
+
+/// Cards someone owns, persisted as JSON.
+///
+/// ```ignore
+/// let mut collection = Collection::load("collection.json")?;
+/// collection.add("xy1-1", 2, PriceVariantKind::Holofoil, Condition::NearMint);
+///
+/// let value = collection.total_value_online(&api).await?;
+/// let missing = collection.missing_from_set(&api, "xy1").await?;
+/// collection.save("collection.json")?;
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Collection {
+    pub entries: Vec<CollectionEntry>,
+}
+
+impl Collection {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds quantity copies of the card, merged with the entry of the same card, variant and
+    /// condition if there is one.
+    pub fn add(&mut self, card_id: &str, quantity: u32, variant: PriceVariantKind, condition: Condition) {
+        match self.entry_mut(card_id, &variant, condition) {
+            Some(entry) => entry.quantity += quantity,
+            None => self.entries.push(CollectionEntry { card_id: card_id.into(), quantity, variant, condition }),
+        }
+    }
+
+    /// Removes up to quantity copies of the card, dropping the entry once none is left, and
+    /// returns how many were removed.
+    pub fn remove(&mut self, card_id: &str, quantity: u32, variant: &PriceVariantKind, condition: Condition) -> u32 {
+        let Some(entry) = self.entry_mut(card_id, variant, condition) else {
+            return 0;
+        };
+
+        let removed = quantity.min(entry.quantity);
+        entry.quantity -= removed;
+        self.entries.retain(|e| e.quantity > 0);
+
+        removed
+    }
+
+    fn entry_mut(&mut self, card_id: &str, variant: &PriceVariantKind, condition: Condition) -> Option<&mut CollectionEntry> {
+        self.entries.iter_mut().find(|e| e.card_id == card_id && e.variant == *variant && e.condition == condition)
+    }
+
+    /// Copies owned of the card, across variants and conditions.
+    pub fn quantity(&self, card_id: &str) -> u32 {
+        self.entries.iter().filter(|e| e.card_id == card_id).map(|e| e.quantity).sum()
+    }
+
+    /// Worth of the collection at the TCGPlayer market price of each entry's variant, taken from
+    /// the cards in db. Prices are those of near mint copies, the condition isn't accounted for.
+    pub fn total_value(&self, db: &CardDatabase) -> Valuation {
+        let mut valuation = Valuation { total: Price::default(), priced: Vec::new(), unpriced: Vec::new() };
+
+        for entry in &self.entries {
+            match db.get(&entry.card_id).and_then(|card| card.market_price(&entry.variant)) {
+                Some(price) => {
+                    valuation.total += price * Price::from(entry.quantity);
+                    valuation.priced.push(entry.clone());
+                },
+                None => valuation.unpriced.push(entry.clone()),
+            }
+        }
+
+        valuation
+    }
+
+    /// `total_value` with the cards of the collection fetched from the API.
+    pub async fn total_value_online(&self, client: &Client) -> Result<Valuation, Error> {
+        let ids: Vec<&str> = self.entries.iter().map(|e| e.card_id.as_str()).collect();
+        let db = CardDatabase::new(client.find_cards(&ids).await?);
+
+        Ok(self.total_value(&db))
+    }
+
+    /// Cards of the set with id set_id not owned in any variant, in collector number order.
+    pub async fn missing_from_set(&self, client: &Client, set_id: &str) -> Result<Vec<Card>, Error> {
+        let owned: HashSet<&str> = self.entries.iter().map(|e| e.card_id.as_str()).collect();
+
+        let mut missing: Vec<Card> = client.search_cards(Q::field("set.id").eq(set_id))
+                                           .await?
+                                           .into_iter()
+                                           .filter(|card| !card.id.as_deref().is_some_and(|id| owned.contains(id)))
+                                           .collect();
+        missing.sort_by_key(Card::number_sort_key);
+
+        Ok(missing)
+    }
+
+    /// Reads a collection written by `save`.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let text = std::fs::read_to_string(path).map_err(|_| Error::FailedOpeningFile)?;
+        serde_json::from_str(&text).map_err(Error::parsing_file)
+    }
+
+    /// Writes the collection as indented JSON.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), Error> {
+        let json = serde_json::to_string_pretty(self).map_err(|e| Error::FailedWritingFile { reason: e.to_string() })?;
+        std::fs::write(path, json).map_err(|e| Error::FailedWritingFile { reason: e.to_string() })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::MockApi;
+    use crate::tests::price;
+
+    use super::*;
+
+    fn priced_card(id: &str, number: &str, prices: serde_json::Value) -> Card {
+        serde_json::from_value(serde_json::json!({
+            "id": id, "number": number, "set": { "id": "xy1" }, "tcgplayer": { "prices": prices },
+        })).unwrap()
+    }
+
+    #[test]
+    fn test_collection_add_remove_and_value() {
+        let db = CardDatabase::new(vec![
+            priced_card("xy1-1", "1", serde_json::json!({ "holofoil": { "market": 3.5 }, "reverseHolofoil": { "market": 5.0 } })),
+            priced_card("xy1-2", "2", serde_json::json!({ "normal": { "market": 0.25 } })),
+            priced_card("xy1-3", "3", serde_json::json!({})),
+        ]);
+
+        let mut collection = Collection::new();
+        collection.add("xy1-1", 1, PriceVariantKind::Holofoil, Condition::NearMint);
+        collection.add("xy1-1", 2, PriceVariantKind::Holofoil, Condition::NearMint);
+        collection.add("xy1-1", 1, PriceVariantKind::ReverseHolofoil, Condition::LightlyPlayed);
+        collection.add("xy1-2", 4, PriceVariantKind::Normal, Condition::Mint);
+        collection.add("xy1-3", 1, PriceVariantKind::Normal, Condition::NearMint);
+        collection.add("xy1-9", 1, PriceVariantKind::Normal, Condition::NearMint);
+
+        assert!(collection.entries.len() == 5 && collection.quantity("xy1-1") == 4);
+        assert!(collection.remove("xy1-2", 3, &PriceVariantKind::Normal, Condition::Mint) == 3);
+        assert!(collection.remove("xy1-2", 3, &PriceVariantKind::Normal, Condition::Mint) == 1);
+        assert!(collection.remove("xy1-1", 1, &PriceVariantKind::Holofoil, Condition::Damaged) == 0);
+        assert!(collection.quantity("xy1-2") == 0 && collection.entries.len() == 4);
+
+        let value = collection.total_value(&db);
+        assert!(value.total == price("15.5"));
+        assert!(value.priced.len() == 2);
+        let unpriced: Vec<&str> = value.unpriced.iter().map(|e| e.card_id.as_str()).collect();
+        assert!(unpriced == vec!["xy1-3", "xy1-9"]);
+    }
+
+    #[test]
+    fn test_collection_save_and_load() {
+        let mut collection = Collection::new();
+        collection.add("sv4-12", 2, PriceVariantKind::Other("pokeball".into()), Condition::HeavilyPlayed);
+        let path = std::env::temp_dir().join(format!("pokemon_tcg_sdk_collection_{}.json", std::process::id()));
+
+        collection.save(&path).unwrap();
+        let loaded = Collection::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(loaded == collection);
+        assert!(matches!(Collection::load(&path), Err(Error::FailedOpeningFile)));
+    }
+
+    #[tokio::test]
+    async fn test_collection_missing_from_set() {
+        let mock = MockApi::start().await;
+        let mut collection = Collection::new();
+        collection.add("xy1-1", 1, PriceVariantKind::Holofoil, Condition::NearMint);
+        collection.add("xy1-3", 1, PriceVariantKind::Normal, Condition::Damaged);
+
+        let missing = collection.missing_from_set(&mock.client(), "xy1").await.unwrap();
+        let ids: Vec<&str> = missing.iter().filter_map(|c| c.id.as_deref()).collect();
+
+        assert!(ids == vec!["xy1-2", "xy1-4"]);
+    }
+}
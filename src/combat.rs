@@ -0,0 +1,174 @@
+//! Damage of an attack once the defender's weakness and resistance are applied.
+//!
+//! ```ignore
+//! let calc = combat::effective_damage(&charizard, &charizard.attacks.as_ref().unwrap()[0], &venusaur);
+//! println!("{} → {} → {}", calc.base, calc.after_weakness, calc.after_resistance);
+//! ```
+
+use crate::models::models::{ Attack, Card, EnergyType };
+
+/// Sign printed after the damage of an attack whose text changes it, e.g. "50+" or "20×".
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DamageSuffix {
+    /// "+": the text adds damage, e.g. for each Energy attached.
+    Plus,
+    /// "×": the text multiplies the damage, e.g. by the amount of heads flipped.
+    Times,
+    /// "-": the text reduces the damage, e.g. for each damage counter on the attacker.
+    Minus,
+}
+
+/// Weakness or resistance printed on a card.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Modifier {
+    /// "×2" on the weaknesses of recent cards.
+    Times(u32),
+    /// "+20" on the weaknesses of some older cards.
+    Plus(u32),
+    /// "-30" on resistances.
+    Minus(u32),
+}
+
+impl Modifier {
+    /// Parses the printed value, e.g. "×2", "x2", "+20" or "-30"; None for anything else.
+    pub fn parse(value: &str) -> Option<Modifier> {
+        let value = value.trim();
+        let mut chars = value.chars();
+        let sign = chars.next()?;
+        let amount: u32 = chars.as_str().trim().parse().ok()?;
+
+        match sign {
+            '×' | 'x' | 'X' | '*' => Some(Modifier::Times(amount)),
+            '+' => Some(Modifier::Plus(amount)),
+            '-' | '−' => Some(Modifier::Minus(amount)),
+            _ => None,
+        }
+    }
+
+    /// Modified damage; no damage stays none.
+    fn apply(self, damage: u32) -> u32 {
+        if damage == 0 {
+            return 0;
+        }
+
+        match self {
+            Modifier::Times(factor) => damage.saturating_mul(factor),
+            Modifier::Plus(amount) => damage.saturating_add(amount),
+            Modifier::Minus(amount) => damage.saturating_sub(amount),
+        }
+    }
+}
+
+/// Breakdown of `effective_damage`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct DamageCalc {
+    /// Printed damage, 0 for attacks only having an effect.
+    pub base: u32,
+    /// Sign after the printed damage; the calculation starts from the printed number, the text
+    /// deciding the rest isn't read.
+    pub suffix: Option<DamageSuffix>,
+    pub after_weakness: u32,
+    /// Damage dealt, never below 0.
+    pub after_resistance: u32,
+}
+
+/// Damage of attack, used by attacker, to defender: the printed damage with each weakness of
+/// defender to one of the attacker's types applied, then each such resistance. Attacks without
+/// damage deal none, whatever the weakness.
+pub fn effective_damage(attacker: &Card, attack: &Attack, defender: &Card) -> DamageCalc {
+    let (base, suffix) = attack.damage.as_deref().map(parse_damage).unwrap_or((0, None));
+    let types = attacker.types.as_deref().unwrap_or_default();
+
+    let weaknesses = defender.weaknesses.iter().flatten().map(|w| (&w.r#type, &w.value));
+    let resistances = defender.resistances.iter().flatten().map(|r| (&r.r#type, &r.value));
+
+    let after_weakness = matching(types, weaknesses).fold(base, |damage, m| m.apply(damage));
+    let after_resistance = matching(types, resistances).fold(after_weakness, |damage, m| m.apply(damage));
+    DamageCalc { base, suffix, after_weakness, after_resistance }
+}
+
+/// Modifiers of printed whose type is one of types.
+fn matching<'c>(types: &'c [EnergyType], printed: impl Iterator<Item = (&'c Option<EnergyType>, &'c Option<String>)> + 'c) -> impl Iterator<Item = Modifier> + 'c {
+    printed.filter(|(t, _)| t.as_ref().is_some_and(|t| types.contains(t)))
+           .filter_map(|(_, value)| Modifier::parse(value.as_deref()?))
+}
+
+/// Number and suffix of a printed damage, e.g. (50, Plus) for "50+"; (0, None) when empty.
+fn parse_damage(damage: &str) -> (u32, Option<DamageSuffix>) {
+    let damage = damage.trim();
+    let digits = damage.find(|c: char| !c.is_ascii_digit()).unwrap_or(damage.len());
+    let suffix = match damage[digits..].trim() {
+        "+" => Some(DamageSuffix::Plus),
+        "×" | "x" | "X" => Some(DamageSuffix::Times),
+        "-" | "−" => Some(DamageSuffix::Minus),
+        _ => None,
+    };
+
+    (damage[..digits].parse().unwrap_or(0), suffix)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture(name: &str) -> Card {
+        let cwd = std::env::current_dir().unwrap();
+        let json = std::fs::read_to_string(cwd.join(format!("src/mock/{name}.json"))).unwrap();
+        serde_json::from_str::<crate::Container<Card>>(&json).unwrap().data
+    }
+
+    fn attack(card: &Card, name: &str) -> Attack {
+        card.attacks.iter().flatten().find(|a| a.name.as_deref() == Some(name)).cloned().unwrap()
+    }
+
+    fn calc(base: u32, suffix: Option<DamageSuffix>, after_weakness: u32, after_resistance: u32) -> DamageCalc {
+        DamageCalc { base, suffix, after_weakness, after_resistance }
+    }
+
+    #[test]
+    fn test_modifier_and_damage_parsing() {
+        assert!(Modifier::parse("×2") == Some(Modifier::Times(2)) && Modifier::parse("x2") == Some(Modifier::Times(2)));
+        assert!(Modifier::parse("+20") == Some(Modifier::Plus(20)) && Modifier::parse("-30") == Some(Modifier::Minus(30)));
+        assert!(Modifier::parse("").is_none() && Modifier::parse("×").is_none() && Modifier::parse("30").is_none());
+
+        assert!(parse_damage("60") == (60, None) && parse_damage("") == (0, None));
+        assert!(parse_damage("50+") == (50, Some(DamageSuffix::Plus)) && parse_damage("20×") == (20, Some(DamageSuffix::Times)));
+        assert!(parse_damage("30-") == (30, Some(DamageSuffix::Minus)));
+    }
+
+    #[test]
+    fn test_effective_damage_of_fixture_cards() {
+        let (charizard, venusaur, zacian) = (fixture("base1-4"), fixture("xy1-1"), fixture("swsh1-138"));
+
+        // Fire against the Fire weakness of Venusaur-EX, then Grass against the Grass resistance of Zacian V
+        assert!(effective_damage(&charizard, &attack(&charizard, "Fire Spin"), &venusaur) == calc(100, None, 200, 200));
+        assert!(effective_damage(&venusaur, &attack(&venusaur, "Poison Powder"), &zacian) == calc(60, None, 60, 30));
+        assert!(effective_damage(&zacian, &attack(&zacian, "Brave Blade"), &charizard) == calc(230, None, 230, 230));
+
+        let charizard_ex = fixture("sv3pt5-6");
+        assert!(effective_damage(&charizard_ex, &attack(&charizard_ex, "Brave Wing"), &venusaur) == calc(60, Some(DamageSuffix::Plus), 120, 120));
+
+        let charizard_gx = fixture("sm3-20");
+        assert!(effective_damage(&charizard_gx, &attack(&charizard_gx, "Flame Blast-GX"), &venusaur) == calc(0, None, 0, 0));
+    }
+
+    #[test]
+    fn test_effective_damage_edge_cases() {
+        let zacian = fixture("swsh1-138");
+        let dual: Card = serde_json::from_value(serde_json::json!({ "types": ["Fire", "Grass"] })).unwrap();
+        let flips: Attack = serde_json::from_value(serde_json::json!({ "damage": "20×" })).unwrap();
+        let nibble: Attack = serde_json::from_value(serde_json::json!({ "damage": "10" })).unwrap();
+
+        // both types of an older dual-type card count, for the weakness and the resistance
+        assert!(effective_damage(&dual, &flips, &zacian) == calc(20, Some(DamageSuffix::Times), 40, 10));
+        assert!(effective_damage(&dual, &nibble, &zacian) == calc(10, None, 20, 0));
+
+        let old: Card = serde_json::from_value(serde_json::json!({
+            "weaknesses": [{ "type": "Fire", "value": "+20" }], "resistances": [{ "type": "Grass", "value": "-30" }],
+        })).unwrap();
+        assert!(effective_damage(&dual, &nibble, &old) == calc(10, None, 30, 0));
+
+        let typeless: Card = serde_json::from_value(serde_json::json!({})).unwrap();
+        assert!(effective_damage(&typeless, &nibble, &zacian) == calc(10, None, 10, 10));
+    }
+}
@@ -0,0 +1,616 @@
+use std::collections::HashMap;
+use std::ops::{ Bound, RangeBounds };
+use std::path::Path;
+
+use serde::{ Deserialize, Serialize };
+
+#[cfg(feature = "chrono")]
+use chrono::NaiveDateTime;
+
+use crate::models::errors::Error;
+use crate::models::models::{ Card, EnergyType, Format, Legality };
+#[cfg(feature = "chrono")]
+use crate::models::models::{ Set, SetId, UPDATED_AT_FORMAT };
+#[cfg(feature = "chrono")]
+use crate::{ Client, Q };
+
+/// Version of the bundle layout written by `CardDatabase::save`; bumped whenever it changes in a
+/// way older versions of this crate cannot read.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Cards kept in memory and persisted as a single JSON bundle, e.g. the result of
+/// `all::<Card>()` saved once and reloaded on the next run instead of downloading it again.
+///
+/// ```ignore
+/// let db = CardDatabase::new(api.all::<Card>().await?);
+/// db.save("cards.json")?;
+///
+/// let db = CardDatabase::load("cards.json")?;
+/// let card = db.get("xy1-1");
+/// let legal_fire = db.query().set("sv4").has_type(EnergyType::Fire).legal_in(Format::Standard).run();
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct CardDatabase {
+    cards: Vec<Card>,
+    by_id: HashMap<String, usize>,
+    by_set: HashMap<String, Vec<usize>>,
+    /// Keyed by lowercase name.
+    by_name: HashMap<String, Vec<usize>>,
+    /// Latest set `updatedAt` seen by `sync`, in `UPDATED_AT_FORMAT`.
+    watermark: Option<String>,
+}
+
+#[derive(Serialize)]
+struct BundleRef<'a> {
+    schema_version: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    watermark: Option<&'a str>,
+    cards: &'a [Card],
+}
+
+#[derive(Deserialize)]
+struct Bundle {
+    schema_version: u32,
+    #[serde(default)]
+    watermark: Option<String>,
+    cards: Vec<Card>,
+}
+
+/// Version of a bundle, read before the rest so a layout this crate doesn't know fails with
+/// `UnsupportedSchemaVersion` rather than a parsing error.
+#[derive(Deserialize)]
+struct Version {
+    schema_version: u32,
+}
+
+impl Version {
+    fn check(self) -> Result<(), Error> {
+        if self.schema_version != SCHEMA_VERSION {
+            return Err(Error::UnsupportedSchemaVersion { found: self.schema_version, expected: SCHEMA_VERSION });
+        }
+        Ok(())
+    }
+}
+
+/// Outcome of `CardDatabase::sync`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SyncReport {
+    /// Ids of the sets updated since the previous sync, whose cards were fetched again.
+    pub refreshed_sets: Vec<String>,
+    /// Cards added, modified or removed.
+    pub changed_cards: usize,
+}
+
+impl CardDatabase {
+    pub fn new(cards: Vec<Card>) -> Self {
+        let mut by_id = HashMap::new();
+        let mut by_set = HashMap::<String, Vec<usize>>::new();
+        let mut by_name = HashMap::<String, Vec<usize>>::new();
+
+        for (i, card) in cards.iter().enumerate() {
+            if let Some(id) = &card.id {
+                by_id.insert(id.to_string(), i);
+            }
+            if let Some(set_id) = card.set.as_ref().and_then(|s| s.id.clone()) {
+                by_set.entry(set_id.into()).or_default().push(i);
+            }
+            if let Some(name) = &card.name {
+                by_name.entry(name.to_lowercase()).or_default().push(i);
+            }
+        }
+
+        Self { cards, by_id, by_set, by_name, watermark: None }
+    }
+
+    /// Reads a bundle written by `save` or `save_pretty`.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let text = std::fs::read_to_string(path).map_err(|_| Error::FailedOpeningFile)?;
+        Self::from_json(&text)
+    }
+
+    /// Parses a bundle; a corrupt one fails with the line and column where parsing stopped.
+    pub fn from_json(text: &str) -> Result<Self, Error> {
+        serde_json::from_str::<Version>(text).map_err(Error::parsing_file)?.check()?;
+        let bundle: Bundle = serde_json::from_str(text).map_err(Error::parsing_file)?;
+        Ok(Self::from_bundle(bundle))
+    }
+
+    fn from_bundle(bundle: Bundle) -> Self {
+        debug_assert!(bundle.schema_version == SCHEMA_VERSION);
+        let mut db = Self::new(bundle.cards);
+        db.watermark = bundle.watermark;
+        db
+    }
+
+    fn bundle(&self) -> BundleRef<'_> {
+        BundleRef { schema_version: SCHEMA_VERSION, watermark: self.watermark.as_deref(), cards: &self.cards }
+    }
+
+    /// Writes the bundle as compact JSON.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), Error> {
+        self.write(path, false)
+    }
+
+    /// Writes the bundle as indented JSON, easier to diff.
+    pub fn save_pretty(&self, path: impl AsRef<Path>) -> Result<(), Error> {
+        self.write(path, true)
+    }
+
+    fn write(&self, path: impl AsRef<Path>, pretty: bool) -> Result<(), Error> {
+        let bundle = self.bundle();
+        let json = if pretty { serde_json::to_string_pretty(&bundle) } else { serde_json::to_string(&bundle) };
+        let json = json.map_err(|e| Error::FailedWritingFile { reason: e.to_string() })?;
+
+        std::fs::write(path, json).map_err(|e| Error::FailedWritingFile { reason: e.to_string() })
+    }
+
+    pub fn get(&self, id: &str) -> Option<&Card> {
+        self.by_id.get(id).map(|i| &self.cards[*i])
+    }
+
+    /// Cards whose name contains substr, ignoring case.
+    pub fn search_by_name(&self, substr: &str) -> Vec<&Card> {
+        let substr = substr.to_lowercase();
+        self.cards.iter()
+            .filter(|card| card.name.as_ref().is_some_and(|name| name.to_lowercase().contains(&substr)))
+            .collect()
+    }
+
+    /// Cards whose ability texts, attack texts or rules contain phrase, ignoring case; the offline
+    /// counterpart of `Client::cards_with_text`.
+    pub fn cards_with_text(&self, phrase: &str) -> Vec<&Card> {
+        let phrase = phrase.to_lowercase();
+        self.cards.iter()
+            .filter(|card| printed_texts(card).any(|text| text.to_lowercase().contains(&phrase)))
+            .collect()
+    }
+
+    /// Offline search over the cards; every filter set must match.
+    pub fn query(&self) -> CardQuery<'_> {
+        CardQuery { db: self, filters: Filters::default() }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Card> {
+        self.cards.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.cards.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cards.is_empty()
+    }
+
+    /// `updatedAt` of the most recently updated set as of the last `sync`, e.g.
+    /// "2024/05/01 10:00:00"; None before the first one.
+    pub fn watermark(&self) -> Option<&str> {
+        self.watermark.as_deref()
+    }
+}
+
+/// MessagePack bundles, enabled by the `msgpack` feature: the same content as the JSON ones in
+/// less space. MessagePack has no lines, its parsing errors are at line and column 0.
+#[cfg(feature = "msgpack")]
+impl CardDatabase {
+    /// Writes the bundle as MessagePack, fields keyed by name like in the JSON.
+    pub fn save_msgpack(&self, path: impl AsRef<Path>) -> Result<(), Error> {
+        let bytes = rmp_serde::to_vec_named(&self.bundle()).map_err(|e| Error::FailedWritingFile { reason: e.to_string() })?;
+        std::fs::write(path, bytes).map_err(|e| Error::FailedWritingFile { reason: e.to_string() })
+    }
+
+    /// Reads a bundle written by `save_msgpack`.
+    pub fn load_msgpack(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let bytes = std::fs::read(path).map_err(|_| Error::FailedOpeningFile)?;
+        let failed = |e: rmp_serde::decode::Error| Error::FailedParsingFile { reason: e.to_string(), line: 0, column: 0 };
+
+        rmp_serde::from_slice::<Version>(&bytes).map_err(failed)?.check()?;
+        let bundle: Bundle = rmp_serde::from_slice(&bytes).map_err(failed)?;
+        Ok(Self::from_bundle(bundle))
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl CardDatabase {
+    /// Fetches again the cards of the sets updated since the previous sync, every set the first
+    /// time, replacing the cards the database held for them. The watermark is kept by `save`.
+    pub async fn sync(&mut self, client: &Client) -> Result<SyncReport, Error> {
+        let since = self.watermark.as_deref().and_then(|w| NaiveDateTime::parse_from_str(w, UPDATED_AT_FORMAT).ok());
+        let sets = client.all::<Set>().await?;
+        let newest = sets.iter().filter_map(Set::updated_at).max();
+
+        // every set is fetched before the database changes, so that a failure leaves it as it was
+        let mut updated = Vec::new();
+        for set in sets.iter().filter(|s| is_updated_since(s, since)) {
+            let Some(set_id) = set.id.clone() else { continue };
+            let fetched = client.search_cards(Q::field("set.id").eq(&set_id)).await?;
+            updated.push((set_id, fetched));
+        }
+
+        let mut report = SyncReport::default();
+        let mut cards = std::mem::take(&mut self.cards);
+        for (set_id, fetched) in updated {
+            let (old, kept): (Vec<Card>, Vec<Card>) = cards.into_iter().partition(|c| card_set_id(c) == Some(&set_id));
+            let old: HashMap<String, serde_json::Value> = old.into_iter()
+                                                            .filter_map(|c| Some((c.id.clone()?.into(), serde_json::to_value(c).ok()?)))
+                                                            .collect();
+            let modified = fetched.iter()
+                                  .filter(|c| c.id.as_deref().and_then(|id| old.get(id)) != serde_json::to_value(c).ok().as_ref())
+                                  .count();
+            let removed = old.keys().filter(|id| !fetched.iter().any(|c| c.id.as_deref() == Some(id.as_str()))).count();
+
+            report.changed_cards += modified + removed;
+            report.refreshed_sets.push(set_id.into());
+            cards = kept.into_iter().chain(fetched).collect();
+        }
+
+        let watermark = newest.map(|t| t.format(UPDATED_AT_FORMAT).to_string()).or(self.watermark.take());
+        *self = Self::new(cards);
+        self.watermark = watermark;
+
+        Ok(report)
+    }
+}
+
+#[cfg(feature = "chrono")]
+fn is_updated_since(set: &Set, since: Option<NaiveDateTime>) -> bool {
+    match (set.updated_at(), since) {
+        (Some(updated), Some(since)) => updated > since,
+        _ => true,
+    }
+}
+
+#[cfg(feature = "chrono")]
+fn card_set_id(card: &Card) -> Option<&SetId> {
+    card.set.as_ref()?.id.as_ref()
+}
+
+#[cfg(feature = "chrono")]
+impl Client {
+    /// Sets whose `updatedAt` is later than ts, along with those whose `updatedAt` is missing or
+    /// malformed.
+    pub async fn sets_updated_since(&self, ts: NaiveDateTime) -> Result<Vec<Set>, Error> {
+        let sets = self.all::<Set>().await?;
+        Ok(sets.into_iter().filter(|s| is_updated_since(s, Some(ts))).collect())
+    }
+}
+
+/// Ability texts, attack texts and rules of card.
+fn printed_texts(card: &Card) -> impl Iterator<Item = &str> {
+    let abilities = card.abilities.iter().flatten().filter_map(|a| a.text.as_deref());
+    let attacks = card.attacks.iter().flatten().filter_map(|a| a.text.as_deref());
+    let rules = card.rules.iter().flatten().map(String::as_str);
+
+    abilities.chain(attacks).chain(rules)
+}
+
+#[derive(Clone, Debug, Default)]
+struct Filters {
+    name: Option<String>,
+    name_contains: Option<String>,
+    types: Vec<EnergyType>,
+    subtypes: Vec<String>,
+    supertype: Option<String>,
+    set: Option<String>,
+    rarity: Option<String>,
+    hp: Option<(Bound<u32>, Bound<u32>)>,
+    legal_in: Vec<Format>,
+}
+
+/// Filters over a `CardDatabase`, combined with AND, see `CardDatabase::query`. Name and set
+/// filters are answered from the database's indices, the others are checked card by card among
+/// the remaining candidates.
+#[derive(Clone, Debug)]
+pub struct CardQuery<'a> {
+    db: &'a CardDatabase,
+    filters: Filters,
+}
+
+impl<'a> CardQuery<'a> {
+    /// Name equal to name, ignoring case.
+    pub fn name(mut self, name: &str) -> Self {
+        self.filters.name = Some(name.to_lowercase());
+        self
+    }
+
+    /// Name containing substr, ignoring case.
+    pub fn name_contains(mut self, substr: &str) -> Self {
+        self.filters.name_contains = Some(substr.to_lowercase());
+        self
+    }
+
+    /// Has energy_type among its types; may be called several times to require them all.
+    pub fn has_type(mut self, energy_type: EnergyType) -> Self {
+        self.filters.types.push(energy_type);
+        self
+    }
+
+    /// Has subtype, e.g. "Stage 2", ignoring case; may be called several times to require them all.
+    pub fn subtype(mut self, subtype: &str) -> Self {
+        self.filters.subtypes.push(subtype.to_lowercase());
+        self
+    }
+
+    /// Supertype, e.g. "Trainer", ignoring case.
+    pub fn supertype(mut self, supertype: &str) -> Self {
+        self.filters.supertype = Some(supertype.to_lowercase());
+        self
+    }
+
+    /// In the set with id set_id, e.g. "sv4".
+    pub fn set(mut self, set_id: &str) -> Self {
+        self.filters.set = Some(set_id.to_owned());
+        self
+    }
+
+    /// Rarity, e.g. "Rare Holo", ignoring case.
+    pub fn rarity(mut self, rarity: &str) -> Self {
+        self.filters.rarity = Some(rarity.to_lowercase());
+        self
+    }
+
+    /// HP within range, e.g. `100..=200`; cards without a numeric HP never match.
+    pub fn hp(mut self, range: impl RangeBounds<u32>) -> Self {
+        self.filters.hp = Some((range.start_bound().cloned(), range.end_bound().cloned()));
+        self
+    }
+
+    /// Legal in format; may be called several times to require them all.
+    pub fn legal_in(mut self, format: Format) -> Self {
+        self.filters.legal_in.push(format);
+        self
+    }
+
+    /// Matching cards, in the order they were added to the database.
+    pub fn run(&self) -> Vec<&'a Card> {
+        let db = self.db;
+        let by_name = self.filters.name.as_ref().map(|name| db.by_name.get(name).map(Vec::as_slice).unwrap_or_default());
+        let by_set = self.filters.set.as_ref().map(|set| db.by_set.get(set).map(Vec::as_slice).unwrap_or_default());
+
+        let smallest = [by_name, by_set].into_iter().flatten().min_by_key(|candidates| candidates.len());
+        match smallest {
+            Some(candidates) => candidates.iter().map(|i| &db.cards[*i]).filter(|card| self.matches(card)).collect(),
+            None => db.cards.iter().filter(|card| self.matches(card)).collect(),
+        }
+    }
+
+    fn matches(&self, card: &Card) -> bool {
+        let f = &self.filters;
+        let lowercase_name = card.name.as_deref().map(str::to_lowercase);
+
+        f.name.as_ref().is_none_or(|name| lowercase_name.as_ref() == Some(name))
+            && f.name_contains.as_ref().is_none_or(|substr| lowercase_name.as_ref().is_some_and(|name| name.contains(substr)))
+            && f.types.iter().all(|t| card.types.iter().flatten().any(|card_type| card_type == t))
+            && f.subtypes.iter().all(|s| card.subtypes.iter().flatten().any(|card_subtype| card_subtype.to_lowercase() == *s))
+            && f.supertype.as_ref().is_none_or(|s| card.supertype.as_ref().is_some_and(|card_supertype| card_supertype.to_lowercase() == *s))
+            && f.set.as_ref().is_none_or(|set| card.set.as_ref().and_then(|s| s.id.as_deref()) == Some(set.as_str()))
+            && f.rarity.as_ref().is_none_or(|r| card.rarity.as_ref().is_some_and(|card_rarity| card_rarity.to_lowercase() == *r))
+            && f.hp.is_none_or(|range| card.hp_value().is_some_and(|hp| range.contains(&hp)))
+            && f.legal_in.iter().all(|format| card.legalities.as_ref().and_then(|l| l.get(*format)) == Some(&Legality::Legal))
+    }
+}
+
+impl From<Vec<Card>> for CardDatabase {
+    fn from(cards: Vec<Card>) -> Self {
+        Self::new(cards)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::MockApi;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_database_save_and_load() {
+        let mock = MockApi::start().await;
+        let db = CardDatabase::new(mock.client().all::<Card>().await.unwrap());
+        let path = std::env::temp_dir().join(format!("pokemon_tcg_sdk_db_{}.json", std::process::id()));
+
+        db.save_pretty(&path).unwrap();
+        let loaded = CardDatabase::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(loaded.len() == 4);
+        assert!(loaded.get("xy1-3").unwrap().name == Some("Weedle".into()));
+        assert!(loaded.get("xy1-999").is_none());
+        assert!(loaded.search_by_name("venusaur").len() == 2);
+        assert!(loaded.iter().count() == db.len());
+    }
+
+    #[test]
+    fn test_database_rejects_corrupt_and_newer_files() {
+        match CardDatabase::from_json("{\n  \"schema_version\": 1,\n  \"cards\": [{\"id\": 7}]\n}") {
+            Err(Error::FailedParsingFile { line, column, .. }) => assert!(line == 3 && column > 0),
+            other => panic!("unexpected {other:?}"),
+        }
+
+        assert!(matches!(
+            CardDatabase::from_json(r#"{"schema_version": 2, "cards": []}"#),
+            Err(Error::UnsupportedSchemaVersion { found: 2, expected: 1 })
+        ));
+        assert!(CardDatabase::from_json(r#"{"schema_version": 1, "cards": []}"#).unwrap().is_empty());
+    }
+
+    fn generated_cards() -> Vec<Card> {
+        let types = ["Fire", "Water", "Grass"];
+        (0..300).map(|i| serde_json::from_value(serde_json::json!({
+            "id": format!("set{}-{i}", i % 3),
+            "name": if i % 50 == 0 { "Charizard".to_owned() } else { format!("Pokemon {i}") },
+            "supertype": if i % 10 == 0 { "Trainer" } else { "Pokémon" },
+            "subtypes": if i % 2 == 0 { vec!["Basic"] } else { vec!["Stage 1", "V"] },
+            "hp": format!("{}", 30 + i % 10 * 10),
+            "types": [types[i % 3]],
+            "set": { "id": format!("set{}", i % 3) },
+            "rarity": if i % 7 == 0 { "Rare" } else { "Common" },
+            "legalities": if i < 150 { serde_json::json!({ "unlimited": "Legal", "standard": "Legal" }) } else { serde_json::json!({ "unlimited": "Legal" }) },
+        })).unwrap()).collect()
+    }
+
+    #[test]
+    fn test_database_cards_with_text() {
+        let cards: Vec<Card> = serde_json::from_str(r#"[
+            {"id": "a", "abilities": [{"name": "Stir", "text": "Once during your turn, you may Discard Your Hand and draw 6 cards."}]},
+            {"id": "b", "attacks": [{"name": "Burn", "text": "Discard an Energy from this Pokémon."}]},
+            {"id": "c", "rules": ["You may play only 1 Supporter card during your turn.", "Discard your hand, then draw 7 cards."]},
+            {"id": "d", "flavorText": "It likes to discard your hand."}
+        ]"#).unwrap();
+        let db = CardDatabase::new(cards);
+
+        let ids: Vec<&str> = db.cards_with_text("discard your hand").iter().filter_map(|c| c.id.as_deref()).collect();
+        assert!(ids == vec!["a", "c"]);
+        assert!(db.cards_with_text("energy").len() == 1 && db.cards_with_text("mulligan").is_empty());
+    }
+
+    #[test]
+    fn test_database_query_filters() {
+        let db = CardDatabase::new(generated_cards());
+
+        assert!(db.query().run().len() == 300);
+        assert!(db.query().name("charizard").run().len() == 6);
+        assert!(db.query().name("charizard").set("set0").run().len() == 2);
+        assert!(db.query().name_contains("MON 29").run().len() == 11);
+        assert!(db.query().set("set1").has_type(EnergyType::Water).run().len() == 100);
+        assert!(db.query().set("set1").has_type(EnergyType::Fire).run().is_empty());
+        assert!(db.query().set("unknown").run().is_empty());
+        assert!(db.query().subtype("stage 1").subtype("V").run().len() == 150);
+        assert!(db.query().supertype("trainer").rarity("rare").run().len() == 5);
+        assert!(db.query().hp(100..).run().len() == 90);
+        assert!(db.query().hp(..=30).legal_in(Format::Standard).run().len() == 15);
+        assert!(db.query().legal_in(Format::Expanded).run().is_empty());
+
+        let ids: Vec<&str> = db.query().name("Charizard").run().iter().filter_map(|c| c.id.as_deref()).collect();
+        assert!(ids == vec!["set0-0", "set2-50", "set1-100", "set0-150", "set2-200", "set1-250"]);
+    }
+
+    #[cfg(feature = "chrono")]
+    #[tokio::test]
+    async fn test_database_sync_refreshes_updated_sets() {
+        use wiremock::{ Mock, MockServer, ResponseTemplate };
+        use wiremock::matchers::{ method, path, query_param };
+
+        fn page(data: serde_json::Value) -> ResponseTemplate {
+            let count = data.as_array().unwrap().len();
+            ResponseTemplate::new(200).set_body_json(serde_json::json!({ "data": data, "page": 1, "pageSize": 250, "count": count, "totalCount": count }))
+        }
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/sets"))
+            .respond_with(page(serde_json::json!([
+                { "id": "base1", "updatedAt": "2020/08/14 09:35:00" },
+                { "id": "xy1", "updatedAt": "2024/05/01 10:00:00" },
+            ])))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/cards"))
+            .and(query_param("q", "set.id:xy1"))
+            .respond_with(page(serde_json::json!([
+                { "id": "xy1-1", "name": "Venusaur-EX", "set": { "id": "xy1" } },
+                { "id": "xy1-2", "name": "M Venusaur-EX", "set": { "id": "xy1" } },
+            ])))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/cards"))
+            .and(query_param("q", "set.id:base1"))
+            .respond_with(page(serde_json::json!([{ "id": "base1-1", "name": "Alakazam", "set": { "id": "base1" } }])))
+            .mount(&server)
+            .await;
+
+        let api = Client::builder().base_url(server.uri()).build().unwrap();
+        let mut db = CardDatabase::from_json(r#"{"schema_version": 1, "watermark": "2022/01/01 00:00:00", "cards": [
+            {"id": "base1-1", "name": "Alakazam", "set": {"id": "base1"}},
+            {"id": "xy1-1", "name": "Venusaur", "set": {"id": "xy1"}},
+            {"id": "xy1-99", "name": "Withdrawn", "set": {"id": "xy1"}}
+        ]}"#).unwrap();
+
+        let report = db.sync(&api).await.unwrap();
+        // xy1-1 renamed, xy1-2 added and xy1-99 removed; base1 wasn't updated since the watermark
+        assert!(report == SyncReport { refreshed_sets: vec!["xy1".into()], changed_cards: 3 });
+        assert!(db.len() == 3 && db.get("xy1-1").unwrap().name == Some("Venusaur-EX".into()) && db.get("xy1-99").is_none());
+        assert!(db.query().set("xy1").run().len() == 2);
+        assert!(db.watermark() == Some("2024/05/01 10:00:00"));
+
+        let path = std::env::temp_dir().join(format!("pokemon_tcg_sdk_db_sync_{}.json", std::process::id()));
+        db.save(&path).unwrap();
+        let mut loaded = CardDatabase::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(loaded.sync(&api).await.unwrap() == SyncReport::default());
+
+        let since = NaiveDateTime::parse_from_str("2020/01/01 00:00:00", UPDATED_AT_FORMAT).unwrap();
+        assert!(api.sets_updated_since(since).await.unwrap().len() == 2);
+    }
+
+    #[cfg(feature = "chrono")]
+    #[tokio::test]
+    async fn test_database_sync_failure_keeps_the_database() {
+        use wiremock::{ Mock, MockServer, ResponseTemplate };
+        use wiremock::matchers::{ method, path, query_param };
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/sets"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "data": [{ "id": "xy1" }, { "id": "base1" }], "page": 1, "pageSize": 250, "count": 2, "totalCount": 2,
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/cards"))
+            .and(query_param("q", "set.id:xy1"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "data": [], "page": 1, "pageSize": 250, "count": 0, "totalCount": 0,
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/cards"))
+            .and(query_param("q", "set.id:base1"))
+            .respond_with(ResponseTemplate::new(500))
+            .mount(&server)
+            .await;
+
+        let api = Client::builder().base_url(server.uri()).max_retries(0).build().unwrap();
+        let mut db = CardDatabase::from_json(r#"{"schema_version": 1, "cards": [
+            {"id": "base1-1", "name": "Alakazam", "set": {"id": "base1"}},
+            {"id": "xy1-1", "name": "Venusaur", "set": {"id": "xy1"}}
+        ]}"#).unwrap();
+
+        assert!(matches!(db.sync(&api).await, Err(Error::ServerError { status: 500, .. })));
+        assert!(db.len() == 2 && db.get("xy1-1").unwrap().name == Some("Venusaur".into()));
+        assert!(db.query().set("base1").run().len() == 1 && db.search_by_name("alakazam").len() == 1);
+        assert!(db.watermark().is_none());
+    }
+
+    #[cfg(feature = "msgpack")]
+    #[test]
+    fn test_database_msgpack_round_trip() {
+        #[cfg_attr(feature = "strict-models", allow(unused_mut))]
+        let mut cards: Vec<Card> = ["xy1-1", "base1-4", "ex6-105", "sm3-20", "sv3pt5-6", "swsh1-138"].iter().map(|id| {
+            let json = std::fs::read_to_string(format!("src/mock/{id}.json")).unwrap();
+            serde_json::from_str::<crate::Container<Card>>(&json).unwrap().data
+        }).collect();
+        // unknown fields are kept in extra, unless strict-models rejects them
+        #[cfg(not(feature = "strict-models"))]
+        cards[0].extra.insert("futureField".into(), serde_json::json!({ "nested": [1, 2.5, null, "text"] }));
+        #[cfg(not(feature = "strict-models"))]
+        cards[1].set.as_mut().unwrap().extra.insert("futureSetField".into(), serde_json::json!(true));
+        let db = CardDatabase::new(cards);
+
+        let path = std::env::temp_dir().join(format!("pokemon_tcg_sdk_db_{}.msgpack", std::process::id()));
+        db.save_msgpack(&path).unwrap();
+        let size = std::fs::metadata(&path).unwrap().len();
+        let loaded = CardDatabase::load_msgpack(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(loaded.iter().eq(db.iter()));
+        #[cfg(not(feature = "strict-models"))]
+        assert!(loaded.get("xy1-1").unwrap().extra["futureField"]["nested"][1] == 2.5);
+        assert!(size < serde_json::to_string(&db.bundle()).unwrap().len() as u64);
+        assert!(matches!(CardDatabase::load_msgpack("Cargo.toml"), Err(Error::FailedParsingFile { line: 0, .. })));
+    }
+}
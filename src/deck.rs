@@ -0,0 +1,450 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use serde::{ Deserialize, Serialize };
+
+use crate::Client;
+use crate::models::errors::Error;
+use crate::models::models::{ Card, Format, Legality };
+use crate::set_codes::{ id_number, SetCodes };
+
+/// Section headers of the export format, in the order they are written.
+const SECTIONS: [&str; 3] = ["Pokémon", "Trainer", "Energy"];
+
+/// Amount of cards a legal deck holds.
+pub const DECK_SIZE: u32 = 60;
+/// Copies allowed of each card name, basic energies aside.
+pub const MAX_COPIES: u32 = 4;
+
+/// Copies of a card in a deck.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DeckEntry {
+    pub card: Card,
+    pub count: u32,
+}
+
+/// Line of a decklist that could not be turned into a card, numbered from 1.
+#[derive(Debug, Serialize)]
+pub struct UnresolvedLine {
+    pub line_number: usize,
+    pub line: String,
+    pub error: Error,
+}
+
+/// A decklist, e.g. imported from the Pokémon TCG Live / Online export format:
+///
+/// ```text
+/// Pokémon: 4
+/// 4 Charizard ex OBF 125
+///
+/// Trainer: 4
+/// 4 Rare Candy SVI 191
+///
+/// Total Cards: 8
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Deck {
+    pub entries: Vec<DeckEntry>,
+}
+
+/// Deck building rule broken by a deck.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub enum Violation {
+    /// The deck doesn't hold exactly 60 cards.
+    DeckSize { count: u32 },
+    /// More than 4 cards share a name that isn't a basic energy.
+    TooManyCopies { name: String, count: u32 },
+    /// The card isn't Legal in the format, including cards whose legality is unknown.
+    NotLegal { id: String, name: String },
+    /// More than one ACE SPEC card.
+    TooManyAceSpecs { count: u32 },
+    /// More than one Radiant Pokémon.
+    TooManyRadiants { count: u32 },
+}
+
+/// `<count> <name> <set code> <number>` line of an export, before the card has been looked up.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct DeckLine {
+    count: u32,
+    name: String,
+    set_code: String,
+    number: String,
+}
+
+impl Deck {
+    /// Adds count copies of card, merged with the entry of the same card id if there is one.
+    pub fn add(&mut self, card: Card, count: u32) {
+        match self.entries.iter_mut().find(|e| e.card.id.is_some() && e.card.id == card.id) {
+            Some(entry) => entry.count += count,
+            None => self.entries.push(DeckEntry { card, count }),
+        }
+    }
+
+    /// Amount of cards in the deck, counting every copy.
+    pub fn total_cards(&self) -> u32 {
+        self.entries.iter().map(|e| e.count).sum()
+    }
+
+    /// Every deck building rule of format the deck breaks; empty for a legal deck. Only looks at
+    /// the cards' `name`, `supertype`, `subtypes` and `legalities`, so no request is sent.
+    pub fn validate(&self, format: Format) -> Vec<Violation> {
+        let mut violations = Vec::new();
+
+        let total = self.total_cards();
+        if total != DECK_SIZE {
+            violations.push(Violation::DeckSize { count: total });
+        }
+
+        let mut copies = Vec::<(String, u32)>::new();
+        for entry in self.entries.iter().filter(|e| !is_basic_energy(&e.card)) {
+            let name = entry.card.name.clone().unwrap_or_default();
+            match copies.iter_mut().find(|(n, _)| *n == name) {
+                Some((_, count)) => *count += entry.count,
+                None => copies.push((name, entry.count)),
+            }
+        }
+        violations.extend(copies.into_iter()
+                                .filter(|(_, count)| *count > MAX_COPIES)
+                                .map(|(name, count)| Violation::TooManyCopies { name, count }));
+
+        for entry in &self.entries {
+            let legal = entry.card.legalities.as_ref().and_then(|l| l.get(format)) == Some(&Legality::Legal);
+            if !legal {
+                violations.push(Violation::NotLegal {
+                    id: entry.card.id.as_deref().unwrap_or_default().into(),
+                    name: entry.card.name.clone().unwrap_or_default(),
+                });
+            }
+        }
+
+        let ace_specs = self.count_with_subtype("ACE SPEC");
+        if ace_specs > 1 {
+            violations.push(Violation::TooManyAceSpecs { count: ace_specs });
+        }
+
+        let radiants = self.count_with_subtype("Radiant");
+        if radiants > 1 {
+            violations.push(Violation::TooManyRadiants { count: radiants });
+        }
+
+        violations
+    }
+
+    fn count_with_subtype(&self, subtype: &str) -> u32 {
+        self.entries.iter()
+            .filter(|e| has_subtype(&e.card, subtype))
+            .map(|e| e.count)
+            .sum()
+    }
+
+    /// Parses an export and looks its cards up by set code and number, with one request per 50
+    /// cards. Set codes are resolved by the `SetCodes` of client, which fetches the sets the first
+    /// time. Lines that are malformed, from a set code no set has or naming a card the API doesn't
+    /// know are returned apart instead of failing the whole import.
+    pub async fn parse(client: &Client, text: &str) -> Result<(Deck, Vec<UnresolvedLine>), Error> {
+        let mut unresolved = Vec::new();
+        let mut wanted = Vec::<(usize, String, DeckLine)>::new();
+
+        for (i, raw) in text.lines().enumerate() {
+            let line = raw.trim();
+            if line.is_empty() || is_header(line) {
+                continue;
+            }
+
+            let Some(parsed) = parse_line(line) else {
+                unresolved.push(UnresolvedLine { line_number: i + 1, line: line.into(), error: Error::InvalidArgument { arg: line.into() } });
+                continue;
+            };
+
+            match client.set_codes().set_id(client, &parsed.set_code).await? {
+                Some(set_id) => wanted.push((i + 1, format!("{set_id}-{}", id_number(&parsed.number)), parsed)),
+                None => unresolved.push(UnresolvedLine { line_number: i + 1, line: line.into(), error: Error::MissingSetMapping { set: parsed.set_code } }),
+            }
+        }
+
+        let ids: Vec<&str> = wanted.iter().map(|(_, id, _)| id.as_str()).collect();
+        let cards: HashMap<String, Card> = client.find_cards(&ids)
+                                                 .await?
+                                                 .into_iter()
+                                                 .filter_map(|c| Some((c.id.clone()?.into(), c)))
+                                                 .collect();
+
+        let mut deck = Deck::default();
+        for (line_number, id, line) in wanted {
+            match cards.get(&id) {
+                Some(card) => deck.add(card.clone(), line.count),
+                None => unresolved.push(UnresolvedLine { line_number, line: line.to_string(), error: Error::NoMatchingCards { q: format!("id:{id}") } }),
+            }
+        }
+        unresolved.sort_by_key(|u| u.line_number);
+
+        Ok((deck, unresolved))
+    }
+
+    /// Writes the deck in the export format read by `Deck::parse`, grouped by supertype. Cards
+    /// whose set has no `ptcgoCode` get the code bundled with the crate, see `to_ptcgl_string_with`.
+    pub fn to_ptcgl_string(&self) -> String {
+        self.to_ptcgl_string_with(&SetCodes::new())
+    }
+
+    /// Same as `to_ptcgl_string`, cards whose set has no `ptcgoCode` getting their code from codes,
+    /// e.g. `api.set_codes()` once a decklist was parsed.
+    pub fn to_ptcgl_string_with(&self, codes: &SetCodes) -> String {
+        let mut out = String::new();
+
+        for section in SECTIONS {
+            let entries: Vec<&DeckEntry> = self.entries.iter().filter(|e| supertype_section(&e.card) == section).collect();
+            if entries.is_empty() {
+                continue;
+            }
+
+            let count: u32 = entries.iter().map(|e| e.count).sum();
+            let _ = writeln!(out, "{section}: {count}");
+
+            for entry in entries {
+                let card = &entry.card;
+                let set = card.set.as_ref();
+                let code = set.and_then(|s| s.ptcgoCode.clone())
+                              .or_else(|| codes.code(set?.id.as_deref()?))
+                              .unwrap_or_default();
+
+                let _ = writeln!(out, "{} {} {} {}", entry.count, card.name.as_deref().unwrap_or_default(), code, card.number.as_deref().unwrap_or_default());
+            }
+            out.push('\n');
+        }
+
+        let _ = writeln!(out, "Total Cards: {}", self.total_cards());
+        out
+    }
+}
+
+/// Decks written as YAML, e.g. in a configuration file, enabled by the `yaml` feature.
+#[cfg(feature = "yaml")]
+impl Deck {
+    /// Reads a deck written by `to_yaml`, its entries holding the cards in full.
+    pub fn from_yaml(text: &str) -> Result<Deck, Error> {
+        serde_yaml_ng::from_str(text).map_err(|e| {
+            let (line, column) = e.location().map(|l| (l.line(), l.column())).unwrap_or((0, 0));
+            Error::FailedParsingFile { reason: e.to_string(), line, column }
+        })
+    }
+
+    pub fn to_yaml(&self) -> Result<String, Error> {
+        serde_yaml_ng::to_string(self).map_err(|e| Error::FailedWritingFile { reason: e.to_string() })
+    }
+}
+
+impl std::fmt::Display for DeckLine {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{} {} {} {}", self.count, self.name, self.set_code, self.number)
+    }
+}
+
+/// Section headers such as `Pokémon: 12` and the closing `Total Cards: 60`.
+fn is_header(line: &str) -> bool {
+    line.split_once(':').is_some_and(|(name, count)| {
+        (SECTIONS.contains(&name.trim()) || name.trim() == "Total Cards") && count.trim().parse::<u32>().is_ok()
+    })
+}
+
+/// Reads `4 Charizard ex OBF 125`, also accepting the `* ` prefix of older exports.
+fn parse_line(line: &str) -> Option<DeckLine> {
+    let tokens: Vec<&str> = line.trim_start_matches('*').split_whitespace().collect();
+    if tokens.len() < 4 {
+        return None;
+    }
+
+    let count = tokens[0].parse().ok().filter(|c| *c > 0)?;
+    let number = tokens[tokens.len() - 1];
+    let set_code = tokens[tokens.len() - 2];
+    let name = tokens[1..tokens.len() - 2].join(" ");
+
+    Some(DeckLine { count, name, set_code: set_code.into(), number: number.into() })
+}
+
+fn has_subtype(card: &Card, subtype: &str) -> bool {
+    card.subtypes.iter().flatten().any(|s| s.eq_ignore_ascii_case(subtype))
+}
+
+fn is_basic_energy(card: &Card) -> bool {
+    card.supertype.as_deref() == Some("Energy") && has_subtype(card, "Basic")
+}
+
+fn supertype_section(card: &Card) -> &'static str {
+    match card.supertype.as_deref() {
+        Some("Trainer") => "Trainer",
+        Some("Energy") => "Energy",
+        _ => "Pokémon",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wiremock::{ Mock, ResponseTemplate };
+    use wiremock::matchers::{ method, path, query_param };
+
+    use crate::testing::MockApi;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_line() {
+        let line = parse_line("4 Charizard ex OBF 125").unwrap();
+        assert!(line == DeckLine { count: 4, name: "Charizard ex".into(), set_code: "OBF".into(), number: "125".into() });
+
+        assert!(parse_line("* 2 Boss's Orders PAL 172").unwrap().name == "Boss's Orders");
+        assert!(parse_line("Charizard ex OBF 125").is_none());
+        assert!(parse_line("4 OBF 125").is_none());
+        assert!(is_header("Pokémon: 12") && is_header("Total Cards: 60"));
+        assert!(!is_header("1 Team Rocket's Ariana: Boss TRR 72"));
+    }
+
+    #[tokio::test]
+    async fn test_deck_parse_and_round_trip() {
+        let cwd = std::env::current_dir().unwrap();
+        let charizard = std::fs::read_to_string(cwd.join("src/mock/card.json")).unwrap();
+        let page = format!(r#"{{"data": [{charizard}], "page": 1, "pageSize": 50, "count": 1, "totalCount": 1}}"#);
+
+        let mock = MockApi::start().await;
+        Mock::given(method("GET"))
+            .and(path("/cards"))
+            .and(query_param("q", r"id:swsh4\-25"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(page, "application/json"))
+            .with_priority(1)
+            .mount(mock.server())
+            .await;
+        let api = mock.client();
+
+        let text = "Pokémon: 3\n3 Charizard VIV 25\n\nTrainer: 5\n4 Professor's Research XYZ 147\nnot a card line\n\nTotal Cards: 7\n";
+        let (deck, unresolved) = Deck::parse(&api, text).await.unwrap();
+
+        assert!(deck.total_cards() == 3);
+        assert!(deck.entries[0].card.id.as_deref() == Some("swsh4-25"));
+        assert!(unresolved.len() == 2);
+        assert!(matches!(&unresolved[0].error, Error::MissingSetMapping { set } if set == "XYZ"));
+        assert!(unresolved[0].line_number == 5);
+        assert!(matches!(unresolved[1].error, Error::InvalidArgument { .. }));
+
+        let exported = deck.to_ptcgl_string();
+        assert!(exported == "Pokémon: 3\n3 Charizard VIV 25\n\nTotal Cards: 3\n");
+
+        let (again, unresolved) = Deck::parse(&api, &exported).await.unwrap();
+        assert!(unresolved.is_empty());
+        assert!(again.to_ptcgl_string() == exported);
+    }
+
+    #[tokio::test]
+    async fn test_deck_parse_resolves_codes_through_the_api() {
+        let charizard: crate::Container<Card> = serde_json::from_str(include_str!("mock/sv3pt5-6.json")).unwrap();
+        let mut set = charizard.data.set.clone().unwrap();
+        set.ptcgoCode = None;
+        let page = |data: serde_json::Value| {
+            ResponseTemplate::new(200).set_body_json(serde_json::json!({ "data": data, "page": 1, "pageSize": 250, "count": 1, "totalCount": 1 }))
+        };
+
+        let mock = MockApi::start().await;
+        Mock::given(method("GET"))
+            .and(path("/sets"))
+            .respond_with(page(serde_json::json!([{ "id": "sv3pt5", "ptcgoCode": "MEW" }])))
+            .with_priority(1)
+            .mount(mock.server())
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/cards"))
+            .and(query_param("q", r"id:sv3pt5\-6"))
+            .respond_with(page(serde_json::json!([charizard.data])))
+            .with_priority(1)
+            .mount(mock.server())
+            .await;
+        let api = mock.client();
+
+        // the bundled mapping has MEW as "sv3.5", the API's set id is "sv3pt5"
+        let (deck, unresolved) = Deck::parse(&api, "Pokémon: 2\n2 Charizard ex MEW 006\n").await.unwrap();
+        assert!(unresolved.is_empty());
+        assert!(deck.total_cards() == 2 && deck.entries[0].card.id.as_deref() == Some("sv3pt5-6"));
+
+        let mut coded = deck.clone();
+        coded.entries[0].card.set = Some(set);
+        assert!(coded.to_ptcgl_string_with(api.set_codes()) == "Pokémon: 2\n2 Charizard ex MEW 6\n\nTotal Cards: 2\n");
+    }
+
+    fn fixture(id: &str, name: &str, supertype: &str, subtypes: &[&str]) -> Card {
+        let cwd = std::env::current_dir().unwrap();
+        let card: Card = serde_json::from_str(&std::fs::read_to_string(cwd.join("src/mock/card.json")).unwrap()).unwrap();
+
+        Card {
+            id: Some(id.parse().unwrap()),
+            name: Some(name.into()),
+            supertype: Some(supertype.into()),
+            subtypes: Some(subtypes.iter().map(|s| s.to_string()).collect()),
+            ..card
+        }
+    }
+
+    fn legal_deck() -> Deck {
+        let mut deck = Deck::default();
+        deck.add(fixture("swsh4-25", "Charizard", "Pokémon", &["Stage 2"]), 4);
+        deck.add(fixture("sve-2", "Basic Fire Energy", "Energy", &["Basic"]), 56);
+        deck
+    }
+
+    #[test]
+    fn test_validate_legal_deck() {
+        assert!(legal_deck().validate(Format::Standard).is_empty());
+    }
+
+    #[test]
+    fn test_validate_deck_size_and_copies() {
+        let mut deck = legal_deck();
+        deck.add(fixture("swsh4-26", "Charizard", "Pokémon", &["Stage 2"]), 1);
+
+        let violations = deck.validate(Format::Standard);
+        assert!(violations == vec![
+            Violation::DeckSize { count: 61 },
+            Violation::TooManyCopies { name: "Charizard".into(), count: 5 },
+        ]);
+    }
+
+    #[test]
+    fn test_validate_legality() {
+        let mut deck = legal_deck();
+        deck.entries[0].card.legalities.as_mut().unwrap().standard = None;
+
+        assert!(deck.validate(Format::Standard) == vec![Violation::NotLegal { id: "swsh4-25".into(), name: "Charizard".into() }]);
+        assert!(deck.validate(Format::Expanded).is_empty());
+    }
+
+    #[test]
+    fn test_validate_ace_spec_and_radiant() {
+        let mut deck = legal_deck();
+        deck.entries[1].count = 52;
+        deck.add(fixture("sv5-153", "Master Ball", "Trainer", &["Item", "ACE SPEC"]), 1);
+        deck.add(fixture("sv5-160", "Prime Catcher", "Trainer", &["Item", "ACE SPEC"]), 1);
+        deck.add(fixture("swsh10-46", "Radiant Greninja", "Pokémon", &["Basic", "Radiant"]), 2);
+
+        assert!(deck.validate(Format::Standard) == vec![
+            Violation::TooManyAceSpecs { count: 2 },
+            Violation::TooManyRadiants { count: 2 },
+        ]);
+    }
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn test_deck_yaml_round_trip() {
+        #[cfg_attr(feature = "strict-models", allow(unused_mut))]
+        let mut charizard = fixture("swsh4-25", "Charizard", "Pokémon", &["Stage 2"]);
+        #[cfg(not(feature = "strict-models"))]
+        charizard.extra.insert("futureField".into(), serde_json::json!({ "nested": [1, 2.5, null] }));
+        let mut deck = Deck::default();
+        deck.add(charizard, 3);
+        deck.add(fixture("swsh1-178", "Professor's Research", "Trainer", &["Supporter"]), 4);
+
+        let yaml = deck.to_yaml().unwrap();
+        assert!(Deck::from_yaml(&yaml).unwrap() == deck);
+
+        match Deck::from_yaml("entries:\n  - count: many\n") {
+            Err(Error::FailedParsingFile { line, .. }) => assert!(line == 2),
+            other => panic!("unexpected {other:?}"),
+        }
+    }
+}
@@ -0,0 +1,192 @@
+//! Field-level comparison of cards, e.g. to tell which cards of a set got new prices and which
+//! got errata between two downloads.
+//!
+//! ```ignore
+//! let diff = diff_sets(&yesterday, &api.search_cards("set.id:sv4").await?);
+//! for card in diff.changed.iter().filter(|c| c.only_prices()) {
+//!     println!("{} repriced", card.id);
+//! }
+//! ```
+
+use std::collections::{ HashMap, HashSet };
+
+use serde_json::{ Map, Value };
+
+use crate::cards::HasId;
+use crate::models::models::Card;
+
+/// Top-level fields holding prices, refreshed by the API every day.
+const PRICE_FIELDS: &[&str] = &["cardmarket", "tcgplayer"];
+
+/// Top-level fields of the game text printed on a card, changed by errata.
+const TEXT_FIELDS: &[&str] = &[
+    "abilities", "ancientTrait", "attacks", "convertedRetreatCost", "evolvesFrom", "flavorText", "hp", "level", "name",
+    "resistances", "retreatCost", "rules", "subtypes", "supertype", "types", "weaknesses",
+];
+
+/// What a changed field is about.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ChangeKind {
+    /// `tcgplayer` or `cardmarket`.
+    Price,
+    /// Game text such as attacks, abilities or HP.
+    Text,
+    /// Anything else, e.g. legalities, images or the rarity.
+    Other,
+}
+
+/// Field whose value differs between two cards, named as in the API's JSON, e.g. "attacks".
+/// Missing fields have a `Value::Null`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FieldChange {
+    pub field: String,
+    pub kind: ChangeKind,
+    pub old: Value,
+    pub new: Value,
+}
+
+/// Changes of the card with id between the two lists given to `diff_sets`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CardChanges {
+    pub id: String,
+    pub changes: Vec<FieldChange>,
+}
+
+impl CardChanges {
+    /// Whether only prices changed, the daily update of the API.
+    pub fn only_prices(&self) -> bool {
+        self.changes.iter().all(|c| c.kind == ChangeKind::Price)
+    }
+
+    /// Whether the game text changed, i.e. the card got errata.
+    pub fn has_errata(&self) -> bool {
+        self.changes.iter().any(|c| c.kind == ChangeKind::Text)
+    }
+}
+
+/// Outcome of `diff_sets`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SetDiff {
+    /// Cards of new whose id isn't in old, in the order of new.
+    pub added: Vec<Card>,
+    /// Cards of old whose id isn't in new, in the order of old.
+    pub removed: Vec<Card>,
+    /// Cards in both whose fields differ, in the order of new.
+    pub changed: Vec<CardChanges>,
+}
+
+impl SetDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+impl Card {
+    /// Fields of other that differ from those of this card, sorted by name; fields the models
+    /// don't know yet are compared as well.
+    pub fn diff(&self, other: &Card) -> Vec<FieldChange> {
+        let (old, new) = (fields(self), fields(other));
+        let names: HashSet<&String> = old.keys().chain(new.keys()).collect();
+        let mut names: Vec<&String> = names.into_iter().collect();
+        names.sort();
+
+        names.into_iter()
+             .filter_map(|name| {
+                 let (old, new) = (field(&old, name), field(&new, name));
+                 (old != new).then(|| FieldChange { field: name.clone(), kind: kind(name), old, new })
+             })
+             .collect()
+    }
+
+    /// Whether both cards print the same game text, whatever their set, number, artwork or
+    /// prices; e.g. a card and its reprints.
+    pub fn same_print_as(&self, other: &Card) -> bool {
+        self.diff(other).iter().all(|c| c.kind != ChangeKind::Text)
+    }
+}
+
+/// Cards added, removed and changed from old to new, matched on id. Cards without an id are
+/// left out.
+pub fn diff_sets(old: &[Card], new: &[Card]) -> SetDiff {
+    let old_by_id: HashMap<&str, &Card> = old.iter().filter_map(|c| Some((c.id()?, c))).collect();
+    let new_ids: HashSet<&str> = new.iter().filter_map(HasId::id).collect();
+    let mut diff = SetDiff::default();
+
+    for card in new {
+        let Some(id) = card.id() else { continue };
+        match old_by_id.get(id) {
+            None => diff.added.push(card.clone()),
+            Some(before) => {
+                let changes = before.diff(card);
+                if !changes.is_empty() {
+                    diff.changed.push(CardChanges { id: id.into(), changes });
+                }
+            },
+        }
+    }
+    diff.removed = old.iter().filter(|c| c.id().is_some_and(|id| !new_ids.contains(id))).cloned().collect();
+
+    diff
+}
+
+fn fields(card: &Card) -> Map<String, Value> {
+    match serde_json::to_value(card) {
+        Ok(Value::Object(fields)) => fields,
+        _ => Map::new(),
+    }
+}
+
+fn field(fields: &Map<String, Value>, name: &str) -> Value {
+    fields.get(name).cloned().unwrap_or(Value::Null)
+}
+
+fn kind(field: &str) -> ChangeKind {
+    if PRICE_FIELDS.contains(&field) {
+        ChangeKind::Price
+    } else if TEXT_FIELDS.contains(&field) {
+        ChangeKind::Text
+    } else {
+        ChangeKind::Other
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn venusaur() -> Card {
+        serde_json::from_str::<crate::Container<Card>>(include_str!("mock/xy1-1.json")).unwrap().data
+    }
+
+    #[test]
+    fn test_card_diff_and_set_diff() {
+        let old = venusaur();
+        assert!(old.diff(&old).is_empty());
+
+        let mut repriced = old.clone();
+        repriced.tcgplayer.as_mut().unwrap().updatedAt = Some("2099/01/01".into());
+        let mut errata = repriced.clone();
+        errata.attacks.as_mut().unwrap()[0].damage = Some("70".into());
+        let mut reprint = old.clone();
+        reprint.id = Some("g1-1".parse().unwrap());
+        reprint.number = Some("1".into());
+        reprint.set = None;
+
+        let changes = old.diff(&errata);
+        let fields: Vec<(&str, ChangeKind)> = changes.iter().map(|c| (c.field.as_str(), c.kind)).collect();
+        assert!(fields == vec![("attacks", ChangeKind::Text), ("tcgplayer", ChangeKind::Price)]);
+        assert!(old.same_print_as(&reprint) && old.same_print_as(&repriced) && !old.same_print_as(&errata));
+        assert!(old.diff(&reprint).iter().any(|c| c.field == "set" && c.new == Value::Null));
+
+        let mut added = old.clone();
+        added.id = Some("xy1-200".parse().unwrap());
+        let mut removed = old.clone();
+        removed.id = Some("xy1-0".parse().unwrap());
+
+        let diff = diff_sets(&[removed, old.clone()], &[repriced.clone(), added]);
+        assert!(diff.added.len() == 1 && diff.added[0].id.as_deref() == Some("xy1-200"));
+        assert!(diff.removed.len() == 1 && diff.removed[0].id.as_deref() == Some("xy1-0"));
+        assert!(diff.changed.len() == 1 && diff.changed[0].only_prices() && !diff.changed[0].has_errata());
+        assert!(diff_sets(&[old], &[errata]).changed[0].has_errata());
+    }
+}
@@ -0,0 +1,98 @@
+use std::collections::HashMap;
+
+use serde::{ Deserialize, Serialize };
+
+use crate::{ Client, Q };
+use crate::models::errors::Error;
+use crate::models::models::Card;
+
+/// Printings of the Pokémon a card evolves from and of those it evolves into.
+/// Stages the card doesn't have, e.g. `previous` for a Basic Pokémon, are empty.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct EvolutionLine {
+    /// Every printing named like the card's `evolvesFrom`.
+    pub previous: Vec<Card>,
+    /// Every printing named like any of the card's `evolvesTo`, e.g. all eight Eeveelutions.
+    pub next: Vec<Card>,
+}
+
+impl EvolutionLine {
+    /// Cards of `next` grouped by name, e.g. to list each evolution once.
+    pub fn next_by_name(&self) -> HashMap<String, Vec<&Card>> {
+        let mut groups = HashMap::<String, Vec<&Card>>::new();
+        for card in &self.next {
+            groups.entry(card.name.clone().unwrap_or_default()).or_default().push(card);
+        }
+        groups
+    }
+}
+
+impl Client {
+    /// Looks up the printings of the pre-evolution and evolutions of card by exact name, with at
+    /// most one request per stage.
+    pub async fn evolution_line(&self, card: &Card) -> Result<EvolutionLine, Error> {
+        let previous = match &card.evolvesFrom {
+            Some(name) => self.cards_named_exactly(&[name.as_str()]).await?,
+            None => Vec::new(),
+        };
+
+        let names: Vec<&str> = card.evolvesTo.iter().flatten().map(String::as_str).collect();
+        let next = self.cards_named_exactly(&names).await?;
+
+        Ok(EvolutionLine { previous, next })
+    }
+
+    async fn cards_named_exactly(&self, names: &[&str]) -> Result<Vec<Card>, Error> {
+        let Some(q) = names.iter().map(|name| Q::field("name").exact(name)).reduce(Q::or) else {
+            return Ok(Vec::new());
+        };
+
+        self.query::<Card>(q.into()).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wiremock::{ Mock, ResponseTemplate };
+    use wiremock::matchers::{ method, path, query_param };
+
+    use crate::testing::MockApi;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_evolution_line_by_name() {
+        let mock = MockApi::start().await;
+        Mock::given(method("GET"))
+            .and(path("/cards"))
+            .and(query_param("q", r#"!name:"M Venusaur-EX""#))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(
+                r#"{"data": [{"id": "xy1-2", "name": "M Venusaur-EX", "evolvesFrom": "Venusaur-EX"}], "page": 1, "pageSize": 250, "count": 1, "totalCount": 1}"#,
+                "application/json",
+            ))
+            .with_priority(1)
+            .expect(1)
+            .mount(mock.server())
+            .await;
+
+        let api = mock.client();
+        let venusaur = api.find_card(&"xy1-1".parse().unwrap()).await.unwrap().unwrap();
+        let line = api.evolution_line(&venusaur).await.unwrap();
+
+        assert!(line.previous.is_empty());
+        assert!(line.next.len() == 1);
+        assert!(line.next_by_name()["M Venusaur-EX"][0].id.as_deref() == Some("xy1-2"));
+    }
+
+    #[tokio::test]
+    async fn test_evolution_line_dead_end() {
+        let mock = MockApi::start().await;
+        let api = mock.client();
+        let card = Card { evolvesFrom: None, evolvesTo: None, ..api.find_card(&"xy1-1".parse().unwrap()).await.unwrap().unwrap() };
+
+        let line = api.evolution_line(&card).await.unwrap();
+
+        assert!(line.previous.is_empty() && line.next.is_empty());
+        assert!(mock.server().received_requests().await.unwrap().len() == 1);
+    }
+}
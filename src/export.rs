@@ -0,0 +1,385 @@
+//! Spreadsheet exports of cards and sets, enabled by the `csv` feature, along with the upload
+//! files of the TCGplayer and Cardmarket seller tools.
+//!
+//! ```ignore
+//! let file = std::fs::File::create("sv4.csv")?;
+//! export::to_csv(&cards, file, &[Column::Id, Column::Name, Column::Rarity, Column::MarketPrice])?;
+//!
+//! let report = export::tcgplayer_csv(&collection, &db, std::fs::File::create("tcgplayer.csv")?)?;
+//! for row in &report.skipped {
+//!     eprintln!("{} not listed: {:?}", row.entry.card_id, row.reason);
+//! }
+//! ```
+
+use std::io::Write;
+
+use crate::{ CardDatabase, CardPrice, Collection };
+use crate::collection::{ CollectionEntry, Condition };
+use crate::models::errors::Error;
+use crate::models::models::{ Card, PriceVariantKind, Set };
+
+/// Exportable field of a card. Missing values are written as empty cells and lists are joined
+/// with `|`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Column {
+    Id,
+    Name,
+    Supertype,
+    Subtypes,
+    Types,
+    Hp,
+    SetId,
+    SetName,
+    Number,
+    Rarity,
+    Artist,
+    NationalPokedexNumbers,
+    /// Lowest TCGPlayer market price among the printing variants, in US Dollars.
+    MarketPrice,
+    /// Cardmarket trend price, in Euros.
+    TrendPrice,
+}
+
+impl Column {
+    pub const ALL: &'static [Column] = &[
+        Column::Id, Column::Name, Column::Supertype, Column::Subtypes, Column::Types, Column::Hp,
+        Column::SetId, Column::SetName, Column::Number, Column::Rarity, Column::Artist,
+        Column::NationalPokedexNumbers, Column::MarketPrice, Column::TrendPrice,
+    ];
+
+    pub fn header(&self) -> &'static str {
+        match self {
+            Column::Id => "id",
+            Column::Name => "name",
+            Column::Supertype => "supertype",
+            Column::Subtypes => "subtypes",
+            Column::Types => "types",
+            Column::Hp => "hp",
+            Column::SetId => "set.id",
+            Column::SetName => "set.name",
+            Column::Number => "number",
+            Column::Rarity => "rarity",
+            Column::Artist => "artist",
+            Column::NationalPokedexNumbers => "nationalPokedexNumbers",
+            Column::MarketPrice => "tcgplayer.market",
+            Column::TrendPrice => "cardmarket.trendPrice",
+        }
+    }
+
+    fn value(&self, card: &Card) -> String {
+        let set = card.set.as_ref();
+
+        match self {
+            Column::Id => cell(&card.id),
+            Column::Name => cell(&card.name),
+            Column::Supertype => cell(&card.supertype),
+            Column::Subtypes => join(card.subtypes.iter().flatten()),
+            Column::Types => join(card.types.iter().flatten()),
+            Column::Hp => cell(&card.hp),
+            Column::SetId => cell(&set.and_then(|s| s.id.clone())),
+            Column::SetName => cell(&set.and_then(|s| s.name.clone())),
+            Column::Number => cell(&card.number),
+            Column::Rarity => cell(&card.rarity),
+            Column::Artist => cell(&card.artist),
+            Column::NationalPokedexNumbers => join(card.nationalPokedexNumbers.iter()),
+            Column::MarketPrice => cell(&CardPrice::from_card(card).tcgplayer_market),
+            Column::TrendPrice => cell(&CardPrice::from_card(card).cardmarket_trend),
+        }
+    }
+}
+
+/// Exportable field of a set.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SetColumn {
+    Id,
+    Name,
+    Series,
+    PrintedTotal,
+    Total,
+    PtcgoCode,
+    ReleaseDate,
+    UpdatedAt,
+}
+
+impl SetColumn {
+    pub const ALL: &'static [SetColumn] = &[
+        SetColumn::Id, SetColumn::Name, SetColumn::Series, SetColumn::PrintedTotal, SetColumn::Total,
+        SetColumn::PtcgoCode, SetColumn::ReleaseDate, SetColumn::UpdatedAt,
+    ];
+
+    pub fn header(&self) -> &'static str {
+        match self {
+            SetColumn::Id => "id",
+            SetColumn::Name => "name",
+            SetColumn::Series => "series",
+            SetColumn::PrintedTotal => "printedTotal",
+            SetColumn::Total => "total",
+            SetColumn::PtcgoCode => "ptcgoCode",
+            SetColumn::ReleaseDate => "releaseDate",
+            SetColumn::UpdatedAt => "updatedAt",
+        }
+    }
+
+    fn value(&self, set: &Set) -> String {
+        match self {
+            SetColumn::Id => cell(&set.id),
+            SetColumn::Name => cell(&set.name),
+            SetColumn::Series => cell(&set.series),
+            SetColumn::PrintedTotal => cell(&set.printedTotal),
+            SetColumn::Total => cell(&set.total),
+            SetColumn::PtcgoCode => cell(&set.ptcgoCode),
+            SetColumn::ReleaseDate => cell(&set.releaseDate),
+            SetColumn::UpdatedAt => cell(&set.updatedAt),
+        }
+    }
+}
+
+fn cell<T: ToString>(value: &Option<T>) -> String {
+    value.as_ref().map(|v| v.to_string()).unwrap_or_default()
+}
+
+fn join<T: ToString>(values: impl Iterator<Item = T>) -> String {
+    values.map(|v| v.to_string()).collect::<Vec<String>>().join("|")
+}
+
+/// Writes a header row and one row per card with the given columns.
+pub fn to_csv<W: Write>(cards: &[Card], writer: W, columns: &[Column]) -> Result<(), Error> {
+    write_rows(writer, b',', columns.iter().map(Column::header), cards.iter().map(|card| {
+        columns.iter().map(|c| c.value(card)).collect()
+    }))
+}
+
+/// Writes a header row and one row per set with the given columns.
+pub fn sets_to_csv<W: Write>(sets: &[Set], writer: W, columns: &[SetColumn]) -> Result<(), Error> {
+    write_rows(writer, b',', columns.iter().map(SetColumn::header), sets.iter().map(|set| {
+        columns.iter().map(|c| c.value(set)).collect()
+    }))
+}
+
+/// Why an entry of a collection has no row in a marketplace export.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SkipReason {
+    /// The card isn't in the database given.
+    UnknownCard,
+    /// The card's set has no name, which the marketplaces match products on.
+    MissingSetName,
+    /// Promos are listed apart from the sets by the marketplaces, with their own numbering.
+    Promo,
+}
+
+/// Entry of a collection left out of a marketplace export.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SkippedRow {
+    pub entry: CollectionEntry,
+    pub reason: SkipReason,
+}
+
+/// Outcome of `tcgplayer_csv` and `cardmarket_csv`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MarketplaceExport {
+    /// Amount of rows written, one per entry of the collection that could be mapped.
+    pub written: usize,
+    pub skipped: Vec<SkippedRow>,
+}
+
+/// Columns of the TCGplayer mass entry upload.
+const TCGPLAYER_HEADERS: &[&str] = &[
+    "Product Line", "Set Name", "Product Name", "Number", "Rarity", "Condition", "Add to Quantity", "TCG Marketplace Price",
+];
+
+/// Columns of the Cardmarket stock upload, which is separated by semicolons.
+const CARDMARKET_HEADERS: &[&str] = &["English Name", "Exp. Name", "Number", "Condition", "Foil?", "Amount", "Price", "Language"];
+
+/// Writes the collection as a TCGplayer mass entry upload, e.g. "Near Mint Holofoil" with the
+/// TCGPlayer market price of the variant; Mint copies are listed as Near Mint, which is
+/// TCGplayer's best condition. Entries that can't be mapped are in the report instead of the file.
+pub fn tcgplayer_csv<W: Write>(collection: &Collection, db: &CardDatabase, writer: W) -> Result<MarketplaceExport, Error> {
+    marketplace_csv(collection, db, writer, b',', TCGPLAYER_HEADERS, |entry, card, set_name| vec![
+        String::from("Pokemon"),
+        set_name.into(),
+        cell(&card.name),
+        cell(&card.number),
+        cell(&card.rarity),
+        format!("{}{}", tcgplayer_condition(entry.condition), tcgplayer_printing(&entry.variant)),
+        entry.quantity.to_string(),
+        cell(&card.market_price(&entry.variant)),
+    ])
+}
+
+/// Writes the collection as a Cardmarket stock upload in English, with Cardmarket's grades,
+/// e.g. "EX" for lightly played copies, and its trend price; reverse holos are the foils.
+/// Entries that can't be mapped are in the report instead of the file.
+pub fn cardmarket_csv<W: Write>(collection: &Collection, db: &CardDatabase, writer: W) -> Result<MarketplaceExport, Error> {
+    marketplace_csv(collection, db, writer, b';', CARDMARKET_HEADERS, |entry, card, set_name| {
+        let reverse = entry.variant == PriceVariantKind::ReverseHolofoil;
+        let prices = card.cardmarket.as_ref().and_then(|c| c.prices.as_ref());
+        let price = if reverse {
+            prices.and_then(|p| p.reverseHoloTrend.or(p.trendPrice))
+        } else {
+            prices.and_then(|p| p.trendPrice)
+        };
+
+        vec![
+            cell(&card.name),
+            set_name.into(),
+            cell(&card.number),
+            cardmarket_condition(entry.condition).into(),
+            if reverse { String::from("X") } else { String::new() },
+            entry.quantity.to_string(),
+            cell(&price),
+            String::from("English"),
+        ]
+    })
+}
+
+/// Writes a row for each entry whose card is listed by the marketplaces, reporting the others.
+fn marketplace_csv<W: Write>(
+    collection: &Collection,
+    db: &CardDatabase,
+    writer: W,
+    delimiter: u8,
+    headers: &'static [&'static str],
+    row: impl Fn(&CollectionEntry, &Card, &str) -> Vec<String>,
+) -> Result<MarketplaceExport, Error> {
+    let mut report = MarketplaceExport::default();
+    let mut rows = Vec::new();
+
+    for entry in &collection.entries {
+        match listed_card(db, entry) {
+            Ok((card, set_name)) => rows.push(row(entry, card, set_name)),
+            Err(reason) => report.skipped.push(SkippedRow { entry: entry.clone(), reason }),
+        }
+    }
+
+    report.written = rows.len();
+    write_rows(writer, delimiter, headers.iter().copied(), rows.into_iter())?;
+    Ok(report)
+}
+
+/// Card of entry along with the name of its set.
+fn listed_card<'a>(db: &'a CardDatabase, entry: &CollectionEntry) -> Result<(&'a Card, &'a str), SkipReason> {
+    let card = db.get(&entry.card_id).ok_or(SkipReason::UnknownCard)?;
+    let set_name = card.set.as_ref().and_then(|s| s.name.as_deref()).ok_or(SkipReason::MissingSetName)?;
+
+    if card.rarity.as_deref() == Some("Promo") || set_name.ends_with("Promos") {
+        return Err(SkipReason::Promo);
+    }
+    Ok((card, set_name))
+}
+
+fn tcgplayer_condition(condition: Condition) -> &'static str {
+    match condition {
+        Condition::Mint | Condition::NearMint => "Near Mint",
+        Condition::LightlyPlayed => "Lightly Played",
+        Condition::ModeratelyPlayed => "Moderately Played",
+        Condition::HeavilyPlayed => "Heavily Played",
+        Condition::Damaged => "Damaged",
+    }
+}
+
+/// Printing appended to the condition, nothing for normal printings.
+fn tcgplayer_printing(variant: &PriceVariantKind) -> String {
+    match variant {
+        PriceVariantKind::Normal => String::new(),
+        PriceVariantKind::Holofoil => String::from(" Holofoil"),
+        PriceVariantKind::ReverseHolofoil => String::from(" Reverse Holofoil"),
+        PriceVariantKind::FirstEditionNormal => String::from(" 1st Edition"),
+        PriceVariantKind::FirstEditionHolofoil => String::from(" 1st Edition Holofoil"),
+        PriceVariantKind::UnlimitedHolofoil => String::from(" Unlimited Holofoil"),
+        PriceVariantKind::Other(variant) => format!(" {variant}"),
+    }
+}
+
+fn cardmarket_condition(condition: Condition) -> &'static str {
+    match condition {
+        Condition::Mint => "MT",
+        Condition::NearMint => "NM",
+        Condition::LightlyPlayed => "EX",
+        Condition::ModeratelyPlayed => "GD",
+        Condition::HeavilyPlayed => "PL",
+        Condition::Damaged => "PO",
+    }
+}
+
+fn write_rows<W: Write>(writer: W, delimiter: u8, headers: impl Iterator<Item = &'static str>, rows: impl Iterator<Item = Vec<String>>) -> Result<(), Error> {
+    let failed = |e: csv::Error| Error::FailedWritingFile { reason: e.to_string() };
+    let mut csv = csv::WriterBuilder::new().delimiter(delimiter).from_writer(writer);
+
+    csv.write_record(headers).map_err(failed)?;
+    for row in rows {
+        csv.write_record(row).map_err(failed)?;
+    }
+
+    csv.flush().map_err(|e| Error::FailedWritingFile { reason: e.to_string() })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cards_to_csv() {
+        let cwd = std::env::current_dir().unwrap();
+        let card: Card = serde_json::from_str(&std::fs::read_to_string(cwd.join("src/mock/card.json")).unwrap()).unwrap();
+        let bare = Card { id: Some("xy1-0".parse().unwrap()), name: Some("Ho, \"Oh\"".into()), ..serde_json::from_str("{}").unwrap() };
+
+        let mut out = Vec::new();
+        to_csv(&[card, bare], &mut out, &[Column::Id, Column::Name, Column::Types, Column::SetName, Column::Hp, Column::TrendPrice]).unwrap();
+        let lines: Vec<&str> = std::str::from_utf8(&out).unwrap().lines().collect();
+
+        assert!(lines[0] == "id,name,types,set.name,hp,cardmarket.trendPrice");
+        assert!(lines[1].starts_with("swsh4-25,Charizard,Fire,Vivid Voltage,170,"));
+        assert!(lines[2] == "xy1-0,\"Ho, \"\"Oh\"\"\",,,,");
+    }
+
+    #[test]
+    fn test_sets_to_csv() {
+        let cwd = std::env::current_dir().unwrap();
+        let sets: crate::VecContainer<Set> = serde_json::from_str(&std::fs::read_to_string(cwd.join("src/mock/sets.json")).unwrap()).unwrap();
+
+        let mut out = Vec::new();
+        sets_to_csv(&sets.data, &mut out, SetColumn::ALL).unwrap();
+
+        assert!(String::from_utf8(out).unwrap().lines().nth(2) == Some("xy2,Flashfire,XY,106,110,FLF,2014/05/07,2020/08/14 09:35:00"));
+    }
+
+    #[test]
+    fn test_marketplace_csv() {
+        let cwd = std::env::current_dir().unwrap();
+        let charizard: Card = serde_json::from_str(&std::fs::read_to_string(cwd.join("src/mock/card.json")).unwrap()).unwrap();
+        let promo: Card = serde_json::from_value(serde_json::json!({
+            "id": "swshp-SWSH050", "name": "Charizard V", "number": "SWSH050", "set": { "id": "swshp", "name": "SWSH Black Star Promos" },
+        })).unwrap();
+        let nameless: Card = serde_json::from_value(serde_json::json!({ "id": "xy0-1", "set": { "id": "xy0" } })).unwrap();
+        let db = CardDatabase::new(vec![charizard, promo, nameless]);
+
+        let mut collection = Collection::new();
+        collection.add("swsh4-25", 2, PriceVariantKind::Normal, Condition::Mint);
+        collection.add("swsh4-25", 1, PriceVariantKind::ReverseHolofoil, Condition::LightlyPlayed);
+        collection.add("swshp-SWSH050", 1, PriceVariantKind::Holofoil, Condition::NearMint);
+        collection.add("xy0-1", 1, PriceVariantKind::Normal, Condition::NearMint);
+        collection.add("xy1-999", 1, PriceVariantKind::Normal, Condition::NearMint);
+
+        let mut out = Vec::new();
+        let report = tcgplayer_csv(&collection, &db, &mut out).unwrap();
+        let lines: Vec<&str> = std::str::from_utf8(&out).unwrap().lines().collect();
+
+        assert!(lines[0] == "Product Line,Set Name,Product Name,Number,Rarity,Condition,Add to Quantity,TCG Marketplace Price");
+        assert!(lines[1] == "Pokemon,Vivid Voltage,Charizard,25,Rare,Near Mint,2,2.82");
+        assert!(lines[2] == "Pokemon,Vivid Voltage,Charizard,25,Rare,Lightly Played Reverse Holofoil,1,3.89");
+        assert!(lines.len() == 3 && report.written == 2);
+        let skipped: Vec<(&str, SkipReason)> = report.skipped.iter().map(|s| (s.entry.card_id.as_str(), s.reason)).collect();
+        assert!(skipped == vec![
+            ("swshp-SWSH050", SkipReason::Promo), ("xy0-1", SkipReason::MissingSetName), ("xy1-999", SkipReason::UnknownCard),
+        ]);
+
+        let mut out = Vec::new();
+        let report = cardmarket_csv(&collection, &db, &mut out).unwrap();
+        let lines: Vec<&str> = std::str::from_utf8(&out).unwrap().lines().collect();
+
+        assert!(lines[0] == "English Name;Exp. Name;Number;Condition;Foil?;Amount;Price;Language");
+        assert!(lines[1] == "Charizard;Vivid Voltage;25;MT;;2;10.29;English");
+        assert!(lines[2] == "Charizard;Vivid Voltage;25;EX;X;1;10.29;English");
+        assert!(report.written == 2 && report.skipped.len() == 3);
+    }
+}
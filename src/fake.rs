@@ -0,0 +1,142 @@
+//! In-memory [`Query`] for the tests of code written against `dyn Query`, answering from fixtures
+//! instead of the API.
+//!
+//! ```ignore
+//! let api: Box<dyn Query + Send> = Box::new(FakeClient::new().with(vec![card]).with_search("name:pikachu", vec![pikachu]));
+//! let found = api.find::<Card>("xy1-1").await?;
+//! ```
+
+use std::collections::HashMap;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::{ Page, Query, QueryFuture, Resource };
+
+/// Fixtures by route, e.g. "cards", served by the [`Query`] methods. Searches answer with every
+/// fixture of the route, unless `with_search` registered the items of that search query. Paging
+/// follows the `page` and `pageSize` arguments like the API.
+#[derive(Clone, Debug, Default)]
+pub struct FakeClient {
+    items: HashMap<String, Vec<Value>>,
+    searches: HashMap<(String, String), Vec<Value>>,
+}
+
+impl FakeClient {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds items to the fixtures of their route.
+    ///
+    /// # Panics
+    /// When an item doesn't serialize to JSON, which the models always do.
+    pub fn with<T: Resource + Serialize>(self, items: Vec<T>) -> Self {
+        self.with_json(&T::path(), items.iter().map(to_json).collect())
+    }
+
+    /// Adds the JSON of items to the fixtures of the route path.
+    pub fn with_json(mut self, path: &str, items: Vec<Value>) -> Self {
+        self.items.entry(path.into()).or_default().extend(items);
+        self
+    }
+
+    /// Answers the search query q of the route of T with items, instead of every fixture.
+    ///
+    /// # Panics
+    /// Same as `with`.
+    pub fn with_search<T: Resource + Serialize>(mut self, q: &str, items: Vec<T>) -> Self {
+        self.searches.insert((T::path(), q.into()), items.iter().map(to_json).collect());
+        self
+    }
+
+    fn matching(&self, path: &str, args: &HashMap<String, String>) -> Vec<Value> {
+        let search = args.get("q").and_then(|q| self.searches.get(&(path.to_owned(), q.clone())));
+        search.or(self.items.get(path)).cloned().unwrap_or_default()
+    }
+}
+
+impl Query for FakeClient {
+    fn find_json<'a>(&'a self, path: &'a str, id: &'a str) -> QueryFuture<'a, Option<Value>> {
+        let found = self.items.get(path).and_then(|items| items.iter().find(|item| item["id"] == id)).cloned();
+        Box::pin(async move { Ok(found) })
+    }
+
+    fn page_json<'a>(&'a self, path: &'a str, args: HashMap<String, String>) -> QueryFuture<'a, Page<Value>> {
+        let items = self.matching(path, &args);
+        let total_count = items.len() as u32;
+        let page = arg(&args, "page").unwrap_or(1).max(1);
+        let page_size = arg(&args, "pageSize").unwrap_or(total_count.max(1)).max(1);
+
+        let skipped = u64::from(page - 1) * u64::from(page_size);
+        let data: Vec<Value> = items.into_iter().skip(usize::try_from(skipped).unwrap_or(usize::MAX)).take(page_size as usize).collect();
+        let found = Page { count: data.len() as u32, data, page, page_size, total_count };
+        Box::pin(async move { Ok(found) })
+    }
+
+    fn query_json<'a>(&'a self, path: &'a str, args: HashMap<String, String>) -> QueryFuture<'a, Vec<Value>> {
+        let items = self.matching(path, &args);
+        Box::pin(async move { Ok(items) })
+    }
+}
+
+fn arg(args: &HashMap<String, String>, name: &str) -> Option<u32> {
+    args.get(name).and_then(|v| v.parse().ok())
+}
+
+fn to_json<T: Serialize>(item: &T) -> Value {
+    serde_json::to_value(item).expect("models serialize to JSON")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ Client, QueryExt };
+    use crate::models::models::{ Card, Set, Type };
+    use crate::testing::MockApi;
+
+    use super::*;
+
+    fn card(id: &str, name: &str) -> Card {
+        serde_json::from_value(serde_json::json!({ "id": id, "name": name })).unwrap()
+    }
+
+    async fn names(api: &(dyn Query + Send)) -> Vec<String> {
+        api.all::<Card>().await.unwrap().into_iter().filter_map(|c| c.name).collect()
+    }
+
+    #[tokio::test]
+    async fn test_dyn_query_over_fake_and_client() {
+        let fake = FakeClient::new()
+            .with(vec![card("xy1-1", "Venusaur-EX"), card("xy1-2", "Weedle"), card("xy1-3", "Pinsir")])
+            .with_search("name:weedle", vec![card("xy1-2", "Weedle")])
+            .with_json("types", vec![serde_json::json!("Grass")]);
+
+        let found: Option<Card> = fake.find("xy1-3").await.unwrap();
+        assert!(found.and_then(|c| c.name) == Some("Pinsir".into()));
+        assert!(fake.find::<Set>("xy1").await.unwrap().is_none());
+
+        let args = HashMap::from([(String::from("q"), String::from("name:weedle"))]);
+        assert!(fake.query::<Card>(args).await.unwrap().len() == 1);
+        assert!(fake.all::<Type>().await.unwrap() == vec![Type("Grass".into())]);
+
+        let args = HashMap::from([(String::from("page"), String::from("2")), (String::from("pageSize"), String::from("2"))]);
+        let page = fake.search_page::<Card>(args).await.unwrap();
+        assert!(page.data.len() == 1 && page.page == 2 && page.total_count == 3);
+        assert!(fake.count::<Card>("").await.unwrap() == 3);
+
+        let mock = MockApi::start().await;
+        let apis: Vec<Box<dyn Query + Send>> = vec![Box::new(fake), Box::new(mock.client())];
+        let handle = tokio::spawn(async move {
+            let mut all = Vec::new();
+            for api in &apis {
+                all.push(names(api.as_ref()).await.len());
+            }
+            all
+        });
+        assert!(handle.await.unwrap() == vec![3, 4]);
+
+        let client: Client = mock.client();
+        let found = QueryExt::find::<Card>(&client, "xy1-1").await.unwrap().unwrap();
+        assert!(found.id.as_deref() == Some("xy1-1") && found.name == Some("Venusaur-EX".parse().unwrap()));
+    }
+}
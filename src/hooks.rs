@@ -0,0 +1,106 @@
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+use reqwest::header::HeaderMap;
+use reqwest::{ Method, StatusCode, Url };
+
+/// Request about to be sent, passed to the hooks given to `ClientBuilder::with_request_hook`;
+/// changes made to it are sent. The API Key is added after the hooks ran, so recording the
+/// requests doesn't leak it.
+#[derive(Clone, Debug)]
+pub struct RequestParts {
+    pub method: Method,
+    /// Full URL, query string included.
+    pub url: Url,
+    pub headers: HeaderMap,
+}
+
+/// Response received, passed to the hooks given to `ClientBuilder::with_response_hook` before
+/// its body is read.
+#[derive(Clone, Debug)]
+pub struct ResponseParts<'a> {
+    pub url: &'a Url,
+    pub status: StatusCode,
+    pub headers: &'a HeaderMap,
+    /// Time from sending the request until the headers of the response arrived.
+    pub elapsed: Duration,
+}
+
+const API_KEY_HEADER: &str = "x-api-key";
+
+type RequestHook = Arc<dyn Fn(&mut RequestParts) + Send + Sync>;
+type ResponseHook = Arc<dyn Fn(&ResponseParts) + Send + Sync>;
+
+/// Hooks shared by the clones of a Client, run in the order they were added.
+#[derive(Clone, Default)]
+pub(crate) struct Hooks {
+    request: Vec<RequestHook>,
+    response: Vec<ResponseHook>,
+}
+
+impl Hooks {
+    pub(crate) fn add_request(&mut self, hook: impl Fn(&mut RequestParts) + Send + Sync + 'static) {
+        self.request.push(Arc::new(hook));
+    }
+
+    pub(crate) fn add_response(&mut self, hook: impl Fn(&ResponseParts) + Send + Sync + 'static) {
+        self.response.push(Arc::new(hook));
+    }
+
+    pub(crate) fn on_request(&self, request: &mut impl HookedRequest) {
+        if self.request.is_empty() {
+            return;
+        }
+
+        let key = request.headers_mut().remove(API_KEY_HEADER);
+        let mut parts = RequestParts {
+            method: request.method_mut().clone(),
+            url: request.url_mut().clone(),
+            headers: std::mem::take(request.headers_mut()),
+        };
+        for hook in &self.request {
+            hook(&mut parts);
+        }
+
+        *request.method_mut() = parts.method;
+        *request.url_mut() = parts.url;
+        *request.headers_mut() = parts.headers;
+        if let Some(key) = key {
+            request.headers_mut().insert(API_KEY_HEADER, key);
+        }
+    }
+
+    pub(crate) fn on_response(&self, url: &Url, status: StatusCode, headers: &HeaderMap, elapsed: Duration) {
+        let parts = ResponseParts { url, status, headers, elapsed };
+        for hook in &self.response {
+            hook(&parts);
+        }
+    }
+}
+
+/// Requests of the async and blocking reqwest clients.
+pub(crate) trait HookedRequest {
+    fn method_mut(&mut self) -> &mut Method;
+    fn url_mut(&mut self) -> &mut Url;
+    fn headers_mut(&mut self) -> &mut HeaderMap;
+}
+
+impl HookedRequest for reqwest::Request {
+    fn method_mut(&mut self) -> &mut Method { self.method_mut() }
+    fn url_mut(&mut self) -> &mut Url { self.url_mut() }
+    fn headers_mut(&mut self) -> &mut HeaderMap { self.headers_mut() }
+}
+
+#[cfg(feature = "blocking")]
+impl HookedRequest for reqwest::blocking::Request {
+    fn method_mut(&mut self) -> &mut Method { self.method_mut() }
+    fn url_mut(&mut self) -> &mut Url { self.url_mut() }
+    fn headers_mut(&mut self) -> &mut HeaderMap { self.headers_mut() }
+}
+
+impl fmt::Debug for Hooks {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Hooks {{ request: {}, response: {} }}", self.request.len(), self.response.len())
+    }
+}
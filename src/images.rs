@@ -0,0 +1,378 @@
+use std::path::{ Path, PathBuf };
+
+use reqwest::header::{ CONTENT_LENGTH, CONTENT_TYPE };
+use tokio::sync::Semaphore;
+
+use crate::Client;
+use crate::models::errors::Error;
+use crate::models::models::{ Card, ImageSize, Set };
+
+/// Files written by `Client::download_set_images`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SetImagePaths {
+    pub symbol: PathBuf,
+    pub logo: PathBuf,
+}
+
+/// Outcome of `Client::prefetch_images`, each list in the order of the cards given.
+#[derive(Debug, Default)]
+pub struct PrefetchReport {
+    pub downloaded: Vec<PathBuf>,
+    /// Files already there, not downloaded again.
+    pub skipped: Vec<PathBuf>,
+    /// Id of the card and why its image couldn't be written, retries included.
+    pub failed: Vec<(String, Error)>,
+}
+
+enum Prefetched {
+    Downloaded(PathBuf),
+    Skipped(PathBuf),
+}
+
+impl Client {
+    /// Downloads the image at url, e.g. `card.images.large`, through the Client's own connection
+    /// pool, with its hooks and within its `max_response_size`. Images are served by a CDN
+    /// rather than the API, so they don't wait for the rate limit. Fails on non-200 responses and
+    /// on content types that aren't images.
+    pub async fn download_image(&self, url: &str) -> Result<Vec<u8>, Error> {
+        Ok(self.fetch_image(url).await?.1)
+    }
+
+    /// Content type and bytes of the image at url.
+    async fn fetch_image(&self, url: &str) -> Result<(String, Vec<u8>), Error> {
+        let resp = self.send_image_request(reqwest::Method::GET, url).await?;
+        let content_type = image_content_type(url, &resp)?;
+        let bytes = crate::read_bytes(resp, url, self.max_response_size).await?;
+
+        Ok((content_type, bytes))
+    }
+
+    /// Sends a request for the image at url through the hooks of the Client.
+    async fn send_image_request(&self, method: reqwest::Method, url: &str) -> Result<reqwest::Response, Error> {
+        let mut request = self.request(method, url).build()?;
+        self.hooks.on_request(&mut request);
+
+        let sent = crate::runtime::Instant::now();
+        let resp = self.client.execute(request).await?;
+        self.hooks.on_response(resp.url(), resp.status(), resp.headers(), sent.elapsed());
+        Ok(resp)
+    }
+
+    /// Downloads the card's artwork in the requested size.
+    pub async fn card_image(&self, card: &Card, size: ImageSize) -> Result<Vec<u8>, Error> {
+        let url = card.images
+                      .as_ref()
+                      .and_then(|images| images.url(size))
+                      .ok_or_else(|| Error::MissingImage { id: card.id.as_deref().unwrap_or_default().into() })?;
+
+        self.download_image(url).await
+    }
+
+    /// Downloads the images of cards in the requested size to dir, at most concurrency at a time,
+    /// as `{card_id}_{small|large}.{ext}` with the extension of the image URL. Files already
+    /// there are skipped; transient failures are retried as the retry policy allows. A card
+    /// failing doesn't stop the others, its error is in the report.
+    ///
+    /// # Errors
+    /// `FailedWritingFile` when dir can't be created.
+    pub async fn prefetch_images(&self, cards: &[Card], size: ImageSize, dir: &Path, concurrency: usize) -> Result<PrefetchReport, Error> {
+        std::fs::create_dir_all(dir).map_err(|e| Error::FailedWritingFile { reason: e.to_string() })?;
+
+        let semaphore = Semaphore::new(concurrency.max(1));
+        let results = futures::future::join_all(cards.iter().map(|card| async {
+            let _permit = semaphore.acquire().await.expect("the semaphore is never closed");
+            (card.id.as_deref().unwrap_or_default().to_owned(), self.prefetch_image(card, size, dir).await)
+        })).await;
+
+        let mut report = PrefetchReport::default();
+        for (id, result) in results {
+            match result {
+                Ok(Prefetched::Downloaded(path)) => report.downloaded.push(path),
+                Ok(Prefetched::Skipped(path)) => report.skipped.push(path),
+                Err(e) => report.failed.push((id, e)),
+            }
+        }
+
+        Ok(report)
+    }
+
+    async fn prefetch_image(&self, card: &Card, size: ImageSize, dir: &Path) -> Result<Prefetched, Error> {
+        let id = card.id.as_deref().ok_or(Error::MissingArgument { arg: "id".into() })?;
+        let url = card.images
+                      .as_ref()
+                      .and_then(|images| images.url(size))
+                      .ok_or_else(|| Error::MissingImage { id: id.into() })?;
+
+        let extension = url_extension(url).unwrap_or_else(|| "png".into());
+        let size_name = match size {
+            ImageSize::Small => "small",
+            ImageSize::Large => "large",
+        };
+        let path = dir.join(format!("{id}_{size_name}.{extension}"));
+        if path.exists() {
+            return Ok(Prefetched::Skipped(path));
+        }
+
+        let mut attempt = 0;
+        let bytes = loop {
+            match self.download_image(url).await {
+                Ok(bytes) => break bytes,
+                Err(e) if is_transient(&e) && self.retry.retry_transient && attempt < self.retry.max_retries => {
+                    crate::runtime::sleep(self.retry.backoff(attempt)).await;
+                    attempt += 1;
+                },
+                Err(e) => return Err(e),
+            }
+        };
+        std::fs::write(&path, bytes).map_err(|e| Error::FailedWritingFile { reason: e.to_string() })?;
+
+        Ok(Prefetched::Downloaded(path))
+    }
+
+    /// Writes the set's symbol and logo to dir as `{set_id}_symbol.{ext}` and
+    /// `{set_id}_logo.{ext}`, the extension following the content type, e.g. png. A file already
+    /// there with the size announced by the server is kept without downloading it again.
+    ///
+    /// # Errors
+    /// `MissingArgument` for a set without id, `MissingImage` when it lacks either URL and
+    /// `FailedWritingFile` when dir can't be written.
+    pub async fn download_set_images(&self, set: &Set, dir: &Path) -> Result<SetImagePaths, Error> {
+        let id = set.id.as_deref().ok_or(Error::MissingArgument { arg: "id".into() })?;
+        let missing = || Error::MissingImage { id: id.into() };
+        let images = set.images.as_ref().ok_or_else(missing)?;
+        let symbol = images.symbol.as_deref().ok_or_else(missing)?;
+        let logo = images.logo.as_deref().ok_or_else(missing)?;
+
+        std::fs::create_dir_all(dir).map_err(|e| Error::FailedWritingFile { reason: e.to_string() })?;
+
+        Ok(SetImagePaths {
+            symbol: self.save_image(symbol, &dir.join(format!("{id}_symbol"))).await?,
+            logo: self.save_image(logo, &dir.join(format!("{id}_logo"))).await?,
+        })
+    }
+
+    /// Writes the image at url to stem with the extension of its content type, unless a file
+    /// of the size given by a HEAD request is already there.
+    async fn save_image(&self, url: &str, stem: &Path) -> Result<PathBuf, Error> {
+        let head = self.send_image_request(reqwest::Method::HEAD, url).await?;
+        let content_type = image_content_type(url, &head)?;
+        let length = head.headers()
+                         .get(CONTENT_LENGTH)
+                         .and_then(|v| v.to_str().ok()?.parse::<u64>().ok());
+
+        let path = stem.with_extension(image_extension(&content_type));
+        let existing = std::fs::metadata(&path).ok().map(|m| m.len());
+        if length.is_some() && existing == length {
+            return Ok(path);
+        }
+
+        let (content_type, bytes) = self.fetch_image(url).await?;
+        let path = stem.with_extension(image_extension(&content_type));
+        std::fs::write(&path, bytes).map_err(|e| Error::FailedWritingFile { reason: e.to_string() })?;
+
+        Ok(path)
+    }
+}
+
+/// Failures worth downloading the image again for.
+fn is_transient(e: &Error) -> bool {
+    match e {
+        Error::Transport { .. } | Error::Timeout { .. } => true,
+        Error::ImageDownloadFailed { status, .. } => *status == 429 || *status >= 500,
+        _ => false,
+    }
+}
+
+/// Content type of a successful image response.
+fn image_content_type(url: &str, resp: &reqwest::Response) -> Result<String, Error> {
+    if !resp.status().is_success() {
+        return Err(Error::ImageDownloadFailed { url: url.into(), status: resp.status().as_u16() });
+    }
+
+    let content_type = resp.headers()
+                           .get(CONTENT_TYPE)
+                           .and_then(|v| v.to_str().ok())
+                           .unwrap_or_default()
+                           .to_owned();
+    if !content_type.starts_with("image/") {
+        return Err(Error::InvalidContentType { url: url.into(), content_type });
+    }
+
+    Ok(content_type)
+}
+
+/// Extension of the file at url, e.g. "png" for `.../1_hires.png?v=2`, without the query string.
+fn url_extension(url: &str) -> Option<String> {
+    let url = reqwest::Url::parse(url).ok()?;
+    Path::new(url.path()).extension()?.to_str().map(String::from)
+}
+
+/// File extension of an image content type, e.g. "jpg" for `image/jpeg`, "svg" for
+/// `image/svg+xml`.
+fn image_extension(content_type: &str) -> &str {
+    let subtype = content_type.split(';').next().unwrap_or_default().trim();
+    let subtype = subtype.strip_prefix("image/").unwrap_or(subtype);
+    match subtype.split('+').next().unwrap_or(subtype) {
+        "jpeg" => "jpg",
+        other => other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wiremock::{ Mock, MockServer, ResponseTemplate };
+    use wiremock::matchers::{ method, path };
+
+    use crate::models::models::Images;
+
+    use super::*;
+
+    fn card_with_images(server: &MockServer) -> Card {
+        let mut card: Card = serde_json::from_str(r#"{"id": "xy1-1"}"#).unwrap();
+        card.images = Some(Images {
+            small: Some(format!("{}/xy1/1.png", server.uri())),
+            large: Some(format!("{}/xy1/1_hires.png", server.uri())),
+        });
+        card
+    }
+
+    #[tokio::test]
+    async fn test_card_image_download() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/xy1/1_hires.png"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(b"\x89PNG".to_vec(), "image/png"))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/xy1/1.png"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw("<html></html>", "text/html"))
+            .mount(&server)
+            .await;
+
+        let api = Client::without_key();
+        let card = card_with_images(&server);
+
+        let bytes = api.card_image(&card, ImageSize::Large).await.unwrap();
+        assert!(bytes == b"\x89PNG");
+
+        let err = api.card_image(&card, ImageSize::Small).await.unwrap_err();
+        assert!(matches!(err, Error::InvalidContentType { content_type, .. } if content_type == "text/html"));
+
+        let hooked = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let counter = hooked.clone();
+        let limited = Client::builder()
+                             .max_response_size(3)
+                             .with_request_hook(move |_| { counter.fetch_add(1, std::sync::atomic::Ordering::Relaxed); })
+                             .build()
+                             .unwrap();
+        let err = limited.card_image(&card, ImageSize::Large).await.unwrap_err();
+        assert!(matches!(err, Error::ResponseTooLarge { limit: 3, .. }));
+        assert!(hooked.load(std::sync::atomic::Ordering::Relaxed) == 1);
+    }
+
+    #[tokio::test]
+    async fn test_image_download_errors() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&server)
+            .await;
+
+        let api = Client::without_key();
+
+        let err = api.download_image(&format!("{}/missing.png", server.uri())).await.unwrap_err();
+        assert!(matches!(err, Error::ImageDownloadFailed { status: 404, .. }));
+
+        let card: Card = serde_json::from_str(r#"{"id": "xy1-1"}"#).unwrap();
+        let err = api.card_image(&card, ImageSize::Small).await.unwrap_err();
+        assert!(matches!(err, Error::MissingImage { id } if id == "xy1-1"));
+    }
+
+    #[tokio::test]
+    async fn test_set_images_written_once() {
+        let server = MockServer::start().await;
+        for (route, body, content_type) in [("/sv4/symbol.png", &b"\x89PNG symbol"[..], "image/png"), ("/sv4/logo.png", &b"\xff\xd8 logo"[..], "image/jpeg")] {
+            Mock::given(method("HEAD"))
+                .and(path(route))
+                .respond_with(ResponseTemplate::new(200).set_body_raw(body.to_vec(), content_type))
+                .mount(&server)
+                .await;
+            Mock::given(method("GET"))
+                .and(path(route))
+                .respond_with(ResponseTemplate::new(200).set_body_raw(body.to_vec(), content_type))
+                .mount(&server)
+                .await;
+        }
+
+        let mut set: Set = serde_json::from_str(r#"{"id": "sv4"}"#).unwrap();
+        set.images = Some(crate::models::models::SetImages {
+            symbol: Some(format!("{}/sv4/symbol.png", server.uri())),
+            logo: Some(format!("{}/sv4/logo.png", server.uri())),
+        });
+        let dir = std::env::temp_dir().join(format!("pokemon_tcg_sdk_set_images_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let api = Client::without_key();
+        let paths = api.download_set_images(&set, &dir).await.unwrap();
+        assert!(paths == SetImagePaths { symbol: dir.join("sv4_symbol.png"), logo: dir.join("sv4_logo.jpg") });
+        assert!(std::fs::read(&paths.logo).unwrap() == b"\xff\xd8 logo");
+
+        std::fs::write(&paths.symbol, b"truncated").unwrap();
+        api.download_set_images(&set, &dir).await.unwrap();
+        let downloads = server.received_requests().await.unwrap().iter().filter(|r| r.method.as_str() == "GET").count();
+        let symbol = std::fs::read(&paths.symbol).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        // the logo was already complete, only the truncated symbol is downloaded again
+        assert!(downloads == 3);
+        assert!(symbol == b"\x89PNG symbol");
+
+        set.images = None;
+        assert!(matches!(api.download_set_images(&set, &dir).await, Err(Error::MissingImage { id }) if id == "sv4"));
+    }
+
+    #[test]
+    fn test_url_extension() {
+        assert!(url_extension("https://images.pokemontcg.io/xy1/1_hires.png?v=2").as_deref() == Some("png"));
+        assert!(url_extension("https://images.pokemontcg.io/xy1/1.jpg#top").as_deref() == Some("jpg"));
+        assert!(url_extension("https://images.pokemontcg.io/xy1/1").is_none() && url_extension("not a url.png").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_prefetch_images_skips_and_retries() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/xy1/2.png"))
+            .respond_with(ResponseTemplate::new(503))
+            .up_to_n_times(1)
+            .with_priority(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(b"\x89PNG".to_vec(), "image/png"))
+            .mount(&server)
+            .await;
+
+        let cards: Vec<Card> = (1..=3).map(|i| {
+            let mut card: Card = serde_json::from_value(serde_json::json!({ "id": format!("xy1-{i}") })).unwrap();
+            card.images = Some(Images { small: Some(format!("{}/xy1/{i}.png", server.uri())), large: None });
+            card
+        }).chain(std::iter::once(serde_json::from_str(r#"{"id": "xy1-4"}"#).unwrap())).collect();
+
+        let dir = std::env::temp_dir().join(format!("pokemon_tcg_sdk_prefetch_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("xy1-3_small.png"), b"cached").unwrap();
+
+        let api = Client::builder().retry_policy(crate::RetryPolicy::exponential(2, std::time::Duration::from_millis(1))).build().unwrap();
+        let report = api.prefetch_images(&cards, ImageSize::Small, &dir, 2).await.unwrap();
+        let retried = std::fs::read(dir.join("xy1-2_small.png")).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert!(report.downloaded == vec![dir.join("xy1-1_small.png"), dir.join("xy1-2_small.png")]);
+        assert!(report.skipped == vec![dir.join("xy1-3_small.png")]);
+        assert!(matches!(report.failed.as_slice(), [(id, Error::MissingImage { .. })] if id == "xy1-4"));
+        assert!(retried == b"\x89PNG");
+    }
+}
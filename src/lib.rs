@@ -1,303 +1,2645 @@
 pub mod models;
-use std::collections::HashMap;
+#[cfg(feature = "blocking")]
+pub mod blocking;
+pub mod booster;
+pub mod bulk;
+pub mod builder;
+pub mod cache;
+pub mod cards;
+mod cassette;
+pub mod collection;
+pub mod combat;
+pub mod database;
+pub mod deck;
+pub mod diff;
+pub mod evolution;
+#[cfg(feature = "csv")]
+pub mod export;
+pub mod fake;
+pub mod order;
+pub mod prices;
+pub mod q;
+pub mod retry;
+pub mod search;
+pub mod set_codes;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+mod hooks;
+mod images;
+mod progress;
+mod runtime;
+mod stream;
+mod throttle;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+mod trace;
+mod typed;
+use std::collections::{ BTreeMap, HashMap };
 use std::fmt::Debug;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+use futures::{ Stream, StreamExt };
+use reqwest::StatusCode;
 use serde::{ de::DeserializeOwned, Deserialize, Serialize };
 
-use models::models::{ Card, Set, Rarity, Subtype, Supertype, Type };
+use models::models::{ Card, CardSummary, EnergyType, KnownSubtype, Set, Rarity, Subtype, Supertype, Type };
+use models::errors::{ Error, ResponseMeta };
+pub use booster::PackTemplate;
+pub use bulk::{ BulkDownload, BulkMeta, BulkResult, Checkpoint, Strictness };
+pub use builder::ClientBuilder;
+pub use cache::{ Conditional, OfflineMode };
+pub use cards::HasId;
+pub use cassette::RecordMode;
+pub use collection::Collection;
+pub use database::CardDatabase;
+pub use deck::Deck;
+pub use evolution::EvolutionLine;
+pub use fake::FakeClient;
+pub use hooks::{ RequestParts, ResponseParts };
+pub use images::{ PrefetchReport, SetImagePaths };
+pub use order::OrderBy;
+pub use prices::CardPrice;
+pub use progress::Progress;
+pub use q::{ NameMatch, Q };
+pub use retry::RetryPolicy;
+pub use search::SearchBuilder;
+pub use set_codes::SetCodes;
+pub use stream::MAX_PREFETCH;
 
-pub const POKEMON_TCG_URL: &'static str = "https://api.pokemontcg.io/v2";
+pub const POKEMON_TCG_URL: &str = "https://api.pokemontcg.io/v2";
 
-///! Interacts with the Pokemon TCG IO API via a dev API Key.
-#[derive(Debug)]
+/// Env variable read by `Client::from_env`.
+pub const API_KEY_ENV_VAR: &str = "POKEMON_TCG_API_KEY";
+
+/// Env variables read by `ClientBuilder::from_env_config` besides `API_KEY_ENV_VAR`.
+pub const BASE_URL_ENV_VAR: &str = "POKEMON_TCG_BASE_URL";
+pub const TIMEOUT_ENV_VAR: &str = "POKEMON_TCG_TIMEOUT_SECS";
+pub const PAGE_SIZE_ENV_VAR: &str = "POKEMON_TCG_PAGE_SIZE";
+pub const CACHE_DIR_ENV_VAR: &str = "POKEMON_TCG_CACHE_DIR";
+
+/// Interacts with the Pokemon TCG IO API, optionally via a dev API Key.
+/// Clones share the connection pool and the response cache, and calls keep their state to
+/// themselves, so a Client can be cloned into as many tasks as needed:
+///
+/// ```
+/// # use pokemon_tcg_sdk_rs::Client;
+/// # use pokemon_tcg_sdk_rs::models::models::CardId;
+/// # use wiremock::{ Mock, MockServer, ResponseTemplate };
+/// # use wiremock::matchers::path;
+/// # #[tokio::main]
+/// # async fn main() {
+/// # let server = MockServer::start().await;
+/// # Mock::given(path("/cards/xy1-1"))
+/// #     .respond_with(ResponseTemplate::new(200).set_body_raw(r#"{"data": {"id": "xy1-1"}}"#, "application/json"))
+/// #     .mount(&server)
+/// #     .await;
+/// let api = Client::builder().base_url(server.uri()).build().unwrap();
+/// let id: CardId = "xy1-1".parse().unwrap();
+///
+/// let workers: Vec<_> = (0..4).map(|_| {
+///     let (api, id) = (api.clone(), id.clone());
+///     tokio::spawn(async move { api.find_card(&id).await })
+/// }).collect();
+///
+/// for worker in workers {
+///     assert!(worker.await.unwrap().unwrap().is_some());
+/// }
+/// # }
+/// ```
+#[derive(Clone, Debug)]
 pub struct Client {
     client: reqwest::Client,
-    key: String,
-    args: HashMap<String, String>,
+    /// Sent with every request when client was given by the caller along a `user_agent`, see
+    /// `with_http_client`.
+    user_agent: Option<reqwest::header::HeaderValue>,
+    key: Option<String>,
+    base_url: String,
+    retry: RetryPolicy,
+    cache: Option<Arc<cache::MemoryCache>>,
+    disk_cache: Option<cache::DiskCache>,
+    offline: OfflineMode,
+    bypass_cache: bool,
+    max_concurrent_pages: usize,
+    /// Overrides the builder's timeout, see `with_timeout`.
+    #[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+    timeout: Option<Duration>,
+    progress: Option<progress::ProgressCallback>,
+    allow_unknown_args: bool,
+    page_size: u32,
+    hooks: hooks::Hooks,
+    cassette: Option<cassette::Cassette>,
+    rate_limiter: Option<throttle::RateLimiter>,
+    set_codes: set_codes::SetCodes,
+    max_response_size: u64,
+    /// Retry budget and circuit breaker of the bulk fetch this Client was cloned for.
+    operation: Option<Arc<retry::Operation>>,
+    bulk_order_by: OrderBy,
 }
 
 impl Client {
-    ///! Creates a new instance with provided API Key
+    /// Creates a new instance with provided API Key and the default configuration.
+    ///
+    /// # Panics
+    /// Like `reqwest::Client::new`, panics when the TLS backend cannot be initialized;
+    /// use `Client::builder()` to handle that error instead.
     pub fn new(key: String) -> Self {
-        Self{
-            client: reqwest::Client::new(),
-            key: key,
-            args: HashMap::new(),
+        Self::builder().api_key(&key).build().expect("failed to initialize the HTTP client")
+    }
+
+    /// Creates a new instance that sends no API Key, subject to the API's keyless rate limit.
+    ///
+    /// # Panics
+    /// Same as `Client::new`.
+    pub fn without_key() -> Self {
+        Self::builder().build().expect("failed to initialize the HTTP client")
+    }
+
+    /// Creates a new instance with the API Key found in the `POKEMON_TCG_API_KEY` env variable.
+    pub fn from_env() -> Result<Self, Error> {
+        let key = std::env::var(API_KEY_ENV_VAR).map_err(|_| Error::ApiKeyNotFound)?;
+        Self::builder().api_key(&key).build()
+    }
+
+    /// Creates a new instance configured by the `POKEMON_TCG_*` env variables, see
+    /// `ClientBuilder::from_env_config`.
+    pub fn from_env_config() -> Result<Self, Error> {
+        ClientBuilder::from_env_config()?.build()
+    }
+
+    /// Creates a new instance sending its requests through http, e.g. an application's tuned
+    /// client, so that both share a connection pool; see `ClientBuilder::with_http_client`.
+    pub fn with_http_client(http: reqwest::Client, key: Option<String>) -> Self {
+        let builder = Self::builder().with_http_client(http);
+        let builder = match key {
+            Some(key) => builder.api_key(&key),
+            None => builder,
+        };
+
+        // nothing left to fail: the default User-Agent is valid and no cassette is opened
+        builder.build().expect("failed to initialize the Client")
+    }
+
+    /// Starts configuring a Client; see [`ClientBuilder`].
+    pub fn builder() -> ClientBuilder {
+        ClientBuilder::default()
+    }
+
+    /// Drops every response stored in the in-memory cache.
+    pub fn clear_cache(&self) {
+        if let Some(cache) = &self.cache {
+            cache.clear();
+        }
+    }
+
+    /// Mapping of set codes used by `card_by_set_code`, shared with the clones of this Client.
+    pub fn set_codes(&self) -> &SetCodes {
+        &self.set_codes
+    }
+
+    /// Client whose calls skip the cached responses and always reach the API; fresh responses are
+    /// still stored for the other calls, e.g. `api.uncached().find_card(&id)`.
+    pub fn uncached(&self) -> Client {
+        Client {
+            bypass_cache: true,
+            ..self.clone()
+        }
+    }
+
+    /// Client whose requests each get timeout instead of the one given to the builder, e.g.
+    /// `api.with_timeout(Duration::from_secs(2)).find_card(&id)` for a call that must answer
+    /// quickly. Fails with `Error::Timeout` once a request, retries aside, takes longer. Has no
+    /// effect on wasm32, where fetch has no timeout.
+    pub fn with_timeout(&self, timeout: Duration) -> Client {
+        Client {
+            timeout: Some(timeout),
+            ..self.clone()
+        }
+    }
+
+    /// Fetches every set from the API and stores the responses in the on-disk cache, so that
+    /// `all::<Set>()` keeps working with `OfflineMode::Only`. Ignores the offline mode.
+    pub async fn warm_cache_sets(&self) -> Result<Vec<Set>, Error> {
+        let online = Client {
+            offline: OfflineMode::Never,
+            bypass_cache: true,
+            ..self.clone()
+        };
+
+        online.all::<Set>().await
+    }
+
+    /// Body of a GET request to url, served from the in-memory cache while fresh, then from the
+    /// on-disk cache as the offline mode allows. Only successful responses are stored. Cached
+    /// bodies come with the meta of a 200 without headers, revalidated ones with the headers of
+    /// the 304 and a 200 status.
+    async fn fetch_body(&self, url: &str, query: &HashMap<String, String>) -> Result<(ResponseMeta, String), Error> {
+        #[cfg(feature = "tracing")]
+        {
+            use tracing::Instrument;
+            self.fetch_body_uninstrumented(url, query).instrument(trace::request_span(url, query)).await
+        }
+
+        #[cfg(not(feature = "tracing"))]
+        self.fetch_body_uninstrumented(url, query).await
+    }
+
+    async fn fetch_body_uninstrumented(&self, url: &str, query: &HashMap<String, String>) -> Result<(ResponseMeta, String), Error> {
+        let key = cache::key(url, query);
+
+        if !self.bypass_cache {
+            if let Some(body) = self.cache.as_ref().and_then(|cache| cache.get(&key)) {
+                trace::debug!(url = %key, "memory cache hit");
+                return Ok((ResponseMeta::cached(&key), body));
+            }
+
+            if self.offline != OfflineMode::Never {
+                if let Some(body) = self.disk_cache.as_ref().and_then(|disk| disk.get(&key)) {
+                    trace::debug!(url = %key, "disk cache hit");
+                    let meta = ResponseMeta::cached(&key);
+                    if let Some(cache) = &self.cache {
+                        let etag = self.disk_cache.as_ref().and_then(|disk| disk.revalidation(&key)).map(|(etag, _)| etag);
+                        cache.insert(key, body.clone(), etag);
+                    }
+                    return Ok((meta, body));
+                }
+            }
+        }
+
+        if let Some(recorded) = self.cassette.as_ref().map(|c| c.replay(url, query)).transpose()?.flatten() {
+            return Ok(recorded);
+        }
+
+        if self.offline == OfflineMode::Only {
+            return Err(Error::OfflineCacheMiss { url: key });
+        }
+
+        #[cfg(feature = "tracing")]
+        let started = runtime::Instant::now();
+
+        // a stale copy with an ETag is revalidated instead of downloaded again
+        let stale = self.cache.as_ref().and_then(|cache| cache.revalidation(&key))
+                        .or_else(|| self.disk_cache.as_ref().and_then(|disk| disk.revalidation(&key)));
+
+        let resp = self.send(url, query, stale.as_ref().map(|(etag, _)| etag.as_str())).await?;
+        let mut meta = ResponseMeta::new(resp.url().as_str(), resp.status(), resp.headers());
+        let etag = response_etag(&resp);
+
+        let (body, etag) = match stale {
+            Some((stale_etag, body)) if resp.status() == StatusCode::NOT_MODIFIED => {
+                trace::debug!(url = %key, "not modified");
+                meta.status = StatusCode::OK.as_u16();
+                (body, etag.or(Some(stale_etag)))
+            },
+            _ => (read_body(resp, url, self.max_response_size).await?, etag),
+        };
+        check_content_type(&meta, &body)?;
+
+        trace::debug!(status = meta.status, elapsed_ms = started.elapsed().as_millis() as u64, "response received");
+
+        if let Some(cassette) = &self.cassette {
+            cassette.record(url, query, meta.status, &body)?;
+        }
+
+        if (200..300).contains(&meta.status) {
+            if let Some(disk) = &self.disk_cache {
+                disk.insert(&key, &body, etag.as_deref());
+            }
+            if let Some(cache) = &self.cache {
+                cache.insert(key, body.clone(), etag);
+            }
+        }
+
+        Ok((meta, body))
+    }
+
+    /// Conditional GET of url, a full URL such as `https://api.pokemontcg.io/v2/sets/sv4`, for
+    /// callers keeping responses in their own storage: with the etag of the stored copy, the API
+    /// answers `Conditional::NotModified` when that copy is still current. Neither cache is used.
+    pub async fn fetch_if_modified(&self, url: &str, etag: Option<&str>) -> Result<Conditional, Error> {
+        let resp = self.send(url, &HashMap::new(), etag).await?;
+        if resp.status() == StatusCode::NOT_MODIFIED {
+            return Ok(Conditional::NotModified);
+        }
+
+        let meta = ResponseMeta::new(resp.url().as_str(), resp.status(), resp.headers());
+        let etag = response_etag(&resp);
+        let body = read_body(resp, url, self.max_response_size).await?;
+        check_status(&meta, &body)?;
+        check_content_type(&meta, &body)?;
+
+        Ok(Conditional::Modified { body, etag })
+    }
+
+    /// Sends a GET request to url. Requests answered with 429 Too Many Requests are sent again after
+    /// the wait requested by the API; connection errors, timeouts and 5xx responses are sent again
+    /// with exponential backoff, as far as the retry policy allows. Sending the ETag of a stored
+    /// copy as if_none_match makes the API answer 304 Not Modified while that copy is current.
+    /// Every attempt first waits for the rate limit, which doesn't count as a retry. Within a bulk
+    /// fetch, retries also come out of its retry budget and failures count towards its circuit
+    /// breaker.
+    async fn send(&self, url: &str, query: &HashMap<String, String>, if_none_match: Option<&str>) -> Result<reqwest::Response, Error> {
+        let operation = self.operation.as_deref();
+        let mut attempt = 0;
+
+        loop {
+            if let Some(operation) = operation {
+                operation.check()?;
+            }
+
+            let mut request = self.request(reqwest::Method::GET, url).query(&normalized_query(query));
+            if let Some(key) = &self.key {
+                request = request.header("X-Api-Key", key.as_str());
+            }
+            #[cfg(not(target_arch = "wasm32"))]
+            if let Some(timeout) = self.timeout {
+                request = request.timeout(timeout);
+            }
+            if let Some(etag) = if_none_match {
+                request = request.header(reqwest::header::IF_NONE_MATCH, etag);
+            }
+
+            let mut request = request.build().map_err(|e| transport_error(url, e))?;
+            self.hooks.on_request(&mut request);
+
+            trace::debug!(url = %cache::key(url, query), attempt, api_key = if self.key.is_some() { "[redacted]" } else { "none" }, "sending request");
+
+            if let Some(wait) = self.rate_limiter.as_ref().map(throttle::RateLimiter::reserve).filter(|w| !w.is_zero()) {
+                trace::debug!(wait_ms = wait.as_millis() as u64, "throttled by the rate limit");
+                runtime::sleep(wait).await;
+            }
+
+            let can_retry = || attempt < self.retry.max_retries && operation.is_none_or(retry::Operation::take_retry);
+            let sent = runtime::Instant::now();
+            let resp = match self.client.execute(request).await {
+                Ok(resp) => {
+                    self.hooks.on_response(resp.url(), resp.status(), resp.headers(), sent.elapsed());
+                    resp
+                },
+                Err(e) => {
+                    if let Some(operation) = operation {
+                        operation.failure()?;
+                    }
+                    if !(runtime::is_unreachable(&e) && self.retry.retry_transient && can_retry()) {
+                        return Err(transport_error(url, e));
+                    }
+
+                    let wait = self.retry.backoff(attempt);
+                    trace::debug!(error = %e, wait_ms = wait.as_millis() as u64, "retrying after transient error");
+                    runtime::sleep(wait).await;
+                    attempt += 1;
+                    continue;
+                },
+            };
+
+            let status = resp.status();
+            if status == StatusCode::TOO_MANY_REQUESTS {
+                let retry_after = self.retry.rate_limit_delay(resp.headers(), attempt);
+                if !self.retry.retry_on_rate_limit || !can_retry() {
+                    return Err(Error::RateLimited { retry_after, meta: ResponseMeta::new(resp.url().as_str(), status, resp.headers()) });
+                }
+
+                trace::debug!(wait_ms = retry_after.as_millis() as u64, "rate limited, retrying");
+                runtime::sleep(retry_after).await;
+            } else if status.is_server_error() {
+                if let Some(operation) = operation {
+                    operation.failure()?;
+                }
+                if !self.retry.retry_transient || !can_retry() {
+                    return Err(Error::ServerError { status: status.as_u16(), meta: ResponseMeta::new(resp.url().as_str(), status, resp.headers()) });
+                }
+
+                let wait = self.retry.backoff(attempt);
+                trace::debug!(status = status.as_u16(), wait_ms = wait.as_millis() as u64, "retrying after server error");
+                runtime::sleep(wait).await;
+            } else {
+                if let Some(operation) = operation {
+                    operation.success();
+                }
+                return Ok(resp);
+            }
+
+            attempt += 1;
+        }
+    }
+
+    /// Requests a single page of resource T with the given query parameters.
+    async fn fetch_page<T: Resource + DeserializeOwned>(&self, query: &HashMap<String, String>) -> Result<VecContainer<T>, Error> {
+        Ok(self.fetch_page_with_meta::<T>(&T::path(), query).await?.0)
+    }
+
+    /// Requests a single page of the route path, e.g. "cards", read as T.
+    async fn fetch_page_with_meta<T: DeserializeOwned>(&self, path: &str, query: &HashMap<String, String>) -> Result<(VecContainer<T>, ResponseMeta), Error> {
+        validate_args(query, self.allow_unknown_args)?;
+        let url: String = format!("{}/{path}", self.base_url);
+
+        let (meta, body) = self.fetch_body(&url, query).await?;
+        check_status(&meta, &body)?;
+        Ok((decode(&url, &body)?, meta))
+    }
+
+    /// Fetches every page of T matching args, starting at the `page` argument when given.
+    /// The query parameters belong to this call only, so a Client can be shared between tasks.
+    async fn fetch_all<T: Resource + DeserializeOwned>(&self, args: HashMap<String, String>) -> Result<Vec<T>, Error> {
+        Ok(self.fetch_bulk(args, Strictness::Strict).await?.items)
+    }
+
+    /// `fetch_all`, also telling whether every page could be fetched under `Strictness::Lenient`.
+    async fn fetch_bulk<T: Resource + DeserializeOwned>(&self, args: HashMap<String, String>, strictness: Strictness) -> Result<BulkResult<T>, Error> {
+        self.fetch_bulk_at(&T::path(), args, strictness, T::bulk_id).await
+    }
+
+    /// `fetch_bulk` of the route path read as T, requested in a stable order and dropping the
+    /// items whose id an earlier page had.
+    async fn fetch_bulk_at<T: DeserializeOwned>(&self, path: &str, args: HashMap<String, String>, strictness: Strictness, id: fn(&T) -> Option<&str>) -> Result<BulkResult<T>, Error> {
+        let args = with_bulk_order(with_page_size(args, self.page_size), path, &self.bulk_order_by);
+        let first_page = args.get("page").and_then(|p| p.parse().ok()).unwrap_or(1);
+        let args = &args;
+        let tracker = &progress::ProgressTracker::new(self.progress.clone());
+        let client = &Client { operation: self.retry.operation().map(Arc::new), ..self.clone() };
+
+        collect_pages(first_page, self.max_concurrent_pages, strictness, |page| {
+            let mut query = args.clone();
+            query.insert(String::from("page"), format!("{page}"));
+
+            async move {
+                let (container, _) = client.fetch_page_with_meta::<T>(path, &query).await?;
+                if let Some(operation) = &client.operation {
+                    operation.page_fetched();
+                }
+                tracker.page(page, &container);
+                Ok(container)
+            }
+        }).await.map(|result| result.dedup(args.get("orderBy").cloned(), id))
+    }
+
+    /// Starts a typed search for T; see [`SearchBuilder`].
+    pub fn search<T: Resource + DeserializeOwned + Clone + Debug>(&self) -> SearchBuilder<'_, T> {
+        SearchBuilder::new(self)
+    }
+
+    /// Like `find`, also returning the response the item was read from, e.g. for its
+    /// `x-ratelimit-remaining` header.
+    pub async fn find_with_meta<T: Identifiable + DeserializeOwned>(&self, id: &str) -> Result<(Option<T>, ResponseMeta), Error> {
+        self.find_meta(&format!("{}/{}/{id}", self.base_url, T::path())).await
+    }
+
+    /// Like `search_page`, also returning the response the page was read from.
+    pub async fn search_with_meta<T: Resource + DeserializeOwned>(&self, args: HashMap<String, String>) -> Result<(Page<T>, ResponseMeta), Error> {
+        let (container, meta) = self.fetch_page_with_meta::<T>(&T::path(), &args).await?;
+        Ok((container.into(), meta))
+    }
+
+    /// Every item of endpoint matching the search query q, read into any type instead of the
+    /// model of the endpoint, e.g. a struct of the few fields a listing shows.
+    ///
+    /// ```ignore
+    /// #[derive(Deserialize)]
+    /// struct CardView { id: String, name: String, number: String }
+    ///
+    /// let views: Vec<CardView> = api.search_as(Endpoint::Cards, "set.id:sv4").await?;
+    /// ```
+    pub async fn search_as<T: DeserializeOwned>(&self, endpoint: Endpoint, q: impl Into<String>) -> Result<Vec<T>, Error> {
+        let q = q.into();
+        let args = if q.is_empty() { HashMap::new() } else { HashMap::from([(String::from("q"), q)]) };
+        self.query_as(endpoint, args).await
+    }
+
+    /// `search_as` with every search parameter, e.g. `select` so the API only sends the fields
+    /// T reads.
+    pub async fn query_as<T: DeserializeOwned>(&self, endpoint: Endpoint, args: HashMap<String, String>) -> Result<Vec<T>, Error> {
+        Ok(self.fetch_bulk_at(endpoint.path(), args, Strictness::Strict, |_| None).await?.items)
+    }
+
+    /// Raw JSON of the route path with params, e.g. `get_json("cards/xy1-1", &HashMap::new())`,
+    /// for routes this crate doesn't model yet. Requests get the same API Key, base URL, cache and
+    /// retries as the typed methods; params aren't checked against the known ones.
+    ///
+    /// # Errors
+    /// `InvalidEndpoint` when path is empty, starts with a slash, is a full URL or carries a query
+    /// string, `..`, percent-encoded dots or slashes, or whitespace.
+    pub async fn get_json(&self, path: &str, params: &HashMap<String, String>) -> Result<serde_json::Value, Error> {
+        validate_path(path)?;
+        let url = format!("{}/{path}", self.base_url);
+
+        let (meta, body) = self.fetch_body(&url, params).await?;
+        check_status(&meta, &body)?;
+        decode(&url, &body)
+    }
+
+    /// Request to url carrying the User-Agent, which a client given by the caller doesn't set.
+    fn request(&self, method: reqwest::Method, url: &str) -> reqwest::RequestBuilder {
+        let request = self.client.request(method, url);
+        match &self.user_agent {
+            Some(user_agent) => request.header(reqwest::header::USER_AGENT, user_agent.clone()),
+            None => request,
+        }
+    }
+
+    async fn find_meta<T: DeserializeOwned>(&self, url: &str) -> Result<(Option<T>, ResponseMeta), Error> {
+        let (meta, body) = self.fetch_body(url, &HashMap::new()).await?;
+        if meta.status == StatusCode::NOT_FOUND.as_u16() {
+            return Ok((None, meta));
         }
+        check_status(&meta, &body)?;
+
+        let container: Container<T> = decode(url, &body)?;
+        Ok((Some(container.data), meta))
     }
 }
 
-trait Url {
+pub trait Resource {
     /// route used by the pokemontcg.io card database for: cards, subtypes, types, supertypes,
     /// energy, trainers, etc...
     fn path() -> String;
+
+    /// Id bulk fetches drop repeated items by; None for resources without one, e.g. types.
+    fn bulk_id(&self) -> Option<&str> {
+        None
+    }
+}
+
+/// Routes of the API, for reading results into types that don't implement `Resource`, see
+/// `Client::search_as`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Endpoint {
+    Cards,
+    Sets,
+    Types,
+    Subtypes,
+    Supertypes,
+    Rarities,
 }
 
-impl Url for Card {
+impl Endpoint {
+    pub fn path(self) -> &'static str {
+        match self {
+            Self::Cards => "cards",
+            Self::Sets => "sets",
+            Self::Types => "types",
+            Self::Subtypes => "subtypes",
+            Self::Supertypes => "supertypes",
+            Self::Rarities => "rarities",
+        }
+    }
+}
+
+/// Resources that can be looked up individually through `/{path}/{id}`; only cards and sets are.
+///
+/// ```compile_fail
+/// use pokemon_tcg_sdk_rs::{ Client, Query };
+/// use pokemon_tcg_sdk_rs::models::models::Type;
+///
+/// async fn lookup(api: Client) {
+///     let _ = api.find::<Type>("Colorless").await;
+/// }
+/// ```
+pub trait Identifiable: Resource {}
+
+impl Resource for Card {
+    fn path () -> String { "cards".into() }
+    fn bulk_id(&self) -> Option<&str> { self.id.as_deref() }
+}
+
+impl Identifiable for Card {}
+
+impl Resource for CardSummary {
     fn path () -> String { "cards".into() }
+    fn bulk_id(&self) -> Option<&str> { self.id.as_deref() }
+}
+
+impl Identifiable for CardSummary {}
+
+impl Resource for Type {
+    fn path() -> String { "types".into() }
 }
 
-impl Url for Type {
+/// Same route as Type, deserialized into the typed enum.
+impl Resource for EnergyType {
     fn path() -> String { "types".into() }
 }
 
-impl Url for Supertype {
+impl Resource for Supertype {
     fn path() -> String { "supertypes".into() }
 }
 
-impl Url for Subtype {
+impl Resource for Subtype {
+    fn path() -> String { "subtypes".into() }
+}
+
+/// Same route as Subtype, deserialized into the typed enum.
+impl Resource for KnownSubtype {
     fn path() -> String { "subtypes".into() }
 }
 
-impl Url for Set {
+impl Resource for Set {
     fn path() -> String { "sets".into() }
+    fn bulk_id(&self) -> Option<&str> { self.id.as_deref() }
 }
 
-impl Url for Rarity {
+impl Identifiable for Set {}
+
+impl Resource for Rarity {
     fn path() -> String {"rarities".into()}
 }
 
-///! Internal structure to simplify the Deserialization of incoming JSON responses.
+/// Internal structure to simplify the Deserialization of incoming JSON responses.
 #[derive(Serialize, Deserialize)]
 struct Container<U> {
     pub data: U,
 }
 
-///! Internal structure to simplify the Deserialization of incoming JSON responses.
+/// Internal structure to simplify the Deserialization of incoming JSON responses.
+/// Paged routes also report `page`, `pageSize`, `count` and `totalCount` next to `data`.
 #[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 struct VecContainer<U> {
     pub data: Vec<U>,
+    pub page: Option<u32>,
+    pub page_size: Option<u32>,
+    pub count: Option<u32>,
+    pub total_count: Option<u32>,
 }
 
-impl Query for Client {
-
-    /// Attempts to find a Type T by id; excludes: types, supertypes, subtypes and rarities.
-    async fn find<T: Url + DeserializeOwned + Clone>(&self, id: &str) -> Option<T> {
-        let _url: String = T::path();
+impl<U> VecContainer<U> {
+    /// True when no further page should be requested: the page came back empty, the envelope
+    /// reports that `totalCount` has been reached, or the route is not paged at all.
+    fn is_last_page(&self) -> bool {
+        if self.data.is_empty() {
+            return true;
+        }
 
-        match _url.as_str() {
-            "types" | "supertypes" | "subtypes" | "rarities" => { return None; }
-            _ => {},
+        match (self.page, self.page_size, self.total_count) {
+            (Some(page), Some(page_size), Some(total_count)) => u64::from(page) * u64::from(page_size) >= u64::from(total_count),
+            _ => true,
         }
+    }
+}
 
-        let url: String = format!("{POKEMON_TCG_URL}/{_url}/{id}").into();
-        let key = self.key.clone();
+/// Query parameters documented by the API; it silently ignores any other, e.g. `pagesize`.
+const KNOWN_ARGS: &[&str] = &["q", "page", "pageSize", "orderBy", "select"];
 
-        let response: Result<reqwest::Response, reqwest::Error> = self.client.get(url)
-                                                                             .header("X-Api-Key", format!("{key}"))
-                                                                             .header("User-Agent", "Mozilla/5.0")
-                                                                             .send().await;
-        
-        if let Ok(resp) =  response {
-            if let Ok(container) = resp.json::<Container<T>>().await {
-                return Some(container.data.clone());
-            }
-        };       
+/// Largest `pageSize` the API accepts.
+pub const MAX_PAGE_SIZE: u32 = 250;
 
-        None
-    }
+/// Bytes of a response body read at most unless `ClientBuilder::max_response_size` says
+/// otherwise: 20 MB, well above a page of 250 cards.
+pub const DEFAULT_MAX_RESPONSE_SIZE: u64 = 20 * 1024 * 1024;
 
-    /// Searches for T candidates that match args HashMap<String, String> and returns a Vec<T>. 
-    async fn _where<T: Url + DeserializeOwned + Clone + Debug>(&mut self, args: HashMap<String, String>) -> Vec<T> {
-        self.args.clear();
-        for(k, v) in args.iter() {
-            self.args.insert(k.clone(), v.clone());
-        }
+/// Fails with `InvalidArgument` on a parameter the API doesn't know, unless allow_unknown is set,
+/// and on a `page` or `pageSize` that isn't a positive integer or a `pageSize` above 250; the
+/// offending argument is given as `key` or `key=value`.
+fn validate_args(args: &HashMap<String, String>, allow_unknown: bool) -> Result<(), Error> {
+    for (key, value) in args {
+        let max = match key.as_str() {
+            "page" => u32::MAX,
+            "pageSize" => MAX_PAGE_SIZE,
+            k if allow_unknown || KNOWN_ARGS.contains(&k) => continue,
+            _ => return Err(Error::InvalidArgument { arg: key.clone() }),
+        };
 
-        self.all::<T>().await
+        if !value.parse::<u32>().is_ok_and(|n| (1..=max).contains(&n)) {
+            return Err(Error::InvalidArgument { arg: format!("{key}={value}") });
+        }
     }
-    
-    /// Returns a Vec<T> containing all expected members of type T within PokemonTCG API V2.
-    async fn all<T: Url + DeserializeOwned + Clone + Debug>(&mut self) -> Vec<T> {
-        let mut res = Vec::<T>::new();
-        let mut fetch_all = true;// TODO: Fix paging, it causes an infinite loop, should be true
 
-        let key = self.key.clone();
-        let u = T::path();
-        let url: String = format!("{POKEMON_TCG_URL}/{u}").into();
-        
-        if let Some(_) = self.args.get(&String::from("page")) {
-            fetch_all = true;
-        } else {
-             self.args.insert(String::from("page"), String::from("1"));
-        }
-
-        'paging: loop {
-            let response: Result<reqwest::Response, reqwest::Error> = if self.args.len() > 0 { 
-                self.client.get(&url).query(&self.args)
-                        .header("X-Api-Key", format!("{key}"))
-                        .header("User-Agent", "Mozilla/5.0")
-                        .send()
-                        .await
-            } else {
-                self.client.get(&url)
-                        .header("X-Api-Key", format!("{key}"))
-                        .header("User-Agent", "Mozilla/5.0")
-                        .send()
-                        .await
-            };
+    Ok(())
+}
 
-            if let Ok(resp) =  response {
-                
-                if let Ok(container) = resp.json::<VecContainer<T>>().await {
-                    
-                    for item in container.data {
-                        res.push(
-                            item.clone()
-                        );
-                    }
-                    
-                    if fetch_all {
-                        if let Some(val) = self.args.get_mut(&String::from("page")) {
-                            let mut num = val.parse().unwrap_or(0);
-                            num += 1;
-                            *val = String::from(format!("{num}"));
-                        } else {
-                            fetch_all = false;
-                        }
-                    } else {
-                        break 'paging;
-                    }
-                }
-            }else {
-                break 'paging;
-            };    
-        }
-        self.args.clear();// parameters for the query are cleared after each request is made...
-        res
-    }
+/// Query parameters in the form every request sends them: sorted by name, values without their
+/// surrounding whitespace. Equivalent arguments inserted in any order thus give the same URL, and
+/// the same cache key and cassette entry.
+pub(crate) fn normalized_query(args: &HashMap<String, String>) -> BTreeMap<&str, &str> {
+    args.iter().map(|(key, value)| (key.as_str(), value.trim())).collect()
 }
 
-/// Used to implement Query Logic for the Pokemon TCG v2 API.
-/// Url internal trait is used to implement the different subroutes for the types: Card, Rarity, Set, Type, Supertype and Subtype.
-pub trait Query {
-    async fn find<T: Url + DeserializeOwned + Clone>(&self, id: &str) -> Option<T>;
-    async fn _where<T: Url + DeserializeOwned + Clone + Debug>(&mut self, args: HashMap<String, String>) -> Vec<T>;
-    async fn all<T: Url + DeserializeOwned + Clone + Debug>(&mut self) -> Vec<T>; 
+/// Sends page_size as `pageSize` unless the call sets its own, so bulk downloads don't depend
+/// on the default of the API.
+fn with_page_size(mut args: HashMap<String, String>, page_size: u32) -> HashMap<String, String> {
+    args.entry(String::from("pageSize")).or_insert_with(|| format!("{page_size}"));
+    args
 }
 
-#[cfg(test)]
-mod tests {
-    use ntest::timeout;
-    use models::errors::Error;
+/// Routes whose results `orderBy` sorts.
+const ORDERED_ROUTES: &[&str] = &["cards", "sets"];
 
-    use super::*;
+/// Adds order_by to the args of a bulk fetch of the route path unless they have an `orderBy`, so
+/// that the results don't shift between pages.
+fn with_bulk_order(mut args: HashMap<String, String>, path: &str, order_by: &OrderBy) -> HashMap<String, String> {
+    if ORDERED_ROUTES.contains(&path) && !order_by.is_empty() {
+        args.entry(String::from("orderBy")).or_insert_with(|| order_by.to_string());
+    }
+    args
+}
 
-    #[tokio::test]
-    async fn test_find_card_by_id() {
-        let cwd = std::env::current_dir().unwrap();
-        let mocks = cwd.join("src/mock/xy1-1.json");
-        let mock_data_string = std::fs::read_to_string(mocks).unwrap();
-        let expected: Container<Card> = serde_json::from_str(mock_data_string.as_str()).unwrap();
+/// Arguments requesting a single item matching q, enough to read the `totalCount` of a search.
+fn count_args(q: &str) -> HashMap<String, String> {
+    let mut args = HashMap::from([(String::from("pageSize"), String::from("1"))]);
+    if !q.is_empty() {
+        args.insert(String::from("q"), q.into());
+    }
+    args
+}
 
-        let key = std::env::var("POKEMON_TCG_API_KEY").map_err(|_| { Error::ApiKeyNotFound }).unwrap();
-        let api = Client::new(key);
+/// Error body sent by the API along failed responses, `{"error": {"message", "code"}}`.
+#[derive(Deserialize)]
+struct ErrorBody {
+    error: ApiError,
+}
 
-        let card = api.find::<Card>("xy1-1".into()).await.unwrap();
+#[derive(Deserialize)]
+struct ApiError {
+    message: Option<String>,
+    code: Option<u16>,
+}
 
-        assert!(card.id == expected.data.id);
-    }
+/// Body of a response, either the expected T or an error body; the API answers some malformed
+/// q strings with a 200 carrying `{"error": ...}` instead of data.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ApiEnvelope<T> {
+    Error { error: ApiError },
+    Data(T),
+}
 
-    #[tokio::test]
-    async fn test_find_set_by_id() { 
-        let cwd = std::env::current_dir().unwrap();
-        let mocks = cwd.join("src/mock/xy1.json");
-        let mock_data_string = std::fs::read_to_string(mocks).unwrap();
-        let expected: Container<Set> = serde_json::from_str(mock_data_string.as_str()).unwrap();
+fn response_etag(resp: &reqwest::Response) -> Option<String> {
+    resp.headers().get(reqwest::header::ETAG)?.to_str().ok().map(String::from)
+}
 
-        let key = std::env::var("POKEMON_TCG_API_KEY").map_err(|_| { Error::ApiKeyNotFound }).unwrap();
-        let api = Client::new(key);
+/// `Error::from(e)`, pointing at url when reqwest doesn't know which URL failed.
+fn transport_error(url: &str, e: reqwest::Error) -> Error {
+    match Error::from(e) {
+        Error::Timeout { url: failed, source } if failed.is_empty() => Error::Timeout { url: url.into(), source },
+        Error::Transport { url: failed, source } if failed.is_empty() => Error::Transport { url: url.into(), source },
+        error => error,
+    }
+}
 
-        let set = api.find::<Set>("xy1".into()).await.unwrap();
+/// Body of resp to url as text, see `read_bytes`.
+async fn read_body(resp: reqwest::Response, url: &str, limit: u64) -> Result<String, Error> {
+    Ok(String::from_utf8_lossy(&read_bytes(resp, url, limit).await?).into_owned())
+}
 
-        assert!(set.id == expected.data.id);
-        assert!(set.name == expected.data.name);
+/// Body of resp to url, failing with `ResponseTooLarge` once it exceeds limit bytes instead of
+/// buffering the rest. On wasm32 the browser buffers the body first, so only a Content-Length
+/// above limit is rejected before it is downloaded.
+pub(crate) async fn read_bytes(resp: reqwest::Response, url: &str, limit: u64) -> Result<Vec<u8>, Error> {
+    if resp.content_length().is_some_and(|len| len > limit) {
+        return Err(Error::ResponseTooLarge { limit, url: url.into() });
     }
 
-    #[tokio::test]
-    #[timeout(1000)]
-    async fn test_fetch_all_types() {
-        let cwd = std::env::current_dir().unwrap();
-        let mocks = cwd.join("src/mock/types.json");
-        let mock_data_string = std::fs::read_to_string(mocks).unwrap();
-        let expected: VecContainer<Type> = serde_json::from_str(mock_data_string.as_str()).unwrap();
+    #[cfg(not(target_arch = "wasm32"))]
+    let body = {
+        let (mut resp, mut body) = (resp, Vec::new());
+        while let Some(chunk) = resp.chunk().await.map_err(|e| transport_error(url, e))? {
+            if (body.len() + chunk.len()) as u64 > limit {
+                return Err(Error::ResponseTooLarge { limit, url: url.into() });
+            }
+            body.extend_from_slice(&chunk);
+        }
+        body
+    };
 
-        let key = std::env::var("POKEMON_TCG_API_KEY").map_err(|_| { Error::ApiKeyNotFound }).unwrap();
-        let mut api = Client::new(key);
+    #[cfg(target_arch = "wasm32")]
+    let body = {
+        let body = resp.bytes().await.map_err(|e| transport_error(url, e))?;
+        if body.len() as u64 > limit {
+            return Err(Error::ResponseTooLarge { limit, url: url.into() });
+        }
+        body.to_vec()
+    };
 
-        let types = api.all::<Type>().await;
-        
-        assert!(types.len() == expected.data.len());
-        assert!(types.contains(&Type("Colorless".into())) == types.contains(&Type("Colorless".into())));
-    }
+    Ok(body)
+}
 
-    #[tokio::test]
-    #[timeout(1000)]
-    async fn test_fetch_all_subtypes() {
-        let cwd = std::env::current_dir().unwrap();
-        let mocks = cwd.join("src/mock/subtypes.json");
-        let mock_data_string = std::fs::read_to_string(mocks).unwrap();
-        let expected: VecContainer<Subtype> = serde_json::from_str(mock_data_string.as_str()).unwrap();
+/// Fails with `NotJson` on a successful response whose Content-Type is set to anything but JSON,
+/// e.g. `application/json` and `application/problem+json` pass. Responses without one and cached
+/// bodies pass as well.
+fn check_content_type(meta: &ResponseMeta, body: &str) -> Result<(), Error> {
+    let Some(content_type) = meta.header("content-type") else {
+        return Ok(());
+    };
 
-        let key = std::env::var("POKEMON_TCG_API_KEY").map_err(|_| { Error::ApiKeyNotFound }).unwrap();
-        let mut api = Client::new(key);
+    let essence = content_type.split(';').next().unwrap_or_default().trim().to_lowercase();
+    if !(200..300).contains(&meta.status) || essence.ends_with("/json") || essence.ends_with("+json") {
+        return Ok(());
+    }
+    Err(Error::not_json(&meta.url, content_type, body))
+}
 
-        let types = api.all::<Subtype>().await;
-        
-        assert!(types.len() == expected.data.len());
-        assert!(types.contains(&Subtype("Item".into())) == expected.data.contains(&Subtype("Item".into())));
-        assert!(types.contains(&Subtype("Supporter".into())) == expected.data.contains(&Subtype("Item".into())));
+/// Maps unsuccessful responses to the matching Error, keeping the message of the API.
+fn check_status(meta: &ResponseMeta, body: &str) -> Result<(), Error> {
+    if (200..300).contains(&meta.status) {
+        return Ok(());
     }
 
-    #[tokio::test]
-    #[timeout(1000)]
-    async fn test_fetch_all_supertypes() {
-        let cwd = std::env::current_dir().unwrap();
-        let mocks = cwd.join("src/mock/supertypes.json");
-        let mock_data_string = std::fs::read_to_string(mocks).unwrap();
-        let expected: VecContainer<Supertype> = serde_json::from_str(mock_data_string.as_str()).unwrap();
+    let message = serde_json::from_str::<ErrorBody>(body)
+        .ok()
+        .and_then(|b| b.error.message)
+        .unwrap_or(body.trim().into());
 
-        let key = std::env::var("POKEMON_TCG_API_KEY").map_err(|_| { Error::ApiKeyNotFound }).unwrap();
-        let mut api = Client::new(key);
+    let meta = meta.clone();
+    Err(match meta.status {
+        400 => Error::bad_request(message, meta),
+        402 => Error::PaymentRequired { meta },
+        403 => Error::Forbidden { meta },
+        404 => Error::NotFound { meta },
+        status @ 500..=599 => Error::ServerError { status, meta },
+        status => Error::UnexpectedStatus { status, message, meta },
+    })
+}
 
-        let types = api.all::<Supertype>().await;
-        
-        assert!(types.len() == expected.data.len());
-        assert!(types.contains(&Supertype("Trainer".into())));
-    }
+/// Rejects paths `get_json` would turn into another URL than a route of the API, along with the
+/// percent-encoded dots and slashes a server may decode into a traversal.
+fn validate_path(path: &str) -> Result<(), Error> {
+    let lower = path.to_ascii_lowercase();
+    let invalid = path.is_empty()
+        || path.starts_with('/')
+        || path.contains("://")
+        || path.split('/').any(|segment| segment.is_empty() || segment == "..")
+        || path.contains(['?', '#'])
+        || path.contains(char::is_whitespace)
+        || ["%2e", "%2f", "%5c"].iter().any(|encoded| lower.contains(encoded));
 
-    #[tokio::test]
-    async fn test_no_find_by_id_on_types() {    
-        let key = std::env::var("POKEMON_TCG_API_KEY").map_err(|_| { Error::ApiKeyNotFound }).unwrap();
-        let api = Client::new(key);
+    if invalid {
+        return Err(Error::InvalidEndpoint { url: path.into() });
+    }
+    Ok(())
+}
 
-        let types = api.find::<Type>("Colorless".into()).await;
-        assert!(types.is_none());
+/// Deserializes the JSON body of a response to url, failing with `Error::Api` on an error body.
+fn decode<T: DeserializeOwned>(url: &str, body: &str) -> Result<T, Error> {
+    match serde_json::from_str::<ApiEnvelope<T>>(body) {
+        Ok(ApiEnvelope::Data(data)) => Ok(data),
+        Ok(ApiEnvelope::Error { error }) => Err(Error::Api {
+            code: error.code,
+            message: error.message.unwrap_or_default(),
+        }),
+        // parsed again for the error of T itself, which untagged enums don't keep
+        Err(_) => serde_json::from_str(body).map_err(|e| Error::decode(url, body, e)),
     }
+}
 
-    #[tokio::test]
-    async fn test_no_find_by_id_on_subtypes() {
-        let key = std::env::var("POKEMON_TCG_API_KEY").map_err(|_| { Error::ApiKeyNotFound }).unwrap();
-        let api = Client::new(key);
+/// A single page of results together with the paging metadata reported by the API.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Page<T> {
+    pub data: Vec<T>,
+    pub page: u32,
+    pub page_size: u32,
+    pub count: u32,
+    pub total_count: u32,
+}
 
-        let subtypes = api.find::<Subtype>("Supporter".into()).await;
-        assert!(subtypes.is_none());
+impl<T> From<VecContainer<T>> for Page<T> {
+    /// Routes that are not paged (types, rarities...) return everything as a single page.
+    fn from(container: VecContainer<T>) -> Self {
+        let len = container.data.len() as u32;
+
+        Self {
+            page: container.page.unwrap_or(1),
+            page_size: container.page_size.unwrap_or(len),
+            count: container.count.unwrap_or(len),
+            total_count: container.total_count.unwrap_or(len),
+            data: container.data,
+        }
     }
+}
 
-    #[tokio::test]
-    async fn test_no_find_by_id_on_supertypes() {
-        let key = std::env::var("POKEMON_TCG_API_KEY").map_err(|_| { Error::ApiKeyNotFound }).unwrap();
-        let api = Client::new(key);
+/// Requests pages starting at `first_page` until the API reports there is nothing left to fetch.
+/// A failed page aborts the whole operation with its error under `Strictness::Strict`; under
+/// `Strictness::Lenient` the pages before it are returned as an incomplete result instead.
+///
+/// Once the first page reveals `totalCount` and `pageSize`, up to `concurrency` of the remaining
+/// pages are requested at a time; results are still returned in page order.
+async fn collect_pages<T, F, Fut>(first_page: u32, concurrency: usize, strictness: Strictness, mut fetch: F) -> Result<BulkResult<T>, Error>
+where
+    F: FnMut(u32) -> Fut,
+    Fut: Future<Output = Result<VecContainer<T>, Error>>,
+{
+    let fail = |items: Vec<T>, page: u32, error: Error| match strictness {
+        Strictness::Strict => Err(error),
+        Strictness::Lenient => Ok(BulkResult::partial(items, page, error)),
+    };
 
-        let supertypes = api.find::<Supertype>("Trainer".into()).await;
-        assert!(supertypes.is_none());
-    }
+    let mut page = first_page;
+    let first = match fetch(page).await {
+        Ok(first) => first,
+        Err(e) => return fail(Vec::new(), page, e),
+    };
+    let mut last_page = first.is_last_page();
+    let known_pages = match (first.page_size, first.total_count) {
+        (Some(page_size), Some(total_count)) if page_size > 0 => Some(total_count.div_ceil(page_size)),
+        _ => None,
+    };
+    let mut res = first.data;
 
-    #[tokio::test]
-    async fn test_no_find_by_id_on_rarities() {
-        let key = std::env::var("POKEMON_TCG_API_KEY").map_err(|_| { Error::ApiKeyNotFound }).unwrap();
-        let api = Client::new(key);
+    if let Some(final_page) = known_pages.filter(|_| concurrency > 1 && !last_page) {
+        let mut pending = std::pin::pin!(futures::stream::iter(page + 1..=final_page)
+            .map(|page| {
+                let fut = fetch(page);
+                async move { (page, fut.await) }
+            })
+            .buffer_unordered(concurrency));
+
+        // once a page failed, only the pages before it are still waited for
+        let mut pages = std::collections::BTreeMap::new();
+        let mut failure: Option<(u32, Error)> = None;
+        while let Some((fetched, container)) = pending.next().await {
+            match container {
+                Ok(container) => { pages.insert(fetched, container); },
+                Err(e) if strictness == Strictness::Strict => return Err(e),
+                Err(e) => if failure.as_ref().is_none_or(|(failed, _)| fetched < *failed) {
+                    failure = Some((fetched, e));
+                },
+            }
+
+            if let Some((failed, _)) = &failure {
+                if (page + 1..*failed).all(|p| pages.contains_key(&p)) {
+                    break;
+                }
+            }
+        }
 
-        let rarities = api.find::<Rarity>("Rare".into()).await;
-        assert!(rarities.is_none());
+        for (fetched, container) in pages {
+            if failure.as_ref().is_some_and(|(failed, _)| fetched > *failed) {
+                break;
+            }
+            page = fetched;
+            last_page = container.is_last_page();
+            res.extend(container.data);
+        }
+
+        if let Some((failed, e)) = failure {
+            return fail(res, failed, e);
+        }
+    }
+
+    // Sequential from here, also picking up pages added since totalCount was read.
+    while !last_page {
+        page += 1;
+        let container = match fetch(page).await {
+            Ok(container) => container,
+            Err(e) => return fail(res, page, e),
+        };
+        last_page = container.is_last_page();
+        res.extend(container.data);
+    }
+
+    Ok(BulkResult::complete(res))
+}
+
+impl Client {
+
+    /// Attempts to find a Type T by id; only available for identifiable resources (cards and sets).
+    /// Returns Ok(None) when the API does not know the id.
+    pub async fn find<T: Identifiable + DeserializeOwned + Clone>(&self, id: &str) -> Result<Option<T>, Error> {
+        let _url: String = T::path();
+        let url: String = format!("{}/{_url}/{id}", self.base_url);
+        Ok(self.find_meta(&url).await?.0)
+    }
+
+    /// Searches for T candidates that match args HashMap<String, String> and returns a Vec<T>.
+    /// A `select` argument such as `id,name,images` limits the fields returned; the others
+    /// deserialize as None.
+    pub async fn query<T: Resource + DeserializeOwned + Clone + Debug>(&self, args: HashMap<String, String>) -> Result<Vec<T>, Error> {
+        self.fetch_all::<T>(args).await
+    }
+
+    /// Former name of `query`, whose underscore only dodged the `where` keyword.
+    #[deprecated(note = "renamed to `query`")]
+    pub async fn _where<T: Resource + DeserializeOwned + Clone + Debug>(&self, args: HashMap<String, String>) -> Result<Vec<T>, Error> {
+        self.query(args).await
+    }
+    
+    /// Returns a Vec<T> containing all expected members of type T within PokemonTCG API V2.
+    pub async fn all<T: Resource + DeserializeOwned + Clone + Debug>(&self) -> Result<Vec<T>, Error> {
+        self.fetch_all::<T>(HashMap::new()).await
+    }
+
+    /// Fetches the single page of T matching args, along with the paging metadata from the API.
+    pub async fn search_page<T: Resource + DeserializeOwned + Clone + Debug>(&self, args: HashMap<String, String>) -> Result<Page<T>, Error> {
+        self.fetch_page::<T>(&args).await.map(Page::from)
+    }
+
+    /// Lazily yields every T matching args, requesting the next page only once the previous one has
+    /// been consumed so callers can stop early. The `pageSize` argument sets how many items are
+    /// requested at a time, `ClientBuilder::page_size` when absent. `SearchBuilder::prefetch`
+    /// streams requesting the next pages ahead instead.
+    pub fn stream<'a, T: Resource + DeserializeOwned + Clone + Debug + 'a>(&'a self, args: HashMap<String, String>) -> impl Stream<Item = Result<T, Error>> + 'a {
+        self.prefetching_stream(args, 0)
+    }
+
+    /// `stream` requesting up to prefetch pages ahead of the one being consumed.
+    pub(crate) fn prefetching_stream<'a, T: Resource + DeserializeOwned + Clone + Debug + 'a>(&'a self, args: HashMap<String, String>, prefetch: usize) -> impl Stream<Item = Result<T, Error>> + 'a {
+        let args = with_page_size(args, self.page_size);
+        let first_page = args.get("page").and_then(|p| p.parse().ok()).unwrap_or(1);
+        let tracker = Arc::new(progress::ProgressTracker::new(self.progress.clone()));
+
+        stream::page_stream(first_page, prefetch, move |page| {
+            let mut query = args.clone();
+            query.insert(String::from("page"), format!("{page}"));
+            let tracker = tracker.clone();
+
+            async move {
+                let container = self.fetch_page::<T>(&query).await?;
+                tracker.page(page, &container);
+                Ok(container)
+            }
+        })
+    }
+
+    /// Amount of T matching the search query q, every T when q is empty, read from the `totalCount`
+    /// of a single page holding one item.
+    pub async fn count<T: Resource + DeserializeOwned + Clone + Debug>(&self, q: &str) -> Result<u64, Error> {
+        let page = self.fetch_page::<T>(&count_args(q)).await?;
+        Ok(page.total_count.map(u64::from).unwrap_or(page.data.len() as u64))
+    }
+}
+
+impl Query for Client {
+    fn find_json<'a>(&'a self, path: &'a str, id: &'a str) -> QueryFuture<'a, Option<serde_json::Value>> {
+        Box::pin(async move { Ok(self.find_meta(&format!("{}/{path}/{id}", self.base_url)).await?.0) })
+    }
+
+    fn page_json<'a>(&'a self, path: &'a str, args: HashMap<String, String>) -> QueryFuture<'a, Page<serde_json::Value>> {
+        Box::pin(async move { Ok(self.fetch_page_with_meta(path, &args).await?.0.into()) })
+    }
+
+    fn query_json<'a>(&'a self, path: &'a str, args: HashMap<String, String>) -> QueryFuture<'a, Vec<serde_json::Value>> {
+        Box::pin(async move { Ok(self.fetch_bulk_at(path, args, Strictness::Strict, json_id).await?.items) })
+    }
+}
+
+fn json_id(item: &serde_json::Value) -> Option<&str> {
+    item.get("id")?.as_str()
+}
+
+/// Used to implement Query Logic for the Pokemon TCG v2 API.
+/// The Resource trait maps the types Card, Rarity, Set, Type, Supertype and Subtype to their subroutes; Identifiable
+/// marks the ones that can be looked up by id.
+///
+/// The trait is object safe: its methods work on the JSON of a route and return boxed futures, so
+/// code can hold a `Box<dyn Query + Send>` and be handed either a [`Client`] or a
+/// [`FakeClient`] serving fixtures. The typed `find`, `query`, `all`, `search_page` and `count`
+/// come from [`QueryExt`] for every implementation.
+///
+/// ```
+/// use pokemon_tcg_sdk_rs::{ FakeClient, Query, QueryExt };
+/// use pokemon_tcg_sdk_rs::models::models::Card;
+///
+/// async fn names(api: &(dyn Query + Send)) -> Vec<String> {
+///     api.all::<Card>().await.unwrap().into_iter().filter_map(|c| c.name).collect()
+/// }
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let api: Box<dyn Query + Send> = Box::new(FakeClient::new().with_json("cards", vec![serde_json::json!({ "id": "xy1-1", "name": "Venusaur-EX" })]));
+/// assert!(names(api.as_ref()).await == vec!["Venusaur-EX"]);
+/// # }
+/// ```
+///
+/// Implementations are shared between tasks, so they must be `Send + Sync`:
+///
+/// ```compile_fail
+/// use std::collections::HashMap;
+/// use std::rc::Rc;
+/// use pokemon_tcg_sdk_rs::{ Page, Query, QueryFuture };
+/// use pokemon_tcg_sdk_rs::models::errors::Error;
+///
+/// struct Local(Rc<()>);
+///
+/// impl Query for Local {
+///     fn find_json<'a>(&'a self, _: &'a str, _: &'a str) -> QueryFuture<'a, Option<serde_json::Value>> { todo!() }
+///     fn page_json<'a>(&'a self, _: &'a str, _: HashMap<String, String>) -> QueryFuture<'a, Page<serde_json::Value>> { todo!() }
+///     fn query_json<'a>(&'a self, _: &'a str, _: HashMap<String, String>) -> QueryFuture<'a, Vec<serde_json::Value>> { todo!() }
+/// }
+/// ```
+pub trait Query: Send + Sync {
+    /// Item of the route path with id, e.g. `find_json("cards", "xy1-1")`, None when there is none.
+    fn find_json<'a>(&'a self, path: &'a str, id: &'a str) -> QueryFuture<'a, Option<serde_json::Value>>;
+
+    /// Single page of the route path matching args.
+    fn page_json<'a>(&'a self, path: &'a str, args: HashMap<String, String>) -> QueryFuture<'a, Page<serde_json::Value>>;
+
+    /// Every item of the route path matching args, across pages.
+    fn query_json<'a>(&'a self, path: &'a str, args: HashMap<String, String>) -> QueryFuture<'a, Vec<serde_json::Value>>;
+}
+
+/// Future returned by the [`Query`] methods; not Send on wasm32, where fetching isn't.
+#[cfg(not(target_arch = "wasm32"))]
+pub type QueryFuture<'a, T> = futures::future::BoxFuture<'a, Result<T, Error>>;
+#[cfg(target_arch = "wasm32")]
+pub type QueryFuture<'a, T> = futures::future::LocalBoxFuture<'a, Result<T, Error>>;
+
+/// Typed methods of every [`Query`], `dyn Query` included, reading the JSON of its routes into the
+/// models. Client has inherent methods of the same names that skip the detour through JSON.
+#[allow(async_fn_in_trait)]
+pub trait QueryExt: Query {
+    async fn find<T: Identifiable + DeserializeOwned + Clone>(&self, id: &str) -> Result<Option<T>, Error> {
+        let path = T::path();
+        self.find_json(&path, id).await?.map(|value| from_json(&path, value)).transpose()
+    }
+
+    async fn query<T: Resource + DeserializeOwned + Clone + Debug>(&self, args: HashMap<String, String>) -> Result<Vec<T>, Error> {
+        let path = T::path();
+        self.query_json(&path, args).await?.into_iter().map(|value| from_json(&path, value)).collect()
+    }
+
+    /// Former name of `query`, whose underscore only dodged the `where` keyword.
+    #[deprecated(note = "renamed to `query`")]
+    async fn _where<T: Resource + DeserializeOwned + Clone + Debug>(&self, args: HashMap<String, String>) -> Result<Vec<T>, Error> {
+        QueryExt::query(self, args).await
+    }
+
+    async fn all<T: Resource + DeserializeOwned + Clone + Debug>(&self) -> Result<Vec<T>, Error> {
+        QueryExt::query(self, HashMap::new()).await
+    }
+
+    async fn search_page<T: Resource + DeserializeOwned + Clone + Debug>(&self, args: HashMap<String, String>) -> Result<Page<T>, Error> {
+        let path = T::path();
+        let page = self.page_json(&path, args).await?;
+        let data = page.data.into_iter().map(|value| from_json(&path, value)).collect::<Result<_, _>>()?;
+
+        Ok(Page { data, page: page.page, page_size: page.page_size, count: page.count, total_count: page.total_count })
+    }
+
+    async fn count<T: Resource + DeserializeOwned + Clone + Debug>(&self, q: &str) -> Result<u64, Error> {
+        Ok(self.page_json(&T::path(), count_args(q)).await?.total_count.into())
+    }
+}
+
+impl<Q: Query + ?Sized> QueryExt for Q {}
+
+/// Item of the route path read from its JSON.
+fn from_json<T: DeserializeOwned>(path: &str, value: serde_json::Value) -> Result<T, Error> {
+    serde_json::from_value(value.clone()).map_err(|e| Error::decode(path, &value.to_string(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::TryStreamExt;
+    use ntest::timeout;
+    use wiremock::{ Mock, MockServer, ResponseTemplate };
+    use wiremock::matchers::{ header, method, path, query_param };
+
+    use crate::models::models::{ CardId, EnergyCost, Era, SetId };
+    use crate::testing::MockApi;
+    use super::*;
+
+    /// Price written in the JSON as val, whichever type the `rust_decimal` feature selects.
+    pub(crate) fn price(val: &str) -> models::models::Price {
+        val.parse().unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_find_card_by_id() {
+        let cwd = std::env::current_dir().unwrap();
+        let mocks = cwd.join("src/mock/xy1-1.json");
+        let mock_data_string = std::fs::read_to_string(mocks).unwrap();
+        let expected: Container<Card> = serde_json::from_str(mock_data_string.as_str()).unwrap();
+
+        let mock = MockApi::start().await;
+        let api = mock.client();
+
+        let card = api.find::<Card>("xy1-1").await.unwrap().unwrap();
+
+        assert!(card.id == expected.data.id);
+    }
+
+    #[tokio::test]
+    async fn test_find_set_by_id() {
+        use models::models::Legality;
+
+        let cwd = std::env::current_dir().unwrap();
+        let mocks = cwd.join("src/mock/xy1.json");
+        let mock_data_string = std::fs::read_to_string(mocks).unwrap();
+        let expected: Container<Set> = serde_json::from_str(mock_data_string.as_str()).unwrap();
+
+        let mock = MockApi::start().await;
+        let api = mock.client();
+
+        let set = api.find::<Set>("xy1").await.unwrap().unwrap();
+
+        assert!(set.id == expected.data.id);
+        assert!(set.name == expected.data.name);
+        assert!(set.series == Some("XY".into()));
+        assert!(set.ptcgoCode == Some("XY".into()));
+        assert!(set.printedTotal == Some(146) && set.total == Some(146));
+        assert!(set.secret_cards() == 0);
+
+        let legalities = set.legalities.unwrap();
+        assert!(legalities.expanded == Some(Legality::Legal));
+        assert!(legalities.standard.is_none());
+
+        let flashfire = &mock.client().all::<Set>().await.unwrap()[1];
+        assert!(flashfire.ptcgoCode == Some("FLF".into()));
+        assert!(flashfire.secret_cards() == 4);
+    }
+
+    #[tokio::test]
+    #[timeout(1000)]
+    async fn test_fetch_all_types() {
+        let cwd = std::env::current_dir().unwrap();
+        let mocks = cwd.join("src/mock/types.json");
+        let mock_data_string = std::fs::read_to_string(mocks).unwrap();
+        let expected: VecContainer<Type> = serde_json::from_str(mock_data_string.as_str()).unwrap();
+
+        let mock = MockApi::start().await;
+        let api = mock.client();
+
+        let types = api.all::<Type>().await.unwrap();
+        
+        assert!(types.len() == expected.data.len());
+        assert!(types.contains(&Type("Colorless".into())) == types.contains(&Type("Colorless".into())));
+    }
+
+    #[tokio::test]
+    #[timeout(1000)]
+    async fn test_fetch_all_subtypes() {
+        let cwd = std::env::current_dir().unwrap();
+        let mocks = cwd.join("src/mock/subtypes.json");
+        let mock_data_string = std::fs::read_to_string(mocks).unwrap();
+        let expected: VecContainer<Subtype> = serde_json::from_str(mock_data_string.as_str()).unwrap();
+
+        let mock = MockApi::start().await;
+        let api = mock.client();
+
+        let types = api.all::<Subtype>().await.unwrap();
+        
+        assert!(types.len() == expected.data.len());
+        assert!(types.contains(&Subtype("Item".into())) == expected.data.contains(&Subtype("Item".into())));
+        assert!(types.contains(&Subtype("Supporter".into())) == expected.data.contains(&Subtype("Item".into())));
+    }
+
+    #[tokio::test]
+    #[timeout(1000)]
+    async fn test_fetch_all_supertypes() {
+        let cwd = std::env::current_dir().unwrap();
+        let mocks = cwd.join("src/mock/supertypes.json");
+        let mock_data_string = std::fs::read_to_string(mocks).unwrap();
+        let expected: VecContainer<Supertype> = serde_json::from_str(mock_data_string.as_str()).unwrap();
+
+        let mock = MockApi::start().await;
+        let api = mock.client();
+
+        let types = api.all::<Supertype>().await.unwrap();
+        
+        assert!(types.len() == expected.data.len());
+        assert!(types == vec![Supertype::Energy, Supertype::Pokemon, Supertype::Trainer]);
+    }
+
+    #[tokio::test]
+    #[ignore = "needs POKEMON_TCG_API_KEY and network access"]
+    async fn test_live_find_card_by_id() {
+        let api = Client::from_env().unwrap();
+
+        let card = api.find::<Card>("xy1-1").await.unwrap().unwrap();
+
+        assert!(card.name == Some("Venusaur-EX".into()));
+    }
+
+    #[tokio::test]
+    #[ignore = "needs POKEMON_TCG_API_KEY and network access"]
+    async fn test_live_find_set_by_id() {
+        let api = Client::from_env().unwrap();
+
+        let set = api.find::<Set>("xy1").await.unwrap().unwrap();
+
+        assert!(set.name == Some("XY".into()));
+    }
+
+    #[tokio::test]
+    #[ignore = "needs POKEMON_TCG_API_KEY and network access"]
+    async fn test_live_fetch_all_types() {
+        let api = Client::from_env().unwrap();
+
+        let types = api.all::<Type>().await.unwrap();
+
+        assert!(types.contains(&Type("Colorless".into())));
+    }
+
+    #[tokio::test]
+    #[timeout(1000)]
+    async fn test_paging_stops_on_empty_page() {
+        let cwd = std::env::current_dir().unwrap();
+        let mut requested = Vec::new();
+
+        let cards = collect_pages(1, 1, Strictness::Strict, |page| {
+            requested.push(page);
+            let mocks = cwd.join(format!("src/mock/cards_page_{page}.json"));
+
+            async move {
+                let mock_data_string = std::fs::read_to_string(mocks).map_err(|_| Error::FailedOpeningFile)?;
+                serde_json::from_str::<VecContainer<Card>>(mock_data_string.as_str()).map_err(Error::parsing_file)
+            }
+        }).await.unwrap().items;
+
+        assert!(cards.len() == 4);
+        assert!(requested == vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_last_page_when_total_count_reached() {
+        let cwd = std::env::current_dir().unwrap();
+        let mocks = cwd.join("src/mock/cards_page_2.json");
+        let mock_data_string = std::fs::read_to_string(mocks).unwrap();
+        let mut page: VecContainer<Card> = serde_json::from_str(mock_data_string.as_str()).unwrap();
+
+        assert!(!page.is_last_page());
+
+        page.total_count = Some(4);
+        assert!(page.is_last_page());
+
+        // pages up to u32::MAX are valid arguments, their product with the page size isn't a u32
+        page.total_count = Some(u32::MAX);
+        page.page = Some(u32::MAX / 2);
+        assert!(!page.is_last_page());
+        page.page = Some(u32::MAX);
+        page.page_size = Some(MAX_PAGE_SIZE);
+        assert!(page.is_last_page());
+    }
+
+    #[test]
+    fn test_page_metadata_from_envelope() {
+        let cwd = std::env::current_dir().unwrap();
+        let mocks = cwd.join("src/mock/cards_page_1.json");
+        let mock_data_string = std::fs::read_to_string(mocks).unwrap();
+        let container: VecContainer<Card> = serde_json::from_str(mock_data_string.as_str()).unwrap();
+
+        let page = Page::from(container);
+
+        assert!(page.data.len() == 2);
+        assert!(page.page == 1);
+        assert!(page.page_size == 2);
+        assert!(page.count == 2);
+        assert!(page.total_count == 6);
+    }
+
+    #[test]
+    fn test_client_is_send_and_sync() {
+        fn assert_send_sync<T: Send + Sync + Clone + 'static>() {}
+        fn assert_send<T: Send>(_: &T) {}
+        assert_send_sync::<Client>();
+
+        // futures of a Client can be spawned on a multi-threaded runtime
+        let api = Client::builder().build().unwrap();
+        assert_send(&api.all::<Card>());
+        assert_send(&api.find_card(&"xy1-1".parse().unwrap()));
+        assert_send(&api.stream::<Card>(HashMap::new()));
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_where_on_shared_client() {
+        let mock = MockApi::start().await;
+        let api = std::sync::Arc::new(mock.client());
+
+        let venusaurs = HashMap::from([
+            (String::from("q"), String::from("set.id:xy1")),
+            (String::from("page"), String::from("1")),
+        ]);
+        let weedles = HashMap::from([
+            (String::from("q"), String::from("set.id:xy1")),
+            (String::from("page"), String::from("2")),
+        ]);
+
+        let (first, second) = tokio::join!(
+            api.query::<Card>(venusaurs),
+            api.query::<Card>(weedles),
+        );
+        let (first, second) = (first.unwrap(), second.unwrap());
+
+        assert!(first[0].name == Some("Venusaur-EX".into()));
+        assert!(second[0].name == Some("Weedle".into()));
+        assert!(first.len() == 4 && second.len() == 2);
+    }
+
+    #[tokio::test]
+    #[ignore = "needs POKEMON_TCG_API_KEY and network access"]
+    async fn test_live_concurrent_where_on_shared_client() {
+        let api = std::sync::Arc::new(Client::from_env().unwrap());
+
+        let venusaurs = HashMap::from([
+            (String::from("q"), String::from("name:venusaur-ex set.id:xy1")),
+        ]);
+        let weedles = HashMap::from([
+            (String::from("q"), String::from("name:weedle set.id:xy1")),
+        ]);
+
+        let (first, second) = tokio::join!(
+            api.query::<Card>(venusaurs),
+            api.query::<Card>(weedles),
+        );
+        let (first, second) = (first.unwrap(), second.unwrap());
+
+        assert!(first.iter().all(|c| c.name == Some("Venusaur-EX".into())));
+        assert!(second.iter().all(|c| c.name == Some("Weedle".into())));
+        assert!(!first.is_empty() && !second.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_builder_custom_base_url_and_user_agent() {
+        let cwd = std::env::current_dir().unwrap();
+        let mocks = cwd.join("src/mock/xy1-1.json");
+        let mock_data_string = std::fs::read_to_string(mocks).unwrap();
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v2/cards/xy1-1"))
+            .and(header("X-Api-Key", "test-key"))
+            .and(header("User-Agent", "pokemon-tcg-sdk-tests/1.0"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(mock_data_string, "application/json"))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let api = Client::builder()
+                         .api_key("test-key")
+                         .base_url(format!("{}/v2/", server.uri()))
+                         .user_agent("pokemon-tcg-sdk-tests/1.0")
+                         .timeout(std::time::Duration::from_secs(5))
+                         .build()
+                         .unwrap();
+
+        let card = api.find::<Card>("xy1-1").await.unwrap().unwrap();
+
+        assert!(card.id.as_deref() == Some("xy1-1"));
+    }
+
+    #[tokio::test]
+    async fn test_builder_proxy_and_root_certificates() {
+        // the mock answers as the proxy, the base URL itself doesn't resolve
+        let mock = MockApi::start().await;
+        let api = Client::builder()
+                         .base_url("http://api.pokemontcg.invalid")
+                         .proxy(&mock.server().uri(), Some(("user", "pass")))
+                         .add_root_certificate(include_bytes!("mock/ca.pem"))
+                         .build()
+                         .unwrap();
+
+        assert!(api.find_card(&"xy1-1".parse().unwrap()).await.unwrap().is_some());
+        let requests = mock.server().received_requests().await.unwrap();
+        assert!(requests[0].url.host_str() == Some("api.pokemontcg.invalid"));
+        assert!(requests[0].headers.get("proxy-authorization").is_some_and(|h| h == "Basic dXNlcjpwYXNz"));
+
+        let invalid_cert = Client::builder().add_root_certificate(b"not a certificate").build();
+        assert!(matches!(invalid_cert, Err(Error::FailedBuildingClient { .. })));
+        let invalid_proxy = Client::builder().proxy("not a url", None).build();
+        assert!(matches!(invalid_proxy, Err(Error::FailedBuildingClient { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_user_provided_http_client() {
+        let mock = MockApi::start().await;
+        let headers = reqwest::header::HeaderMap::from_iter([(
+            reqwest::header::HeaderName::from_static("x-tuned"),
+            reqwest::header::HeaderValue::from_static("yes"),
+        )]);
+        let http = reqwest::Client::builder().default_headers(headers).user_agent("tuned/1.0").build().unwrap();
+
+        let api = Client::with_http_client(http.clone(), Some("test-key".into()));
+        let api = Client { base_url: mock.server().uri(), ..api };
+        assert!(api.find_card(&"xy1-1".parse().unwrap()).await.unwrap().is_some());
+
+        let api = Client::builder().base_url(mock.server().uri()).user_agent("deck-builder/2.0").with_http_client(http).build().unwrap();
+        assert!(api.find_card(&"xy1-1".parse().unwrap()).await.unwrap().is_some());
+
+        let requests = mock.server().received_requests().await.unwrap();
+        let header = |i: usize, name: &str| requests[i].headers.get(name).map(|h| h.to_str().unwrap().to_owned());
+        assert!(header(0, "x-tuned") == Some("yes".into()) && header(1, "x-tuned") == Some("yes".into()));
+        assert!(header(0, "x-api-key") == Some("test-key".into()) && header(1, "x-api-key").is_none());
+        assert!(header(0, "user-agent") == Some("tuned/1.0".into()));
+        assert!(header(1, "user-agent") == Some("deck-builder/2.0".into()));
+    }
+
+    #[tokio::test]
+    async fn test_client_without_key_sends_no_key_header() {
+        let cwd = std::env::current_dir().unwrap();
+        let mocks = cwd.join("src/mock/xy1-1.json");
+        let mock_data_string = std::fs::read_to_string(mocks).unwrap();
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/cards/xy1-1"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(mock_data_string, "application/json"))
+            .mount(&server)
+            .await;
+
+        let api = Client::builder().base_url(server.uri()).build().unwrap();
+        api.find::<Card>("xy1-1").await.unwrap().unwrap();
+
+        let requests = server.received_requests().await.unwrap();
+        assert!(requests.len() == 1);
+        assert!(!requests[0].headers.contains_key("X-Api-Key"));
+    }
+
+    #[test]
+    fn test_card_pricing_deserialization() {
+        use models::models::PriceVariantKind;
+
+        let cwd = std::env::current_dir().unwrap();
+        let mocks = cwd.join("src/mock/xy1-1.json");
+        let mock_data_string = std::fs::read_to_string(mocks).unwrap();
+        let card: Container<Card> = serde_json::from_str(mock_data_string.as_str()).unwrap();
+
+        let tcgplayer = card.data.tcgplayer.unwrap();
+        let prices = tcgplayer.prices.unwrap();
+        assert!(tcgplayer.updatedAt == Some("2025/01/08".into()));
+        assert!(!prices.contains_key(&PriceVariantKind::Normal));
+        assert!(prices[&PriceVariantKind::Holofoil].market == Some(price("3.47")));
+        assert!(prices[&PriceVariantKind::ReverseHolofoil].directLow == Some(price("4.5")));
+
+        let cardmarket = card.data.cardmarket.unwrap();
+        let prices = cardmarket.prices.unwrap();
+        assert!(cardmarket.url == Some("https://prices.pokemontcg.io/cardmarket/xy1-1".into()));
+        assert!(prices.trendPrice == Some(price("2.38")));
+        assert!(prices.avg30 == Some(price("9.06")));
+    }
+
+    #[tokio::test]
+    #[allow(deprecated)]
+    async fn test_deprecated_where_forwards_to_query() {
+        let mock = MockApi::start().await;
+        let api = mock.client();
+        let args = HashMap::from([(String::from("q"), String::from("name:Weedle"))]);
+
+        let old: Vec<Card> = api._where(args.clone()).await.unwrap();
+        let new: Vec<Card> = api.query(args).await.unwrap();
+        assert!(old.len() == 4 && old.iter().zip(&new).all(|(a, b)| a.id == b.id));
+    }
+
+    #[tokio::test]
+    async fn test_get_json_raw_routes() {
+        let mock = MockApi::start().await;
+        let api = mock.client();
+
+        let card = api.get_json("cards/xy1-1", &HashMap::new()).await.unwrap();
+        assert!(card["data"]["name"] == "Venusaur-EX");
+        let page = api.get_json("cards", &HashMap::from([("page".into(), "2".into()), ("madeUp".into(), "1".into())])).await.unwrap();
+        assert!(page["page"] == 2);
+        assert!(matches!(api.get_json("cards/xy1-999", &HashMap::new()).await, Err(Error::NotFound { .. })));
+
+        for path in ["", "/cards", "https://example.com/cards", "cards/../../admin", "cards?q=name:x", "cards//xy1-1", "set s",
+                     "cards/%2e%2e/admin", "cards/%2E%2E%2Fadmin", "cards%2f..%2fadmin"] {
+            assert!(matches!(api.get_json(path, &HashMap::new()).await, Err(Error::InvalidEndpoint { url }) if url == path));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_search_as_reads_user_types() {
+        #[derive(Deserialize)]
+        struct CardView {
+            id: String,
+            name: String,
+        }
+
+        let mock = MockApi::start().await;
+        let api = mock.client();
+
+        let views: Vec<CardView> = api.search_as(Endpoint::Cards, "name:Venusaur*").await.unwrap();
+        let ids: Vec<&str> = views.iter().map(|v| v.id.as_str()).collect();
+        assert!(ids == vec!["xy1-1", "xy1-2", "xy1-3", "xy1-4"] && views[0].name == "Venusaur-EX");
+
+        let args = HashMap::from([(String::from("select"), String::from("id,name"))]);
+        let views: Vec<CardView> = api.query_as(Endpoint::Cards, args).await.unwrap();
+        let requests = mock.server().received_requests().await.unwrap();
+        assert!(views.len() == 4);
+        assert!(requests.iter().any(|r| r.url.query().is_some_and(|q| q.contains("select=id%2Cname"))));
+        assert!(requests.iter().all(|r| r.url.path() == "/cards"));
+    }
+
+    #[cfg(feature = "rust_decimal")]
+    #[test]
+    fn test_decimal_prices_are_exact() {
+        use models::models::{ Price, PriceVariant };
+
+        let variant: PriceVariant = serde_json::from_str(r#"{"market": 10.99, "low": 0.1}"#).unwrap();
+        assert!(variant.market.unwrap().to_string() == "10.99");
+
+        let back: PriceVariant = serde_json::from_str(&serde_json::to_string(&variant).unwrap()).unwrap();
+        assert!(back.market == Some(price("10.99")));
+
+        // 0.1 added 30 times is 3.0000000000000013 as an f64
+        let sum: Price = std::iter::repeat_n(variant.low.unwrap(), 30).sum();
+        assert!(sum == price("3"));
+    }
+
+    #[test]
+    fn test_market_price_by_variant() {
+        use models::models::PriceVariantKind;
+
+        let card: Card = serde_json::from_str(r#"{"tcgplayer": {"prices": {
+            "reverseHolofoil": {"market": 4.1},
+            "1stEditionHolofoil": {"market": 120.5},
+            "holofoil": {"low": 2.0},
+            "prerelease": {"market": 8.25}
+        }}}"#).unwrap();
+
+        assert!(card.market_price(&PriceVariantKind::FirstEditionHolofoil) == Some(price("120.5")));
+        assert!(card.market_price(&PriceVariantKind::Other("prerelease".into())) == Some(price("8.25")));
+        assert!(card.market_price(&PriceVariantKind::Holofoil).is_none());
+        // the holofoil has no market price, the reverse holofoil is next in line
+        assert!(card.best_market_price() == Some((PriceVariantKind::ReverseHolofoil, price("4.1"))));
+
+        let promo: Card = serde_json::from_str(r#"{"tcgplayer": {"prices": {"staff": {"market": 30.0}, "prerelease": {"market": 8.25}}}}"#).unwrap();
+        assert!(promo.best_market_price() == Some((PriceVariantKind::Other("prerelease".into()), price("8.25"))));
+
+        let json = serde_json::to_value(&card.tcgplayer.unwrap().prices).unwrap();
+        assert!(json["1stEditionHolofoil"].get("market").is_some());
+    }
+
+    #[test]
+    fn test_legalities_deserialization() {
+        use models::models::{ Legalities, Legality };
+
+        let cwd = std::env::current_dir().unwrap();
+        let mocks = cwd.join("src/mock/card.json");
+        let mock_data_string = std::fs::read_to_string(mocks).unwrap();
+        let card: Card = serde_json::from_str(mock_data_string.as_str()).unwrap();
+
+        let legalities = card.legalities.unwrap();
+        assert!(legalities.standard == Some(Legality::Legal));
+        assert!(card.set.unwrap().legalities.unwrap().expanded == Some(Legality::Legal));
+
+        let legalities: Legalities = serde_json::from_str(r#"{"standard": "banned", "expanded": "Rotating"}"#).unwrap();
+        assert!(legalities.standard == Some(Legality::Banned));
+        assert!(legalities.expanded == Some(Legality::Other("Rotating".into())));
+        assert!(legalities.unlimited.is_none());
+        assert!(serde_json::to_string(&Legality::Banned).unwrap() == r#""Banned""#);
+    }
+
+    #[test]
+    fn test_card_attacks_abilities_weaknesses_resistances() {
+        let cwd = std::env::current_dir().unwrap();
+        let mocks = cwd.join("src/mock/xy1-1.json");
+        let mock_data_string = std::fs::read_to_string(mocks).unwrap();
+        let card: Container<Card> = serde_json::from_str(mock_data_string.as_str()).unwrap();
+
+        let attacks = card.data.attacks.unwrap();
+        assert!(attacks.len() == 2);
+        assert!(attacks[0].name == Some("Poison Powder".into()));
+        assert!(attacks[0].damage == Some("60".into()));
+        assert!(attacks[0].convertedEnergyCost == Some(3));
+        assert!(attacks[1].cost.as_ref().unwrap()[..2] == [EnergyType::Grass, EnergyType::Grass]);
+
+        let weaknesses = card.data.weaknesses.unwrap();
+        assert!(weaknesses[0].r#type == Some(EnergyType::Fire));
+        assert!(weaknesses[0].value == Some("×2".into()));
+        assert!(card.data.resistances.is_none());
+
+        let mocks = cwd.join("src/mock/card.json");
+        let mock_data_string = std::fs::read_to_string(mocks).unwrap();
+        let card: Card = serde_json::from_str(mock_data_string.as_str()).unwrap();
+        assert!(card.abilities.unwrap()[0].r#type == Some("Ability".into()));
+
+        let card: Card = serde_json::from_str(r#"{"resistances": [{"type": "Fighting", "value": "-30"}]}"#).unwrap();
+        let resistances = card.resistances.unwrap();
+        assert!(resistances[0].r#type == Some(EnergyType::Fighting));
+        assert!(resistances[0].value == Some("-30".into()));
+    }
+
+    #[test]
+    fn test_card_hp_and_retreat_cost() {
+        let cwd = std::env::current_dir().unwrap();
+        let mocks = cwd.join("src/mock/xy1-1.json");
+        let mock_data_string = std::fs::read_to_string(mocks).unwrap();
+        let card: Container<Card> = serde_json::from_str(mock_data_string.as_str()).unwrap();
+
+        assert!(card.data.hp_value() == Some(180));
+        assert!(card.data.converted_retreat_cost() == 4);
+        assert!(card.data.retreatCost.unwrap().iter().all(|t| *t == EnergyType::Colorless));
+
+        let promo: Card = serde_json::from_str(r#"{"hp": "60+", "retreatCost": ["Colorless"]}"#).unwrap();
+        assert!(promo.hp_value().is_none());
+        assert!(promo.hp == Some("60+".into()));
+        assert!(promo.converted_retreat_cost() == 1);
+
+        let trainer: Card = serde_json::from_str(r#"{"hp": "None"}"#).unwrap();
+        assert!(trainer.hp_value().is_none());
+        assert!(trainer.converted_retreat_cost() == 0);
+    }
+
+    #[test]
+    fn test_energy_type_conversions() {
+        let cwd = std::env::current_dir().unwrap();
+        let mocks = cwd.join("src/mock/card.json");
+        let mock_data_string = std::fs::read_to_string(mocks).unwrap();
+        let card: Card = serde_json::from_str(mock_data_string.as_str()).unwrap();
+
+        assert!(card.types == Some(vec![EnergyType::Fire]));
+        assert!("colorless".parse::<EnergyType>() == Ok(EnergyType::Colorless));
+        assert!("Plasma".parse::<EnergyType>() == Ok(EnergyType::Other("Plasma".into())));
+        assert!(EnergyType::Lightning.to_string() == "Lightning");
+        assert!(EnergyType::from(Type("Darkness".into())) == EnergyType::Darkness);
+        assert!(Type::from(EnergyType::Metal) == Type("Metal".into()));
+        assert!(serde_json::to_string(&EnergyType::Water).unwrap() == r#""Water""#);
+    }
+
+    #[tokio::test]
+    async fn test_known_subtypes() {
+        let mock = MockApi::start().await;
+        let subtypes = mock.client().all::<KnownSubtype>().await.unwrap();
+        let names = mock.client().all::<Subtype>().await.unwrap();
+
+        assert!(subtypes.len() == names.len() && !subtypes.iter().any(|s| matches!(s, KnownSubtype::Other(_))));
+        assert!(subtypes.iter().map(|s| Subtype::from(s.clone())).collect::<Vec<_>>() == names);
+
+        assert!("BASIC".parse::<KnownSubtype>() == Ok(KnownSubtype::Basic));
+        assert!("ex".parse::<KnownSubtype>() == Ok(KnownSubtype::ExLowercase) && "Ex".parse::<KnownSubtype>() == Ok(KnownSubtype::Ex));
+        assert!(KnownSubtype::from(Subtype("Stellar".into())) == KnownSubtype::Other("Stellar".into()));
+        assert!(KnownSubtype::PokemonTool.to_string() == "Pokémon Tool");
+
+        let card: Card = serde_json::from_str(r#"{"subtypes": ["Stage 2", "ex"]}"#).unwrap();
+        assert!(card.has_subtype(KnownSubtype::Stage2) && card.has_subtype(KnownSubtype::ExLowercase));
+        assert!(!card.has_subtype(KnownSubtype::Ex) && !card.has_subtype(KnownSubtype::Supporter));
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn test_set_dates() {
+        use chrono::{ NaiveDate, Timelike };
+
+        let cwd = std::env::current_dir().unwrap();
+        let mocks = cwd.join("src/mock/xy1-1.json");
+        let mock_data_string = std::fs::read_to_string(mocks).unwrap();
+        let card: Container<Card> = serde_json::from_str(mock_data_string.as_str()).unwrap();
+        let xy = card.data.set.unwrap();
+
+        assert!(xy.release_date() == NaiveDate::from_ymd_opt(2014, 2, 5));
+        assert!(xy.updated_at().unwrap().hour() == 10);
+
+        let mut sets: Vec<Set> = serde_json::from_str(r#"[
+            {"id": "swsh4", "releaseDate": "2020/11/13"},
+            {"id": "broken", "releaseDate": "someday", "updatedAt": "garbage"},
+            {"id": "xy1", "releaseDate": "2014/02/05"}
+        ]"#).unwrap();
+        sets.sort_by_key(|s| s.release_date());
+
+        let ids: Vec<_> = sets.iter().map(|s| s.id.clone().unwrap()).collect();
+        assert!(ids == ["broken", "xy1", "swsh4"]);
+        assert!(sets[0].updated_at().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_transient_errors_are_retried() {
+        let cwd = std::env::current_dir().unwrap();
+        let mocks = cwd.join("src/mock/xy1-1.json");
+        let mock_data_string = std::fs::read_to_string(mocks).unwrap();
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(503))
+            .up_to_n_times(2)
+            .with_priority(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/cards/xy1-1"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(mock_data_string, "application/json"))
+            .mount(&server)
+            .await;
+
+        let api = Client::builder()
+                         .base_url(server.uri())
+                         .retry_policy(RetryPolicy::exponential(3, std::time::Duration::from_millis(1)))
+                         .build()
+                         .unwrap();
+
+        let card = api.find::<Card>("xy1-1").await.unwrap().unwrap();
+
+        assert!(card.id.as_deref() == Some("xy1-1"));
+        assert!(server.received_requests().await.unwrap().len() == 3);
+    }
+
+    #[tokio::test]
+    async fn test_server_error_after_retries_exhausted() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(502))
+            .mount(&server)
+            .await;
+
+        let api = Client::builder()
+                         .base_url(server.uri())
+                         .retry_policy(RetryPolicy::exponential(2, std::time::Duration::from_millis(1)))
+                         .build()
+                         .unwrap();
+
+        let err = api.all::<Card>().await.unwrap_err();
+
+        assert!(matches!(err, Error::ServerError { status: 502, .. }));
+        assert!(server.received_requests().await.unwrap().len() == 3);
+    }
+
+    #[tokio::test]
+    async fn test_cached_find_does_not_hit_server_again() {
+        let cwd = std::env::current_dir().unwrap();
+        let mocks = cwd.join("src/mock/xy1-1.json");
+        let mock_data_string = std::fs::read_to_string(mocks).unwrap();
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/cards/xy1-1"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(mock_data_string, "application/json"))
+            .mount(&server)
+            .await;
+
+        let api = Client::builder()
+                         .base_url(server.uri())
+                         .cache_ttl(std::time::Duration::from_secs(3600))
+                         .build()
+                         .unwrap();
+
+        let first = api.find::<Card>("xy1-1").await.unwrap().unwrap();
+        let second = api.find::<Card>("xy1-1").await.unwrap().unwrap();
+        assert!(first.id == second.id);
+        assert!(server.received_requests().await.unwrap().len() == 1);
+
+        api.uncached().find::<Card>("xy1-1").await.unwrap().unwrap();
+        assert!(server.received_requests().await.unwrap().len() == 2);
+
+        api.clear_cache();
+        api.find::<Card>("xy1-1").await.unwrap().unwrap();
+        api.find::<Card>("xy1-1").await.unwrap().unwrap();
+        assert!(server.received_requests().await.unwrap().len() == 3);
+    }
+
+    #[tokio::test]
+    async fn test_offline_mode_uses_warmed_disk_cache() {
+        let dir = std::env::temp_dir().join(format!("pokemon_tcg_sdk_offline_{}", std::process::id()));
+        let cwd = std::env::current_dir().unwrap();
+        let mocks = cwd.join("src/mock/sets.json");
+        let mock_data_string = std::fs::read_to_string(mocks).unwrap();
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/sets"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(mock_data_string, "application/json"))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let api = Client::builder()
+                         .base_url(server.uri())
+                         .cache_dir(&dir)
+                         .offline_mode(OfflineMode::Only)
+                         .build()
+                         .unwrap();
+
+        assert!(matches!(api.all::<Set>().await, Err(Error::OfflineCacheMiss { .. })));
+
+        let warmed = api.warm_cache_sets().await.unwrap();
+        let offline = api.all::<Set>().await.unwrap();
+        assert!(warmed.len() == 2);
+        assert!(offline.len() == 2);
+        assert!(offline[0].id.as_deref() == Some("xy1"));
+
+        assert!(matches!(api.find::<Set>("xy2").await, Err(Error::OfflineCacheMiss { .. })));
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_count_reads_total_count() {
+        let cwd = std::env::current_dir().unwrap();
+        let mocks = cwd.join("src/mock/cards_page_1.json");
+        let mock_data_string = std::fs::read_to_string(mocks).unwrap();
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/cards"))
+            .and(query_param("pageSize", "1"))
+            .and(query_param("q", "set.id:xy1"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(mock_data_string, "application/json"))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let api = Client::builder().base_url(server.uri()).build().unwrap();
+        assert!(api.count::<Card>("set.id:xy1").await.unwrap() == 6);
+    }
+
+    #[tokio::test]
+    #[timeout(5000)]
+    async fn test_concurrent_pages_keep_page_order() {
+        let cwd = std::env::current_dir().unwrap();
+        let server = MockServer::start().await;
+
+        for page in 1..=3 {
+            let mock_data_string = std::fs::read_to_string(cwd.join(format!("src/mock/cards_page_{page}.json"))).unwrap();
+            let delay = if page == 2 { 300 } else { 0 };
+
+            Mock::given(method("GET"))
+                .and(path("/cards"))
+                .and(query_param("page", format!("{page}")))
+                .respond_with(ResponseTemplate::new(200)
+                              .set_body_raw(mock_data_string, "application/json")
+                              .set_delay(std::time::Duration::from_millis(delay)))
+                .expect(1)
+                .mount(&server)
+                .await;
+        }
+
+        let api = Client::builder().base_url(server.uri()).max_concurrent_pages(4).build().unwrap();
+        let cards = api.all::<Card>().await.unwrap();
+        let ids: Vec<String> = cards.into_iter().filter_map(|c| c.id.map(String::from)).collect();
+
+        assert!(ids == vec!["xy1-1", "xy1-2", "xy1-3", "xy1-4"]);
+    }
+
+    #[tokio::test]
+    async fn test_error_statuses_keep_api_message() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/cards"))
+            .and(query_param("q", "name:"))
+            .respond_with(ResponseTemplate::new(400).set_body_raw(r#"{"error": {"message": "Bad Request. Your request is either malformed, or is missing one or more required fields.", "code": 400}}"#, "application/json"))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/cards"))
+            .and(query_param("q", "paid"))
+            .respond_with(ResponseTemplate::new(402).set_body_raw(r#"{"error": {"message": "Payment Required", "code": 402}}"#, "application/json"))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/sets"))
+            .respond_with(ResponseTemplate::new(403)
+                .set_body_raw(r#"{"error": {"message": "Forbidden", "code": 403}}"#, "application/json")
+                .insert_header("X-RateLimit-Remaining", "0")
+                .insert_header("X-Api-Key", "secret"))
+            .mount(&server)
+            .await;
+
+        let api = Client::builder().base_url(server.uri()).build().unwrap();
+
+        match api.search_cards("name:").await {
+            Err(Error::BadRequest { message, meta }) => assert!(message.starts_with("Bad Request.") && meta.status == 400),
+            other => panic!("unexpected {other:?}"),
+        }
+        assert!(matches!(api.search_cards("paid").await, Err(Error::PaymentRequired { .. })));
+        assert!(matches!(api.all::<Rarity>().await, Err(Error::NotFound { .. })));
+
+        let forbidden = api.all::<Set>().await.unwrap_err();
+        let meta = forbidden.response_meta().unwrap();
+        assert!(matches!(forbidden, Error::Forbidden { .. }));
+        assert!(meta.status == 403 && meta.url.starts_with(&format!("{}/sets?", server.uri())));
+        assert!(meta.header("X-RateLimit-Remaining") == Some("0"));
+        assert!(meta.header("x-api-key").is_none() && !format!("{forbidden}").contains("secret"));
+        assert!(matches!(api.find::<Card>("xy1-999").await, Ok(None)));
+    }
+
+    #[test]
+    #[cfg(not(feature = "strict-models"))]
+    fn test_unknown_fields_are_kept() {
+        let json = r#"{"id": "dp1-1", "name": "Dialga", "stamp": "Prerelease", "set": {"id": "dp1", "logo": {"scale": 2}}}"#;
+        let card: Card = serde_json::from_str(json).unwrap();
+
+        assert!(card.extra["stamp"] == "Prerelease");
+        assert!(card.set.as_ref().unwrap().extra["logo"]["scale"] == 2);
+        assert!(!card.extra.contains_key("name"));
+
+        let round_trip: serde_json::Value = serde_json::to_value(&card).unwrap();
+        assert!(round_trip["stamp"] == "Prerelease" && round_trip["set"]["logo"]["scale"] == 2);
+    }
+
+    #[test]
+    #[cfg(feature = "strict-models")]
+    fn test_strict_models_reject_unknown_fields() {
+        let nested = r#"{"id": "dp1-1", "attacks": [{"name": "Roar of Time", "energyCostText": "MMC"}]}"#;
+        assert!(serde_json::from_str::<Card>(nested).is_err());
+        assert!(serde_json::from_str::<Set>(r#"{"id": "dp1", "logo": {"scale": 2}}"#).is_err());
+    }
+
+    /// Run with `--features strict-models` to also catch the fields of nested objects, e.g.
+    /// attacks or prices, that the models drop.
+    #[test]
+    fn test_fixtures_of_every_era_are_fully_modeled() {
+        let cwd = std::env::current_dir().unwrap();
+
+        for fixture in ["base1-4", "ex6-105", "sm3-20", "swsh1-138", "sv3pt5-6", "xy1-1"] {
+            let json = std::fs::read_to_string(cwd.join(format!("src/mock/{fixture}.json"))).unwrap();
+            let card = match serde_json::from_str::<Container<Card>>(&json) {
+                Ok(container) => container.data,
+                Err(e) => panic!("{fixture}: {e}"),
+            };
+
+            assert!(card.id.as_deref() == Some(fixture));
+            assert!(card.extra.is_empty() && card.set.unwrap().extra.is_empty(), "{fixture} has unmodeled fields");
+        }
+
+        let base_charizard: Container<Card> = serde_json::from_str(include_str!("mock/base1-4.json")).unwrap();
+        assert!(base_charizard.data.level == Some("76".into()));
+    }
+
+    #[tokio::test]
+    async fn test_regulation_mark_and_rules() {
+        let mock = MockApi::start().await;
+        let api = mock.client();
+
+        let charizard_ex = api.find_card(&"sv3pt5-6".parse().unwrap()).await.unwrap().unwrap();
+        assert!(charizard_ex.regulationMark == Some("G".into()));
+        assert!(charizard_ex.rules.as_ref().unwrap()[0].starts_with("Pokémon ex rule"));
+        assert!(charizard_ex.ancientTrait.is_none() && charizard_ex.extra.is_empty());
+        assert!(!charizard_ex.is_rotated_out(&["G", "H", "I"]));
+
+        let charizard = api.find_card(&"swsh4-25".parse().unwrap()).await.unwrap().unwrap();
+        assert!(charizard.is_rotated_out(&["G", "H", "I"]));
+        assert!(api.find_card(&"xy1-1".parse().unwrap()).await.unwrap().unwrap().is_rotated_out(&["G", "H", "I"]));
+
+        let energy: Card = serde_json::from_str(r#"{"id": "sve-2", "supertype": "Energy", "subtypes": ["Basic"]}"#).unwrap();
+        assert!(!energy.is_rotated_out(&["G", "H", "I"]));
+
+        let trevenant: Card = serde_json::from_str(r#"{"id": "xy4-55", "ancientTrait": {"name": "θ Stop", "text": "Prevent all effects of your opponent's Pokémon's Abilities done to this Pokémon."}}"#).unwrap();
+        assert!(trevenant.ancientTrait.unwrap().name == Some("θ Stop".into()));
+    }
+
+    #[test]
+    fn test_sort_by_number() {
+        use models::models::{ sort_by_number, NumberSortKey };
+
+        let numbers = ["112", "TG10", "12", "SWSH250", "1", "TG05", "12a", "SWSH020", "SV107", "?"];
+        let mut cards: Vec<Card> = numbers.iter()
+            .map(|n| serde_json::from_value(serde_json::json!({ "number": n })).unwrap())
+            .collect();
+
+        sort_by_number(&mut cards);
+        let sorted: Vec<&str> = cards.iter().filter_map(|c| c.number.as_deref()).collect();
+
+        assert!(sorted == vec!["1", "12", "12a", "112", "?", "SV107", "SWSH020", "SWSH250", "TG05", "TG10"]);
+        assert!(NumberSortKey::parse("TG05") == NumberSortKey { prefix: "TG".into(), number: Some(5), suffix: "".into() });
+        assert!(NumberSortKey::parse("tg5") == NumberSortKey::parse("TG05"));
+    }
+
+    #[tokio::test]
+    async fn test_timeouts_and_cancellation() {
+        let mock = MockApi::start().await;
+        Mock::given(method("GET"))
+            .and(path("/cards/xy1-1"))
+            .respond_with(ResponseTemplate::new(200)
+                .set_body_raw(include_str!("mock/xy1-1.json"), "application/json")
+                .set_delay(Duration::from_millis(500)))
+            .with_priority(1)
+            .mount(mock.server())
+            .await;
+
+        let api = mock.builder().timeout(Duration::from_millis(100)).retry_policy(RetryPolicy::none()).build().unwrap();
+        let err = api.find_card(&"xy1-1".parse().unwrap()).await.unwrap_err();
+        assert!(matches!(&err, Error::Timeout { url, source } if url.ends_with("/cards/xy1-1") && source.is_timeout()));
+        assert!(std::error::Error::source(&err).is_some());
+        assert!(api.with_timeout(Duration::from_secs(5)).find_card(&"xy1-1".parse().unwrap()).await.unwrap().is_some());
+
+        let patient = mock.builder().retry_policy(RetryPolicy::none()).build().unwrap();
+        assert!(matches!(patient.with_timeout(Duration::from_millis(100)).find_card(&"xy1-1".parse().unwrap()).await, Err(Error::Timeout { .. })));
+
+        // dropping a call midway leaves nothing behind, the same client keeps working
+        assert!(tokio::time::timeout(Duration::from_millis(100), patient.find_card(&"xy1-1".parse().unwrap())).await.is_err());
+        assert!(patient.all::<Card>().await.unwrap().len() == 4);
+    }
+
+    #[tokio::test]
+    async fn test_compressed_responses() {
+        use std::io::Write;
+
+        let body = include_str!("mock/cards_page_1.json").replace("\"totalCount\": 6", "\"totalCount\": 2");
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(body.as_bytes()).unwrap();
+        let gzipped = encoder.finish().unwrap();
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/cards"))
+            .and(header("accept-encoding", "gzip, br, deflate"))
+            .respond_with(ResponseTemplate::new(200)
+                .insert_header("content-encoding", "gzip")
+                .set_body_raw(gzipped, "application/json"))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/cards"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(body, "application/json"))
+            .mount(&server)
+            .await;
+
+        let api = Client::builder().base_url(server.uri()).build().unwrap();
+        let cards = api.all::<Card>().await.unwrap();
+        assert!(cards.len() == 2 && cards[0].name == Some("Venusaur-EX".into()));
+
+        let plain = Client::builder().base_url(server.uri()).compression(false).build().unwrap();
+        assert!(plain.all::<Card>().await.unwrap().len() == 2);
+
+        let requests = server.received_requests().await.unwrap();
+        assert!(requests[0].headers.get("accept-encoding").unwrap().to_str().unwrap().contains("gzip"));
+        assert!(requests[1].headers.get("accept-encoding").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_etag_revalidation() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/cards/xy1-1"))
+            .and(header("if-none-match", "\"v1\""))
+            .respond_with(ResponseTemplate::new(304))
+            .with_priority(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/cards/xy1-1"))
+            .respond_with(ResponseTemplate::new(200)
+                .insert_header("etag", "\"v1\"")
+                .set_body_raw(include_str!("mock/xy1-1.json"), "application/json"))
+            .mount(&server)
+            .await;
+
+        // expired right away, so every call revalidates
+        let api = Client::builder().base_url(server.uri()).cache_ttl(Duration::ZERO).build().unwrap();
+        for _ in 0..3 {
+            assert!(api.find_card(&"xy1-1".parse().unwrap()).await.unwrap().unwrap().name == Some("Venusaur-EX".into()));
+        }
+
+        let requests = server.received_requests().await.unwrap();
+        assert!(requests.len() == 3);
+        assert!(requests[0].headers.get("if-none-match").is_none());
+        assert!(requests[1..].iter().all(|r| r.headers.get("if-none-match").unwrap() == "\"v1\""));
+
+        let url = format!("{}/cards/xy1-1", server.uri());
+        let Conditional::Modified { etag, body } = api.fetch_if_modified(&url, None).await.unwrap() else { panic!("expected a body") };
+        assert!(etag == Some("\"v1\"".into()) && body.contains("Venusaur-EX"));
+        assert!(api.fetch_if_modified(&url, etag.as_deref()).await.unwrap() == Conditional::NotModified);
+        assert!(matches!(api.fetch_if_modified(&format!("{}/cards/xy1-999", server.uri()), None).await, Err(Error::NotFound { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_progress_reported_per_page() {
+        let mock = MockApi::start().await;
+        let reports = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let seen = reports.clone();
+        let api = mock.builder().on_progress(move |p| seen.lock().unwrap().push(p)).build().unwrap();
+
+        assert!(api.all::<Card>().await.unwrap().len() == 4);
+        assert!(*reports.lock().unwrap() == vec![
+            Progress { fetched: 2, total_count: Some(6), current_page: 1 },
+            Progress { fetched: 4, total_count: Some(6), current_page: 2 },
+            Progress { fetched: 4, total_count: Some(6), current_page: 3 },
+        ]);
+
+        reports.lock().unwrap().clear();
+        let first: Vec<Card> = api.stream::<Card>(HashMap::new()).take(1).try_collect().await.unwrap();
+        assert!(first.len() == 1);
+        assert!(*reports.lock().unwrap() == vec![Progress { fetched: 2, total_count: Some(6), current_page: 1 }]);
+
+        // clones share the callback
+        reports.lock().unwrap().clear();
+        api.clone().all_types().await.unwrap();
+        assert!(reports.lock().unwrap()[0].fetched == 11 && reports.lock().unwrap()[0].total_count.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_query_args_are_validated() {
+        let mock = MockApi::start().await;
+        let api = mock.client();
+        let args = |key: &str, value: &str| HashMap::from([(String::from(key), String::from(value))]);
+
+        assert!(matches!(api.query::<Card>(args("pagesize", "10")).await, Err(Error::InvalidArgument { arg }) if arg == "pagesize"));
+        assert!(matches!(api.query::<Card>(args("pageSize", "251")).await, Err(Error::InvalidArgument { arg }) if arg == "pageSize=251"));
+        assert!(matches!(api.search_page::<Card>(args("pageSize", "0")).await, Err(Error::InvalidArgument { .. })));
+        assert!(matches!(api.search_page::<Card>(args("page", "-1")).await, Err(Error::InvalidArgument { arg }) if arg == "page=-1"));
+        assert!(mock.server().received_requests().await.unwrap().is_empty());
+
+        assert!(api.query::<Card>(args("pageSize", "250")).await.unwrap().len() == 4);
+        let lenient = mock.builder().allow_unknown_args(true).build().unwrap();
+        assert!(lenient.query::<Card>(args("pagesize", "10")).await.unwrap().len() == 4);
+    }
+
+    #[test]
+    fn test_string_newtypes() {
+        let mut rarities: Vec<Rarity> = vec!["Rare Holo".into(), "Common".parse().unwrap(), Rarity::from(String::from("Uncommon"))];
+        rarities.sort();
+
+        assert!(rarities[0] == "Common" && rarities[2] == *"Rare Holo");
+        assert!(format!("{}", rarities[1]) == "Uncommon" && rarities[1].as_ref() == "Uncommon");
+        assert!(Type("Fire".into()) == "Fire" && format!("{}", Subtype::from("Stage 2")) == "Stage 2");
+        assert!(serde_json::to_string(&Supertype::from("Trainer")).unwrap() == "\"Trainer\"");
+    }
+
+    #[tokio::test]
+    async fn test_supertype_ignores_case_and_accents() {
+        assert!(Supertype::Pokemon == "POKEMON" && Supertype::Pokemon == "pokémon" && Supertype::Trainer == "trainer");
+        assert!(matches!(Supertype::from("Mystery"), Supertype::Other(other) if other == "Mystery"));
+        assert!(serde_json::to_string(&Supertype::Pokemon).unwrap() == "\"Pokémon\"");
+
+        let mock = MockApi::start().await;
+        let card = mock.client().find_card(&"xy1-1".parse().unwrap()).await.unwrap().unwrap();
+        assert!(card.is_pokemon() && !card.is_trainer() && !card.is_energy());
+
+        let energy: Card = serde_json::from_str(r#"{"supertype": "Energy"}"#).unwrap();
+        assert!(energy.is_energy() && !energy.is_pokemon());
+    }
+
+    #[tokio::test]
+    async fn test_results_with_response_meta() {
+        let mock = MockApi::start().await;
+        let api = Client::builder().base_url(mock.uri()).api_key("secret").build().unwrap();
+
+        let (card, meta) = api.find_with_meta::<Card>("xy1-1").await.unwrap();
+        assert!(card.unwrap().id.as_deref() == Some("xy1-1"));
+        assert!(meta.status == 200 && meta.url == format!("{}/cards/xy1-1", mock.uri()));
+        assert!(meta.header("Content-Type").is_some_and(|t| t.starts_with("application/json")));
+        assert!(!format!("{meta:?}").contains("secret"));
+
+        let (missing, meta) = api.find_with_meta::<Card>("xy1-999").await.unwrap();
+        assert!(missing.is_none() && meta.status == 404);
+
+        let args = HashMap::from([("page".into(), "2".into())]);
+        let (page, meta) = api.search_with_meta::<Card>(args).await.unwrap();
+        assert!(page.page == 2 && meta.status == 200 && meta.url.contains("page=2"));
+    }
+
+    #[tokio::test]
+    async fn test_page_size_is_always_sent() {
+        async fn page_sizes(mock: &MockApi) -> Vec<Option<String>> {
+            mock.server().received_requests().await.unwrap().iter()
+                .map(|r| r.url.query_pairs().find(|(k, _)| k == "pageSize").map(|(_, v)| v.into_owned()))
+                .collect()
+        }
+
+        let mock = MockApi::start().await;
+        mock.client().all::<Card>().await.unwrap();
+        let sizes = page_sizes(&mock).await;
+        assert!(sizes.len() > 1 && sizes.iter().all(|s| s.as_deref() == Some("250")));
+
+        let mock = MockApi::start().await;
+        let api = mock.builder().page_size(1000).build().unwrap();
+        let _: Vec<Card> = api.stream::<Card>(HashMap::new()).take(1).try_collect().await.unwrap();
+        assert!(page_sizes(&mock).await == vec![Some("250".into())]);
+
+        let mock = MockApi::start().await;
+        let api = mock.builder().page_size(50).build().unwrap();
+        api.query::<Card>(HashMap::from([("pageSize".into(), "10".into())])).await.unwrap();
+        assert!(page_sizes(&mock).await.iter().all(|s| s.as_deref() == Some("10")));
+
+        let mock = MockApi::start().await;
+        mock.builder().page_size(50).build().unwrap().all::<Card>().await.unwrap();
+        assert!(page_sizes(&mock).await.iter().all(|s| s.as_deref() == Some("50")));
+    }
+
+    #[tokio::test]
+    async fn test_equivalent_args_send_the_same_request() {
+        fn args(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+            let mut args = HashMap::new();
+            for (key, value) in pairs {
+                args.insert(String::from(*key), String::from(*value));
+            }
+            args
+        }
+
+        let first = args(&[("q", "name:venusaur"), ("page", "1"), ("pageSize", "2"), ("orderBy", "-set.releaseDate,number")]);
+        let second = args(&[("orderBy", "-set.releaseDate,number "), ("pageSize", "2"), ("page", "1"), ("q", " name:venusaur")]);
+        let url = "https://api.pokemontcg.io/v2/cards";
+        assert!(cache::key(url, &first) == cache::key(url, &second));
+        assert!(cache::key(url, &first) == "https://api.pokemontcg.io/v2/cards?orderBy=-set.releaseDate%2Cnumber&page=1&pageSize=2&q=name%3Avenusaur");
+
+        let mock = MockApi::start().await;
+        let api = mock.client();
+        api.search_page::<Card>(first.clone()).await.unwrap();
+        api.search_page::<Card>(second.clone()).await.unwrap();
+        let requests = mock.server().received_requests().await.unwrap();
+        assert!(requests.len() == 2 && requests[0].url == requests[1].url);
+        assert!(requests[0].url.query() == Some("orderBy=-set.releaseDate%2Cnumber&page=1&pageSize=2&q=name%3Avenusaur"));
+
+        let mock = MockApi::start().await;
+        let api = mock.builder().cache_ttl(std::time::Duration::from_secs(60)).build().unwrap();
+        api.search_page::<Card>(first).await.unwrap();
+        api.search_page::<Card>(second).await.unwrap();
+        assert!(mock.server().received_requests().await.unwrap().len() == 1);
+    }
+
+    #[tokio::test]
+    async fn test_errors_keep_their_source() {
+        use std::error::Error as _;
+
+        let server = MockServer::start().await;
+        let html = format!("<html><body>{}</body></html>", "Service temporarily unavailable. ".repeat(20));
+        Mock::given(method("GET"))
+            .and(path("/cards/xy1-1"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(html.clone(), "application/json"))
+            .mount(&server)
+            .await;
+
+        let api = Client::builder().base_url(server.uri()).build().unwrap();
+        let err = api.find_card(&"xy1-1".parse().unwrap()).await.unwrap_err();
+        match &err {
+            Error::Decode { body_snippet, .. } => assert!(body_snippet.len() == 200 && html.starts_with(body_snippet.as_str())),
+            other => panic!("unexpected {other:?}"),
+        }
+        assert!(err.source().is_some_and(|source| source.is::<serde_json::Error>()));
+        assert!(serde_json::to_value(&err).unwrap()["Decode"]["source"].is_string());
+
+        let unreachable = Client::builder().base_url("http://127.0.0.1:9").build().unwrap();
+        let err = unreachable.find_card(&"xy1-1".parse().unwrap()).await.unwrap_err();
+        assert!(matches!(&err, Error::Transport { url, .. } if url.ends_with("/cards/xy1-1")));
+        assert!(err.source().is_some_and(|source| source.is::<reqwest::Error>()));
+    }
+
+    #[tokio::test]
+    async fn test_hooks_run_for_every_page() {
+        use std::sync::Mutex;
+
+        let mock = MockApi::start().await;
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let statuses = Arc::new(Mutex::new(Vec::new()));
+
+        let api = {
+            let (seen, statuses) = (seen.clone(), statuses.clone());
+            mock.builder()
+                .api_key("secret")
+                .with_request_hook(move |req| {
+                    seen.lock().unwrap().push((req.url.to_string(), req.headers.contains_key("x-api-key")));
+                    req.headers.insert("x-proxy-token", reqwest::header::HeaderValue::from_static("corp"));
+                })
+                .with_response_hook(move |resp| statuses.lock().unwrap().push(resp.status.as_u16()))
+                .build()
+                .unwrap()
+        };
+
+        assert!(api.all::<Card>().await.unwrap().len() == 4);
+
+        let seen = seen.lock().unwrap().clone();
+        assert!(seen.len() == 3 && seen.iter().all(|(_, has_key)| !has_key));
+        assert!(seen.iter().enumerate().all(|(i, (url, _))| url.contains(&format!("page={}", i + 1))));
+        assert!(*statuses.lock().unwrap() == vec![200, 200, 200]);
+
+        let requests = mock.server().received_requests().await.unwrap();
+        assert!(requests.iter().all(|r| r.headers.get("x-proxy-token").is_some_and(|v| v == "corp")));
+        assert!(requests.iter().all(|r| r.headers.get("x-api-key").is_some_and(|v| v == "secret")));
+    }
+
+    #[tokio::test]
+    async fn test_error_body_with_success_status() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/cards"))
+            .respond_with(ResponseTemplate::new(200)
+                .set_body_raw(r#"{"error": {"message": "Bad Request. Your request is either malformed, or is missing one or more required fields.", "code": 400}}"#, "application/json"))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/cards/xy1-1"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(r#"{"error": {"message": "Internal error"}}"#, "application/json"))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/sets"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(r#"{"data": "not a list"}"#, "application/json"))
+            .mount(&server)
+            .await;
+
+        let api = Client::builder().base_url(server.uri()).build().unwrap();
+
+        let err = api.search_cards("name:\"").await.unwrap_err();
+        assert!(matches!(err, Error::Api { code: Some(400), message } if message.starts_with("Bad Request.")));
+        assert!(matches!(api.find_card(&"xy1-1".parse().unwrap()).await, Err(Error::Api { code: None, message }) if message == "Internal error"));
+
+        match api.all::<Set>().await {
+            Err(Error::Decode { source, .. }) => assert!(source.to_string().contains("expected a sequence")),
+            other => panic!("unexpected {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_rarities_sort_by_rank() {
+        let rank = |r: &str| Rarity::from(r).rank();
+        assert!(rank("Common") == Some(0) && rank("Uncommon") == Some(1));
+        assert!(rank("Double Rare") < rank("Illustration Rare"));
+        assert!(rank("Illustration Rare") < rank("Special Illustration Rare"));
+        assert!(rank("Special Illustration Rare") < rank("Hyper Rare"));
+        assert!(rank("Mythic").is_none());
+
+        let mut rarities: Vec<Rarity> = ["Zebra Rare", "Hyper Rare", "Common", "Alpha Rare", "Rare Holo", "Illustration Rare", "Uncommon"]
+            .into_iter()
+            .map(Rarity::from)
+            .collect();
+        rarities.sort();
+
+        let names: Vec<&str> = rarities.iter().map(AsRef::as_ref).collect();
+        assert!(names == vec!["Common", "Uncommon", "Rare Holo", "Illustration Rare", "Hyper Rare", "Alpha Rare", "Zebra Rare"]);
+
+        let fixture: VecContainer<Rarity> = serde_json::from_str(include_str!("mock/rarities.json")).unwrap();
+        assert!(fixture.data.iter().all(|r| r.rank().is_some()), "every rarity of the API is ranked");
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_spaces_requests() {
+        struct Timestamps(Arc<std::sync::Mutex<Vec<std::time::Instant>>>);
+
+        impl wiremock::Respond for Timestamps {
+            fn respond(&self, _: &wiremock::Request) -> ResponseTemplate {
+                self.0.lock().unwrap().push(std::time::Instant::now());
+                ResponseTemplate::new(200).set_body_raw(r#"{"data": {"id": "xy1-1"}}"#, "application/json")
+            }
+        }
+
+        let received = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let server = MockServer::start().await;
+        Mock::given(method("GET")).respond_with(Timestamps(received.clone())).mount(&server).await;
+
+        // a burst of 20, then one request every 50ms shared by both clones
+        let api = Client::builder().base_url(server.uri()).rate_limit(20.0).build().unwrap();
+        let clone = api.clone();
+        let id = "xy1-1".parse().unwrap();
+        let (first, second) = tokio::join!(
+            futures::future::join_all((0..15).map(|_| api.find_card(&id))),
+            futures::future::join_all((0..15).map(|_| clone.find_card(&id))),
+        );
+        assert!(first.into_iter().chain(second).all(|card| card.is_ok_and(|c| c.is_some())));
+
+        let received = received.lock().unwrap();
+        let spaced = received[20..].windows(2).filter(|w| w[1] - w[0] >= Duration::from_millis(40)).count();
+        assert!(received.len() == 30 && received[29] - received[0] >= Duration::from_millis(450));
+        assert!(spaced >= 7);
+    }
+
+    #[test]
+    fn test_card_and_set_display_snapshots() {
+        let venusaur: Container<Card> = serde_json::from_str(include_str!("mock/xy1-1.json")).unwrap();
+        let charizard: Container<Card> = serde_json::from_str(include_str!("mock/base1-4.json")).unwrap();
+
+        assert!(venusaur.data.to_string() == "Venusaur-EX — XY 1/146 — Rare Holo EX — 180 HP");
+        assert!(venusaur.data.set.as_ref().unwrap().to_string() == "XY (xy1) — XY — 146 cards — 2014/02/05");
+        assert!(venusaur.data.detailed_text() == "\
+Venusaur-EX — XY 1/146 — Rare Holo EX — 180 HP
+Pokémon: Basic, EX {G}
+{G}{C}{C} Poison Powder 60 — Your opponent's Active Pokémon is now Poisoned.
+{G}{G}{C}{C} Jungle Hammer 90 — Heal 30 damage from this Pokémon.
+Weakness: {R} ×2
+Retreat: {C}{C}{C}{C}
+Pokémon-EX rule: When a Pokémon-EX has been Knocked Out, your opponent takes 2 Prize cards.
+Legalities: Expanded Legal, Unlimited Legal");
+        assert!(charizard.data.detailed_text() == "\
+Charizard — Base 4/102 — Rare Holo — 120 HP
+Pokémon: Stage 2 {R}
+Evolves from Charmeleon
+Pokémon Power: Energy Burn — As often as you like during your turn (before your attack), you may turn all Energy \
+attached to Charizard into Fire Energy for the rest of the turn. This power can't be used if Charizard is Asleep, \
+Confused, or Paralyzed.
+{R}{R}{R}{R} Fire Spin 100 — Discard 2 Energy cards attached to Charizard in order to use this attack.
+Weakness: {W} ×2
+Resistance: {F} -30
+Retreat: {C}{C}{C}
+Legalities: Unlimited Legal");
+
+        let bare: Card = serde_json::from_value(serde_json::json!({ "id": "sv4-1", "number": "1" })).unwrap();
+        assert!(bare.to_string() == "sv4-1 — #1" && bare.detailed_text() == "sv4-1 — #1");
+    }
+
+    #[test]
+    fn test_bad_request_messages_are_classified() {
+        let classify = |body: &str, url: &str| {
+            let meta = ResponseMeta { status: 400, headers: Default::default(), url: url.into() };
+            let err = check_status(&meta, body).unwrap_err();
+            assert!(err.response_meta() == Some(&meta));
+            err
+        };
+        let cards = "https://api.pokemontcg.io/v2/cards";
+
+        match classify(include_str!("mock/errors/value_required.json"), &format!("{cards}?q=name%3A&page=1")) {
+            Error::QuerySyntax { detail, query, .. } => assert!(detail == "Value is required" && query == "name:"),
+            other => panic!("unexpected {other:?}"),
+        }
+        match classify(include_str!("mock/errors/lucene_parse.json"), &format!("{cards}?q=name%3A%22charizard")) {
+            Error::QuerySyntax { detail, query, .. } => assert!(detail.starts_with("Cannot parse") && query == "name:\"charizard"),
+            other => panic!("unexpected {other:?}"),
+        }
+        match classify(include_str!("mock/errors/select_field.json"), &format!("{cards}?select=id,flavour")) {
+            Error::InvalidSelectField { field, .. } => assert!(field == "flavour"),
+            other => panic!("unexpected {other:?}"),
+        }
+        assert!(matches!(classify(include_str!("mock/errors/page_out_of_range.json"), &format!("{cards}?page=999")), Error::PageOutOfRange { .. }));
+        assert!(matches!(classify(include_str!("mock/errors/malformed.json"), cards), Error::BadRequest { .. }));
+        assert!(matches!(classify("not json", cards), Error::BadRequest { message, .. } if message == "not json"));
+    }
+
+    #[test]
+    fn test_models_compare_and_hash() {
+        let venusaur = serde_json::from_str::<Container<Card>>(include_str!("mock/xy1-1.json")).unwrap().data;
+        let mut repriced = venusaur.clone();
+        repriced.tcgplayer.as_mut().unwrap().updatedAt = Some("2099/01/01".into());
+        let charizard = serde_json::from_str::<Container<Card>>(include_str!("mock/base1-4.json")).unwrap().data;
+
+        assert!(venusaur == venusaur.clone() && venusaur != repriced);
+        assert!(venusaur.attacks == repriced.attacks && venusaur.set == repriced.set);
+
+        let cards = [venusaur.clone(), venusaur.clone(), repriced, charizard];
+        let ids: std::collections::HashSet<&CardId> = cards.iter().filter_map(|c| c.id.as_ref()).collect();
+        assert!(ids.len() == 2 && venusaur.id.as_ref().is_some_and(|id| ids.contains(id)));
+
+        let sets: std::collections::HashSet<Set> = cards.into_iter().filter_map(|c| c.set).collect();
+        assert!(sets.len() == 2);
+    }
+
+    #[test]
+    fn test_card_and_set_ids() {
+        let id: CardId = "sv3pt5-6".parse().unwrap();
+        assert!(id == "sv3pt5-6" && id.set_id() == "sv3pt5" && id.starts_with("sv3"));
+        assert!("swshp-SWSH001".parse::<CardId>().unwrap().set_id() == "swshp");
+        for invalid in ["", "xy1", "-1", "xy1-", "xy1 -1", "xy1-1/../2", "xy1-1?page=2"] {
+            assert!(matches!(invalid.parse::<CardId>(), Err(Error::InvalidId { id }) if id == invalid));
+        }
+
+        assert!("xy1".parse::<SetId>().is_ok());
+        assert!(["", "xy1-1", "x y"].iter().all(|invalid| invalid.parse::<SetId>().is_err()));
+
+        let card: Card = serde_json::from_str(r#"{"id": "xy1-1", "set": {"id": "xy1"}}"#).unwrap();
+        assert!(card.id.as_deref() == Some("xy1-1") && card.set.as_ref().unwrap().id.as_deref() == Some("xy1"));
+        assert!(serde_json::to_value(&card.id).unwrap() == serde_json::json!("xy1-1"));
+
+        let by_id: HashMap<CardId, Card> = HashMap::from([(id, card)]);
+        assert!(by_id.contains_key("sv3pt5-6"));
+    }
+
+    #[test]
+    fn test_energy_cost() {
+        let venusaur = serde_json::from_str::<Container<Card>>(include_str!("mock/xy1-1.json")).unwrap().data;
+        for attack in venusaur.attacks.iter().flatten() {
+            assert!(attack.convertedEnergyCost == Some(attack.cost.as_ref().unwrap().converted()));
+        }
+
+        // Jungle Hammer: Grass, Grass, Colorless, Colorless
+        let cost = venusaur.attacks.unwrap()[1].cost.clone().unwrap();
+        assert!(cost.by_type() == HashMap::from([(EnergyType::Grass, 2), (EnergyType::Colorless, 2)]));
+
+        let energy = |counts: &[(EnergyType, u8)]| counts.iter().cloned().collect::<HashMap<EnergyType, u8>>();
+        assert!(cost.payable_with(&energy(&[(EnergyType::Grass, 2), (EnergyType::Fire, 1), (EnergyType::Water, 1)])));
+        assert!(cost.payable_with(&energy(&[(EnergyType::Grass, 4)])));
+        assert!(!cost.payable_with(&energy(&[(EnergyType::Grass, 1), (EnergyType::Fire, 3)])));
+        assert!(!cost.payable_with(&energy(&[(EnergyType::Grass, 2), (EnergyType::Colorless, 1)])));
+        assert!(cost.payable_with(&energy(&[(EnergyType::Grass, 2), (EnergyType::Colorless, 2)])));
+
+        let free: EnergyCost = serde_json::from_str(r#"["Free"]"#).unwrap();
+        for zero in [free, EnergyCost::default()] {
+            assert!(zero.converted() == 0 && zero.by_type().is_empty() && zero.payable_with(&HashMap::new()));
+        }
+        assert!(serde_json::to_string(&cost).unwrap() == r#"["Grass","Grass","Colorless","Colorless"]"#);
+    }
+
+    #[test]
+    fn test_set_era() {
+        let sets = serde_json::from_str::<VecContainer<Set>>(include_str!("mock/sets.json")).unwrap().data;
+        assert!(sets.iter().all(|s| s.era() == Era::Xy));
+
+        let set = |series: &str| serde_json::from_value::<Set>(serde_json::json!({ "series": series })).unwrap();
+        assert!(set("Scarlet & Violet").era() == Era::ScarletViolet && set("sword & shield").era() == Era::SwordShield);
+        assert!(set("E-Card").era() == Era::ECard && set("Base").era() < set("Neo").era());
+        assert!(set("Legends Arceus").era() == Era::Other("Legends Arceus".into()));
+        assert!(serde_json::from_value::<Set>(serde_json::json!({})).unwrap().era() == Era::Other(String::new()));
+
+        for era in Era::standard_legal_eras() {
+            assert!(era.to_string().parse::<Era>() == Ok(era.clone()) && !matches!(era, Era::Other(_)));
+        }
+        assert!(Era::HeartGoldSoulSilver.series() == "HeartGold & SoulSilver" && Era::Other("Other".into()).to_string() == "Other");
+    }
+
+    #[tokio::test]
+    async fn test_response_size_limit_and_content_type() {
+        use std::io::Write;
+
+        // 4 MB of spaces gzipped to a few KB, so that only reading the body tells its size
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(&vec![b' '; 4 * 1024 * 1024]).unwrap();
+        let gzipped = encoder.finish().unwrap();
+        let html = format!("<html><body>{}</body></html>", "Proxy error. ".repeat(20));
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/cards/xy1-2"))
+            .respond_with(ResponseTemplate::new(200).insert_header("content-encoding", "gzip").set_body_raw(gzipped, "application/json"))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/cards/xy1-3"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(html.clone(), "text/html; charset=utf-8"))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/cards/xy1-1"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(include_str!("mock/xy1-1.json"), "application/json; charset=utf-8"))
+            .mount(&server)
+            .await;
+
+        let api = Client::builder().base_url(server.uri()).max_response_size(1024 * 1024).build().unwrap();
+        match api.find_card(&"xy1-2".parse().unwrap()).await {
+            Err(Error::ResponseTooLarge { limit, url }) => assert!(limit == 1024 * 1024 && url.ends_with("/cards/xy1-2")),
+            other => panic!("unexpected {other:?}"),
+        }
+        assert!(api.find_card(&"xy1-1".parse().unwrap()).await.unwrap().is_some());
+
+        let small = Client::builder().base_url(server.uri()).max_response_size(100).build().unwrap();
+        assert!(matches!(small.find_card(&"xy1-1".parse().unwrap()).await, Err(Error::ResponseTooLarge { limit: 100, .. })));
+
+        match api.find_card(&"xy1-3".parse().unwrap()).await {
+            Err(Error::NotJson { content_type, body_snippet, .. }) => {
+                assert!(content_type == "text/html; charset=utf-8" && body_snippet.len() == 200 && html.starts_with(body_snippet.as_str()));
+            },
+            other => panic!("unexpected {other:?}"),
+        }
     }
 }
@@ -1,7 +1,6 @@
-use std::{ fmt::Formatter };
+use std::{ collections::BTreeMap, fmt::Formatter, time::Duration };
 
-use derive_more::{ From };
-use serde::Serialize;
+use serde::{ Serialize, Serializer };
 
 #[derive(Debug, Serialize)]
 pub enum Error {
@@ -10,9 +9,22 @@ pub enum Error {
 
     
     FailedOpeningFile,
-    FailedParsingFile,
+    FailedParsingFile {
+        reason: String,
+        line: usize,
+        column: usize,
+    },
+    FailedWritingFile {
+        reason: String,
+    },
+    FailedQueryingDatabase {
+        reason: String,
+    },
 
     ApiKeyNotFound,
+    FailedBuildingClient {
+        reason: String,
+    },
 
     InvalidArgument {
         arg: String,
@@ -20,6 +32,231 @@ pub enum Error {
     InvalidEndpoint {
         url: String,
     },
+    /// Card or set id not shaped like those of the API, e.g. a card id without its set prefix.
+    InvalidId {
+        id: String,
+    },
+
+    Transport {
+        url: String,
+        #[serde(serialize_with = "serialize_display")]
+        source: reqwest::Error,
+    },
+    Timeout {
+        url: String,
+        #[serde(serialize_with = "serialize_display")]
+        source: reqwest::Error,
+    },
+    Decode {
+        url: String,
+        #[serde(serialize_with = "serialize_display")]
+        source: serde_json::Error,
+        /// Start of the body that couldn't be decoded, e.g. an HTML error page.
+        body_snippet: String,
+    },
+    /// Body longer than the `max_response_size` of the Client, left unread past the limit.
+    ResponseTooLarge {
+        limit: u64,
+        url: String,
+    },
+    /// Successful response whose Content-Type isn't JSON, e.g. the HTML page of a misconfigured
+    /// proxy; not deserialized.
+    NotJson {
+        url: String,
+        content_type: String,
+        /// Start of the body, as for `Decode`.
+        body_snippet: String,
+    },
+
+    /// Error body sent along a successful status, e.g. for some malformed q strings.
+    Api {
+        code: Option<u16>,
+        message: String,
+    },
+    BadRequest {
+        message: String,
+        meta: ResponseMeta,
+    },
+    /// 400 for a `q` the API couldn't parse, e.g. `name:` or an unterminated quote; detail is
+    /// the API's message.
+    QuerySyntax {
+        detail: String,
+        query: String,
+        meta: ResponseMeta,
+    },
+    /// 400 for a `select` naming a field the resource doesn't have.
+    InvalidSelectField {
+        field: String,
+        meta: ResponseMeta,
+    },
+    /// 400 for a `page` past the results.
+    PageOutOfRange {
+        meta: ResponseMeta,
+    },
+    PaymentRequired {
+        meta: ResponseMeta,
+    },
+    Forbidden {
+        meta: ResponseMeta,
+    },
+    NotFound {
+        meta: ResponseMeta,
+    },
+    RateLimited {
+        retry_after: Duration,
+        meta: ResponseMeta,
+    },
+    ServerError {
+        status: u16,
+        meta: ResponseMeta,
+    },
+    UnexpectedStatus {
+        status: u16,
+        message: String,
+        meta: ResponseMeta,
+    },
+    /// Bulk fetch stopped by `ClientBuilder::circuit_breaker` after failures requests in a row
+    /// failed, pages_fetched pages having been fetched before.
+    CircuitOpen {
+        failures: u32,
+        pages_fetched: u32,
+    },
+
+    MissingImage {
+        id: String,
+    },
+    ImageDownloadFailed {
+        url: String,
+        status: u16,
+    },
+    InvalidContentType {
+        url: String,
+        content_type: String,
+    },
+
+    OfflineCacheMiss {
+        url: String,
+    },
+    /// Request not recorded in the cassette replayed, e.g. "GET https://api.pokemontcg.io/v2/sets?page=1".
+    CassetteMiss {
+        request: String,
+        cassette: String,
+    },
+    NoMatchingCards {
+        q: String,
+    },
+    SetNotFound {
+        id: String,
+    },
+    SeriesNotFound {
+        series: String,
+    },
+
+    UnsupportedSchemaVersion {
+        found: u32,
+        expected: u32,
+    },
+}
+
+/// Headers never exposed by ResponseMeta, as they may carry credentials.
+const REDACTED_HEADERS: &[&str] = &["x-api-key", "authorization", "proxy-authorization", "cookie", "set-cookie"];
+
+/// Status, headers and final URL of an API response, e.g. to attach to a bug report along with
+/// the `x-ratelimit-*` headers. Headers that may carry credentials are left out.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct ResponseMeta {
+    pub status: u16,
+    /// Header names in lowercase; repeated headers are joined with ", ".
+    pub headers: BTreeMap<String, String>,
+    pub url: String,
+}
+
+impl ResponseMeta {
+    pub(crate) fn new(url: &str, status: reqwest::StatusCode, headers: &reqwest::header::HeaderMap) -> Self {
+        let mut kept = BTreeMap::<String, String>::new();
+        for (name, value) in headers {
+            if REDACTED_HEADERS.contains(&name.as_str()) {
+                continue;
+            }
+
+            let value = String::from_utf8_lossy(value.as_bytes());
+            kept.entry(name.as_str().into())
+                .and_modify(|joined| { joined.push_str(", "); joined.push_str(&value); })
+                .or_insert_with(|| value.into());
+        }
+
+        Self { status: status.as_u16(), headers: kept, url: url.into() }
+    }
+
+    /// Meta of a body answered from a cache: a 200 without headers.
+    pub(crate) fn cached(url: &str) -> Self {
+        Self { status: 200, headers: BTreeMap::new(), url: url.into() }
+    }
+
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(&name.to_lowercase()).map(String::as_str)
+    }
+}
+
+impl Error {
+    /// Response the API answered with, for the variants mapped from an HTTP status.
+    pub fn response_meta(&self) -> Option<&ResponseMeta> {
+        match self {
+            Self::BadRequest { meta, .. }
+            | Self::QuerySyntax { meta, .. }
+            | Self::InvalidSelectField { meta, .. }
+            | Self::PageOutOfRange { meta }
+            | Self::PaymentRequired { meta }
+            | Self::Forbidden { meta }
+            | Self::NotFound { meta }
+            | Self::RateLimited { meta, .. }
+            | Self::ServerError { meta, .. }
+            | Self::UnexpectedStatus { meta, .. } => Some(meta),
+            _ => None,
+        }
+    }
+
+    /// Variant for the message of a 400 answered to the request of meta, mapping the messages the
+    /// API is known to send to the variant telling what to fix; `BadRequest` for any other.
+    pub(crate) fn bad_request(message: String, meta: ResponseMeta) -> Error {
+        let lower = message.to_lowercase();
+
+        // Parse errors quote the query, checked first as it may hold any word.
+        if QUERY_SYNTAX_MESSAGES.iter().any(|m| lower.contains(m)) {
+            let query = reqwest::Url::parse(&meta.url)
+                .ok()
+                .and_then(|url| url.query_pairs().find(|(key, _)| key == "q").map(|(_, q)| q.into_owned()))
+                .unwrap_or_default();
+            return Self::QuerySyntax { detail: message, query, meta };
+        }
+        if lower.contains("select") {
+            if let Some(field) = quoted(&message) {
+                return Self::InvalidSelectField { field: field.into(), meta };
+            }
+        }
+        if lower.contains("page") && (lower.contains("out of range") || lower.contains("exceeds")) {
+            return Self::PageOutOfRange { meta };
+        }
+
+        Self::BadRequest { message, meta }
+    }
+
+    /// `Decode` error of the body of a response to url, keeping its first 200 characters.
+    pub(crate) fn decode(url: &str, body: &str, source: serde_json::Error) -> Error {
+        let body_snippet = body.chars().take(BODY_SNIPPET_LEN).collect();
+        Self::Decode { url: url.into(), source, body_snippet }
+    }
+
+    /// `NotJson` error of the body of a response to url, keeping its first 200 characters.
+    pub(crate) fn not_json(url: &str, content_type: &str, body: &str) -> Error {
+        let body_snippet = body.chars().take(BODY_SNIPPET_LEN).collect();
+        Self::NotJson { url: url.into(), content_type: content_type.into(), body_snippet }
+    }
+
+    /// `FailedParsingFile` pointing at where serde_json stopped reading the file.
+    pub(crate) fn parsing_file(e: serde_json::Error) -> Error {
+        Self::FailedParsingFile { line: e.line(), column: e.column(), reason: e.to_string() }
+    }
 }
 
 impl std::fmt::Display for Error {
@@ -34,4 +271,43 @@ impl From<&str> for Error {
     }
 }
 
-impl std::error::Error for Error { }
+/// Timeouts get their own variant so callers can tell a slow API from an unreachable one.
+impl From<reqwest::Error> for Error {
+    fn from(source: reqwest::Error) -> Error {
+        let url = source.url().map(|url| url.to_string()).unwrap_or_default();
+        if source.is_timeout() {
+            Self::Timeout { url, source }
+        } else {
+            Self::Transport { url, source }
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Transport { source, .. } | Self::Timeout { source, .. } => Some(source),
+            Self::Decode { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+/// Parts of the messages the API answers a `q` it couldn't parse with, in lowercase.
+const QUERY_SYNTAX_MESSAGES: &[&str] = &["value is required", "cannot parse", "lexical error", "syntax error"];
+
+/// First text between single or double quotes of message.
+fn quoted(message: &str) -> Option<&str> {
+    let start = message.find(['\'', '"'])?;
+    let quote = &message[start..start + 1];
+    let rest = &message[start + 1..];
+    rest.find(quote).map(|end| &rest[..end]).filter(|field| !field.is_empty())
+}
+
+/// Amount of characters of an undecodable body kept by `Error::Decode`.
+const BODY_SNIPPET_LEN: usize = 200;
+
+/// Sources aren't serializable, their message is written instead.
+fn serialize_display<T: std::fmt::Display, S: Serializer>(value: &T, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(value)
+}
@@ -1,2 +1,3 @@
+#[allow(clippy::module_inception)]
 pub mod models;
-pub mod errors;
\ No newline at end of file
+pub mod errors;
@@ -1,77 +1,458 @@
 #![allow(non_snake_case)]
 // We must keep the non-snake-case since the other clients use non-snake-case
-use std::{ fmt };
+use std::{ fmt, str::FromStr };
 use std::collections::HashMap;
+use std::hash::{ Hash, Hasher };
 
-use serde::{Serialize, Deserialize};
+use serde::{ Serialize, Serializer, Deserialize, Deserializer };
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+use super::errors::Error;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct DataCardMap {
     pub data: Option<Card>,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+/// Every field is optional, so cards fetched with the `select` parameter deserialize with the
+/// fields left out set to None. Prices are floats, so cards are `PartialEq` only; compare or
+/// hash them by their [`CardId`] instead, e.g. in a `HashSet<CardId>`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
 pub struct Card {
-    pub id: Option<String>,
+    pub id: Option<CardId>,
     pub name: Option<String>,
     pub supertype: Option<String>,
     pub subtypes: Option<Vec<String>>,
+    /// Printed level of cards up to the Platinum era, e.g. "76" or "X" for LV.X cards.
+    pub level: Option<String>,
     pub hp: Option<String>,
-    pub types: Option<Vec<String>>,
+    pub types: Option<Vec<EnergyType>>,
     pub evolvesTo: Option<Vec<String>>,
     pub evolvesFrom:  Option<String>,
     pub rules: Option<Vec<String>>,
     pub abilities: Option<Vec<Ability>>,
     pub attacks: Option<Vec<Attack>>,
     pub weaknesses: Option<Vec<Weakness>>,
+    pub resistances: Option<Vec<Resistance>>,
     pub convertedRetreatCost: Option<u32>,
-    pub retreatCost: Option<Vec<String>>,
+    pub retreatCost: Option<Vec<EnergyType>>,
     pub set: Option<Set>,
     pub number: Option<String>,
     pub artist: Option<String>,
     pub rarity: Option<String>,
     pub flavorText: Option<String>,
-    pub nationalPokedexNumbers: Option<Vec<u32>>,
+    /// Only on some XY-era cards, e.g. the "Ω Barrier" of Primal Groudon-EX.
+    pub ancientTrait: Option<AncientTrait>,
+    /// Letter printed in the bottom corner since Sword & Shield, e.g. "G", deciding which cards
+    /// rotate out of Standard.
+    pub regulationMark: Option<String>,
+    /// Empty for trainers and energies.
+    #[serde(default)]
+    pub nationalPokedexNumbers: Vec<u32>,
     pub legalities: Option<Legalities>,
     pub images: Option<Images>,
     pub tcgplayer: Option<TcgPlayer>,
-    pub cardmarket: Option<CardMarket>,
+    pub cardmarket: Option<Cardmarket>,
+    /// Fields sent by the API that this crate doesn't model yet, serialized back as they came.
+    /// Always empty with the `strict-models` feature, which rejects them instead.
+    #[cfg_attr(not(feature = "strict-models"), serde(flatten))]
+    #[cfg_attr(feature = "strict-models", serde(skip))]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+impl Card {
+    /// HP as a number; `hp` keeps the raw string since the API sends values like "None" or
+    /// malformed ones such as "60+" on some old promos, which map to None here.
+    pub fn hp_value(&self) -> Option<u32> {
+        self.hp.as_ref()?.trim().parse().ok()
+    }
+
+    /// Whether the card's regulation mark is missing from current_marks, the marks legal in
+    /// Standard, e.g. `&["G", "H", "I"]`. Cards printed before regulation marks count as rotated
+    /// out, except basic Energy which is always legal.
+    pub fn is_rotated_out(&self, current_marks: &[&str]) -> bool {
+        match &self.regulationMark {
+            Some(mark) => !current_marks.contains(&mark.as_str()),
+            None => {
+                let basic_energy = self.supertype.as_deref() == Some("Energy")
+                    && self.subtypes.iter().flatten().any(|s| s == "Basic");
+                !basic_energy
+            }
+        }
+    }
+
+    /// Whether subtype is among the card's subtypes, e.g. `card.has_subtype(KnownSubtype::Supporter)`.
+    pub fn has_subtype(&self, subtype: KnownSubtype) -> bool {
+        self.subtypes.iter().flatten().any(|s| s.parse::<KnownSubtype>().is_ok_and(|s| s == subtype))
+    }
+
+    pub fn is_pokemon(&self) -> bool {
+        self.supertype_is(Supertype::Pokemon)
+    }
+
+    pub fn is_trainer(&self) -> bool {
+        self.supertype_is(Supertype::Trainer)
+    }
+
+    pub fn is_energy(&self) -> bool {
+        self.supertype_is(Supertype::Energy)
+    }
+
+    /// TCGPlayer market price of the given printing variant, in US Dollars.
+    pub fn market_price(&self, kind: &PriceVariantKind) -> Option<Price> {
+        self.tcgplayer_prices()?.get(kind)?.market
+    }
+
+    /// TCGPlayer market price of the most common variant that has one, following
+    /// `PriceVariantKind::PRECEDENCE`; variants this crate doesn't know come last, by key.
+    pub fn best_market_price(&self) -> Option<(PriceVariantKind, Price)> {
+        let prices = self.tcgplayer_prices()?;
+        let known = PriceVariantKind::PRECEDENCE
+            .into_iter()
+            .find_map(|kind| Some((kind.clone(), prices.get(&kind)?.market?)));
+
+        known.or_else(|| {
+            prices.iter()
+                  .filter(|(kind, _)| matches!(kind, PriceVariantKind::Other(_)))
+                  .filter_map(|(kind, variant)| Some((kind.clone(), variant.market?)))
+                  .min_by_key(|(kind, _)| kind.to_string())
+        })
+    }
+
+    fn tcgplayer_prices(&self) -> Option<&TcgPlayerPrices> {
+        self.tcgplayer.as_ref()?.prices.as_ref()
+    }
+
+    fn supertype_is(&self, supertype: Supertype) -> bool {
+        self.supertype.as_deref().map(Supertype::from) == Some(supertype)
+    }
+
+    /// Collector number made sortable, e.g. "12" before "112" and "TG05" before "TG10"; see
+    /// `NumberSortKey`.
+    pub fn number_sort_key(&self) -> NumberSortKey {
+        NumberSortKey::parse(self.number.as_deref().unwrap_or_default())
+    }
+
+    /// Amount of energy needed to retreat, counted from `retreatCost` when the API omits
+    /// `convertedRetreatCost`.
+    pub fn converted_retreat_cost(&self) -> u32 {
+        self.convertedRetreatCost
+            .or(self.retreatCost.as_ref().map(|cost| cost.len() as u32))
+            .unwrap_or(0)
+    }
+}
+
+/// Collector number split as prefix, numeric part and suffix, e.g. "TG05" as ("TG", 5, "") and
+/// "177a" as ("", 177, "a"). Compares by prefix first, so plain numbers come before the trainer
+/// gallery ("TG"), shiny vault ("SV") or promo ("SWSH") numbers, and each group is in numeric
+/// order; numbers without any digit, e.g. "?" for an Unown, are all prefix.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NumberSortKey {
+    pub prefix: String,
+    pub number: Option<u64>,
+    pub suffix: String,
+}
+
+impl NumberSortKey {
+    pub fn parse(number: &str) -> Self {
+        let number = number.trim();
+        let digits_start = number.find(|c: char| c.is_ascii_digit()).unwrap_or(number.len());
+        let (prefix, rest) = number.split_at(digits_start);
+        let digits_end = rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len());
+        let (digits, suffix) = rest.split_at(digits_end);
+
+        Self {
+            prefix: prefix.to_uppercase(),
+            number: digits.parse().ok(),
+            suffix: suffix.to_owned(),
+        }
+    }
+}
+
+/// Sorts cards by collector number, see `NumberSortKey`.
+pub fn sort_by_number(cards: &mut [Card]) {
+    cards.sort_by_cached_key(Card::number_sort_key);
 }
 
+/// One-line summary, e.g. "Charizard ex — Obsidian Flames 125/197 — Double Rare — 330 HP";
+/// whatever the card lacks is left out.
 impl fmt::Display for Card {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let c = self.clone();
-        let price = extract_price(c.clone());
+        f.write_str(self.name.as_deref().or(self.id.as_deref()).unwrap_or("?"))?;
+
+        let set_name = self.set.as_ref().and_then(|s| s.name.as_deref());
+        if set_name.is_some() || self.number.is_some() {
+            f.write_str(" — ")?;
+            if let Some(name) = set_name {
+                f.write_str(name)?;
+            }
+            if let Some(number) = &self.number {
+                let separator = if set_name.is_some() { " " } else { "#" };
+                write!(f, "{separator}{number}")?;
+                if let Some(total) = self.set.as_ref().and_then(|s| s.printedTotal) {
+                    write!(f, "/{total}")?;
+                }
+            }
+        }
+        if let Some(rarity) = &self.rarity {
+            write!(f, " — {rarity}")?;
+        }
+        if let Some(hp) = self.hp_value() {
+            write!(f, " — {hp} HP")?;
+        }
 
-        write!(f, "{} {} {} {}\n", c.name.unwrap_or("N/A".into()), c.id.unwrap_or("N/A".into()), c.number.unwrap_or("N/A".into()), price)
+        Ok(())
     }
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
-struct Ability {
-    name: Option<String>,
-    text: Option<String>,
-    r#type: Option<String>,
+impl Card {
+    /// Multi-line rendering of the card: the `Display` summary, then its kind, abilities,
+    /// attacks, weaknesses, resistances, retreat cost, rules and legalities, with energy written
+    /// as symbols such as `{R}{R}{C}`.
+    pub fn detailed_text(&self) -> String {
+        let mut text = self.to_string();
+        // writing to a String never fails
+        let _ = self.write_details(&mut text);
+        text
+    }
+
+    fn write_details(&self, out: &mut String) -> fmt::Result {
+        use fmt::Write;
+
+        if let Some(supertype) = &self.supertype {
+            write!(out, "\n{supertype}")?;
+            if let Some(subtypes) = self.subtypes.as_ref().filter(|s| !s.is_empty()) {
+                write!(out, ": {}", subtypes.join(", "))?;
+            }
+            if let Some(types) = self.types.as_ref().filter(|t| !t.is_empty()) {
+                write!(out, " {}", EnergySymbols(types))?;
+            }
+        }
+        if let Some(from) = &self.evolvesFrom {
+            write!(out, "\nEvolves from {from}")?;
+        }
+        for ability in self.abilities.iter().flatten() {
+            write!(out, "\n{}: {}", ability.r#type.as_deref().unwrap_or("Ability"), ability.name.as_deref().unwrap_or("?"))?;
+            if let Some(effect) = ability.text.as_deref().filter(|t| !t.is_empty()) {
+                write!(out, " — {effect}")?;
+            }
+        }
+        for attack in self.attacks.iter().flatten() {
+            out.push('\n');
+            if let Some(cost) = attack.cost.as_ref().filter(|c| !c.is_empty()) {
+                write!(out, "{} ", EnergySymbols(cost))?;
+            }
+            out.push_str(attack.name.as_deref().unwrap_or("?"));
+            if let Some(damage) = attack.damage.as_deref().filter(|d| !d.is_empty()) {
+                write!(out, " {damage}")?;
+            }
+            if let Some(effect) = attack.text.as_deref().filter(|t| !t.is_empty()) {
+                write!(out, " — {effect}")?;
+            }
+        }
+        for weakness in self.weaknesses.iter().flatten() {
+            write!(out, "\nWeakness: {} {}", OptionalSymbol(weakness.r#type.as_ref()), weakness.value.as_deref().unwrap_or(""))?;
+        }
+        for resistance in self.resistances.iter().flatten() {
+            write!(out, "\nResistance: {} {}", OptionalSymbol(resistance.r#type.as_ref()), resistance.value.as_deref().unwrap_or(""))?;
+        }
+        if let Some(cost) = self.retreatCost.as_ref().filter(|c| !c.is_empty()) {
+            write!(out, "\nRetreat: {}", EnergySymbols(cost))?;
+        }
+        for rule in self.rules.iter().flatten() {
+            write!(out, "\n{rule}")?;
+        }
+        if let Some(legalities) = &self.legalities {
+            let mut separator = "\nLegalities: ";
+            for (format, name) in [(Format::Standard, "Standard"), (Format::Expanded, "Expanded"), (Format::Unlimited, "Unlimited")] {
+                if let Some(legality) = legalities.get(format) {
+                    write!(out, "{separator}{name} {legality}")?;
+                    separator = ", ";
+                }
+            }
+        }
+
+        Ok(())
+    }
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
-struct Attack {
-    name: Option<String>,
-    cost: Option<Vec<String>>,
-    convertedEnergyCost: Option<u32>,
-    damage: Option<String>,
-    text: Option<String>,
+/// Energy written as their symbols, e.g. `{G}{C}{C}`.
+struct EnergySymbols<'a>(&'a [EnergyType]);
+
+impl fmt::Display for EnergySymbols<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.iter().try_for_each(|energy| write!(f, "{{{}}}", energy.symbol()))
+    }
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
-struct Weakness {
-    r#type: Option<String>,
-    value: Option<String>,
+struct OptionalSymbol<'a>(Option<&'a EnergyType>);
+
+impl fmt::Display for OptionalSymbol<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.0 {
+            Some(energy) => write!(f, "{{{}}}", energy.symbol()),
+            None => f.write_str("?"),
+        }
+    }
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+/// One-line summary, e.g. "Obsidian Flames (sv3) — Scarlet & Violet — 197 cards — 2023/08/11".
+impl fmt::Display for Set {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name.as_deref().or(self.id.as_deref()).unwrap_or("?"))?;
+        if let (Some(id), Some(_)) = (&self.id, &self.name) {
+            write!(f, " ({id})")?;
+        }
+        if let Some(series) = &self.series {
+            write!(f, " — {series}")?;
+        }
+        if let Some(total) = self.printedTotal.or(self.total) {
+            write!(f, " — {total} cards")?;
+        }
+        if let Some(released) = &self.releaseDate {
+            write!(f, " — {released}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The few fields of a card needed to list it, fetched with `select=id,name,images,set` to keep
+/// responses small when paging through thousands of cards; see `CardSummary::SELECT`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct CardSummary {
+    pub id: Option<CardId>,
+    pub name: Option<String>,
+    pub images: Option<Images>,
+    #[serde(rename(deserialize = "set"), deserialize_with = "deserialize_set_id", default)]
+    pub setId: Option<String>,
+}
+
+impl CardSummary {
+    /// Fields to request through the `select` parameter.
+    pub const SELECT: &'static [&'static str] = &["id", "name", "images", "set"];
+}
+
+/// Reads only the id of the nested set object.
+fn deserialize_set_id<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<String>, D::Error> {
+    #[derive(Deserialize)]
+    struct SetRef {
+        id: Option<String>,
+    }
+
+    Ok(Option::<SetRef>::deserialize(deserializer)?.and_then(|set| set.id))
+}
+
+/// Trait printed under the name of some XY-era Pokémon, e.g. "α Growth" or "Δ Evolution".
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
+pub struct AncientTrait {
+    pub name: Option<String>,
+    pub text: Option<String>,
+}
+
+/// Ability printed on a Pokémon card; `type` is e.g. "Ability" or "Poké-Power".
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
+pub struct Ability {
+    pub name: Option<String>,
+    pub text: Option<String>,
+    pub r#type: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
+pub struct Attack {
+    pub name: Option<String>,
+    pub cost: Option<EnergyCost>,
+    pub convertedEnergyCost: Option<u32>,
+    /// Printed damage, e.g. "60", "100+" or "20×"; empty for effect-only attacks.
+    pub damage: Option<String>,
+    pub text: Option<String>,
+}
+
+/// Energy an attack needs, e.g. `[Fire, Fire, Colorless]`; a list of types in the JSON. The API
+/// writes the cost of free attacks either as `[]` or as `["Free"]`, both cost nothing here.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct EnergyCost(pub Vec<EnergyType>);
+
+impl EnergyCost {
+    /// Amount of Energy needed, the `convertedEnergyCost` of the attack.
+    pub fn converted(&self) -> u32 {
+        self.energies().count() as u32
+    }
+
+    /// Amount of Energy needed of each type, Colorless included.
+    pub fn by_type(&self) -> HashMap<EnergyType, u8> {
+        let mut counts = HashMap::new();
+        for energy in self.energies() {
+            *counts.entry(energy.clone()).or_insert(0u8) += 1;
+        }
+        counts
+    }
+
+    /// Whether the available Energy pays for the cost: each typed Energy needs one of its type,
+    /// and Colorless is paid by any Energy left.
+    pub fn payable_with(&self, available: &HashMap<EnergyType, u8>) -> bool {
+        let mut needed_colorless = 0u32;
+        let mut spent = 0u32;
+
+        for (energy, count) in self.by_type() {
+            if energy == EnergyType::Colorless {
+                needed_colorless += u32::from(count);
+            } else if available.get(&energy).copied().unwrap_or(0) < count {
+                return false;
+            } else {
+                spent += u32::from(count);
+            }
+        }
+
+        let total: u32 = available.values().copied().map(u32::from).sum();
+        total - spent >= needed_colorless
+    }
+
+    /// Energy of the cost, without the "Free" placeholder.
+    fn energies(&self) -> impl Iterator<Item = &EnergyType> {
+        self.0.iter().filter(|e| !matches!(e, EnergyType::Other(other) if other.eq_ignore_ascii_case("free")))
+    }
+}
+
+impl std::ops::Deref for EnergyCost {
+    type Target = [EnergyType];
+
+    fn deref(&self) -> &[EnergyType] {
+        &self.0
+    }
+}
+
+impl From<Vec<EnergyType>> for EnergyCost {
+    fn from(energies: Vec<EnergyType>) -> Self {
+        Self(energies)
+    }
+}
+
+/// `value` is the printed modifier, e.g. "×2".
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
+pub struct Weakness {
+    pub r#type: Option<EnergyType>,
+    pub value: Option<String>,
+}
+
+/// `value` is the printed modifier, e.g. "-30".
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
+pub struct Resistance {
+    pub r#type: Option<EnergyType>,
+    pub value: Option<String>,
+}
+
+/// Expansion cards belong to; `ptcgoCode` is the code used by decklist exports, e.g. "PHF".
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
 pub struct Set {
-    pub id: Option<String>,
+    pub id: Option<SetId>,
     pub name: Option<String>,
     pub series: Option<String>,
     pub printedTotal: Option<u32>,
@@ -81,76 +462,365 @@ pub struct Set {
     pub releaseDate: Option<String>,
     pub updatedAt: Option<String>,
     pub images: Option<SetImages>,
+    /// Fields sent by the API that this crate doesn't model yet, serialized back as they came.
+    /// Always empty with the `strict-models` feature, which rejects them instead.
+    #[cfg_attr(not(feature = "strict-models"), serde(flatten))]
+    #[cfg_attr(feature = "strict-models", serde(skip))]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+/// Hashes the id alone, which equal sets share; the fields the crate doesn't model can't be hashed.
+impl Hash for Set {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+impl Set {
+    /// Amount of cards numbered beyond `printedTotal`, i.e. the secret rares; 0 when either total
+    /// is missing.
+    pub fn secret_cards(&self) -> u32 {
+        match (self.printedTotal, self.total) {
+            (Some(printed), Some(total)) => total.saturating_sub(printed),
+            _ => 0,
+        }
+    }
+
+    /// Era of the set's `series`; `Era::Other` holding an empty name when the series is missing.
+    pub fn era(&self) -> Era {
+        let Ok(era) = self.series.as_deref().unwrap_or_default().parse();
+        era
+    }
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
-struct Legalities {
-    unlimited: Option<String>,
-    standard: Option<String>,
-    expanded: Option<String>,
+/// Series the API groups sets in, oldest first. Parsed case-insensitively from the `series` of a
+/// set; series this crate doesn't know yet, the API's own "Other" series included, are kept as
+/// Other.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Era {
+    Base,
+    Gym,
+    Neo,
+    ECard,
+    Ex,
+    NintendoPromos,
+    Pop,
+    DiamondPearl,
+    Platinum,
+    HeartGoldSoulSilver,
+    BlackWhite,
+    Xy,
+    SunMoon,
+    SwordShield,
+    ScarletViolet,
+    MegaEvolution,
+    Other(String),
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
-struct SetImages {
-    symbol: Option<String>,
-    logo: Option<String>,
+/// `series` of the sets of each era, as written by the API.
+const ERA_SERIES: [(Era, &str); 16] = [
+    (Era::Base, "Base"),
+    (Era::Gym, "Gym"),
+    (Era::Neo, "Neo"),
+    (Era::ECard, "E-Card"),
+    (Era::Ex, "EX"),
+    (Era::NintendoPromos, "NP"),
+    (Era::Pop, "POP"),
+    (Era::DiamondPearl, "Diamond & Pearl"),
+    (Era::Platinum, "Platinum"),
+    (Era::HeartGoldSoulSilver, "HeartGold & SoulSilver"),
+    (Era::BlackWhite, "Black & White"),
+    (Era::Xy, "XY"),
+    (Era::SunMoon, "Sun & Moon"),
+    (Era::SwordShield, "Sword & Shield"),
+    (Era::ScarletViolet, "Scarlet & Violet"),
+    (Era::MegaEvolution, "Mega Evolution"),
+];
+
+impl Era {
+    /// Eras whose sets have cards legal in the Standard format at the time of this release; the
+    /// `legalities` of sets and cards tell for sure, rotations not waiting for new crate versions.
+    pub fn standard_legal_eras() -> Vec<Era> {
+        vec![Era::ScarletViolet, Era::MegaEvolution]
+    }
+
+    /// `series` of the sets of the era, e.g. "Scarlet & Violet".
+    pub fn series(&self) -> &str {
+        match self {
+            Self::Other(series) => series,
+            era => ERA_SERIES.iter().find(|(e, _)| e == era).map(|(_, series)| *series).unwrap_or_default(),
+        }
+    }
+}
+
+impl FromStr for Era {
+    type Err = std::convert::Infallible;
+
+    fn from_str(val: &str) -> Result<Self, Self::Err> {
+        let val = val.trim();
+        Ok(ERA_SERIES.iter()
+                     .find(|(_, series)| series.eq_ignore_ascii_case(val))
+                     .map(|(era, _)| era.clone())
+                     .unwrap_or_else(|| Self::Other(val.into())))
+    }
+}
+
+impl fmt::Display for Era {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.series())
+    }
+}
+
+/// Format of `releaseDate`, e.g. "2014/02/05".
+pub const RELEASE_DATE_FORMAT: &str = "%Y/%m/%d";
+/// Format of `updatedAt`, e.g. "2020/08/14 09:35:00".
+pub const UPDATED_AT_FORMAT: &str = "%Y/%m/%d %H:%M:%S";
+
+#[cfg(feature = "chrono")]
+impl Set {
+    /// `releaseDate` parsed as a date; None when missing or malformed, so sorting sets
+    /// chronologically is `sets.sort_by_key(|s| s.release_date())`.
+    pub fn release_date(&self) -> Option<chrono::NaiveDate> {
+        chrono::NaiveDate::parse_from_str(self.releaseDate.as_ref()?, RELEASE_DATE_FORMAT).ok()
+    }
+
+    /// `updatedAt` parsed as a date and time; None when missing or malformed.
+    pub fn updated_at(&self) -> Option<chrono::NaiveDateTime> {
+        chrono::NaiveDateTime::parse_from_str(self.updatedAt.as_ref()?, UPDATED_AT_FORMAT).ok()
+    }
+}
+
+/// Formats a card or set may be played in; formats it is not legal in are absent.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
+pub struct Legalities {
+    pub unlimited: Option<Legality>,
+    pub standard: Option<Legality>,
+    pub expanded: Option<Legality>,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
-struct Images {
-    small: Option<String>,
-    large: Option<String>,
+impl Legalities {
+    pub fn get(&self, format: Format) -> Option<&Legality> {
+        match format {
+            Format::Standard => self.standard.as_ref(),
+            Format::Expanded => self.expanded.as_ref(),
+            Format::Unlimited => self.unlimited.as_ref(),
+        }
+    }
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+/// Play formats listed in `legalities`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Format {
+    Standard,
+    Expanded,
+    Unlimited,
+}
+
+/// Parsed case-insensitively; values this crate doesn't know yet are kept as Other instead of
+/// failing the deserialization of the whole card.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Legality {
+    Legal,
+    Banned,
+    Other(String),
+}
+
+impl From<&str> for Legality {
+    fn from(val: &str) -> Self {
+        match val.to_lowercase().as_str() {
+            "legal" => Self::Legal,
+            "banned" => Self::Banned,
+            _ => Self::Other(val.into()),
+        }
+    }
+}
+
+impl fmt::Display for Legality {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Legal => write!(f, "Legal"),
+            Self::Banned => write!(f, "Banned"),
+            Self::Other(val) => write!(f, "{val}"),
+        }
+    }
+}
+
+impl Serialize for Legality {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for Legality {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let val = String::deserialize(deserializer)?;
+        Ok(Self::from(val.as_str()))
+    }
+}
+
+/// URLs of the set's symbol and logo artwork.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
+pub struct SetImages {
+    pub symbol: Option<String>,
+    pub logo: Option<String>,
+}
+
+/// URLs of the card's artwork; `large` is the high resolution scan.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
+pub struct Images {
+    pub small: Option<String>,
+    pub large: Option<String>,
+}
+
+impl Images {
+    pub fn url(&self, size: ImageSize) -> Option<&str> {
+        match size {
+            ImageSize::Small => self.small.as_deref(),
+            ImageSize::Large => self.large.as_deref(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ImageSize {
+    Small,
+    Large,
+}
+
+/// TCGPlayer listing of a card, prices are in US Dollars.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
 pub struct TcgPlayer {
-    url: Option<String>,
-    updatedAt: Option<String>,
+    pub url: Option<String>,
+    pub updatedAt: Option<String>,
     pub prices: Option<TcgPlayerPrices>,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
-pub struct CardMarket {
-    url: Option<String>,
-    date: Option<String>,
-    pub prices: Option<Prices>,
-}
-
-#[derive(Clone, Debug, Serialize, Deserialize)]
-pub struct Prices {
-    pub averageSellPrice: Option<f32>,
-    lowPrice: Option<f32>,
-    trendPrice: Option<f32>,
-    germanProLow: Option<f32>,
-    suggestedPrice: Option<f32>,
-    reverseHoloSell: Option<f32>,
-    reverseHoloLow: Option<f32>,
-    reverseHoloTrend: Option<f32>,
-    lowPriceExPlus: Option<f32>,
-    pub avg1: Option<f32>,
-    pub avg7: Option<f32>,
-    pub avg30: Option<f32>,
-    pub reverseHoloAvg1: Option<f32>,
-    pub reverseHoloAvg7: Option<f32>,
-    pub reverseHoloAvg30: Option<f32>, 
-}
-
-#[derive(Clone, Debug, Serialize, Deserialize)]
-pub struct TcgPlayerPrices {
-    pub normal: Option<TcgPricing>,
-    pub reverseHolofoil: Option<TcgPricing>,
-}
-
-#[derive(Clone, Debug, Serialize, Deserialize)]
-pub struct TcgPricing {
-    low: Option<f32>,
-    mid: Option<f32>,
-    high: Option<f32>,
-    pub market: Option<f32>,
-    directLow: Option<f32>,
-}
-
-#[derive(Clone, Debug, Serialize, Deserialize)]
+/// Amount of money in a card's prices: an `f64`, or an exact `rust_decimal::Decimal` with the
+/// `rust_decimal` feature so sums across a collection don't accumulate float error.
+#[cfg(not(feature = "rust_decimal"))]
+pub type Price = f64;
+#[cfg(feature = "rust_decimal")]
+pub type Price = rust_decimal::Decimal;
+
+/// Cardmarket listing of a card, prices are in Euros.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
+pub struct Cardmarket {
+    pub url: Option<String>,
+    pub updatedAt: Option<String>,
+    pub prices: Option<CardmarketPrices>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
+pub struct CardmarketPrices {
+    pub averageSellPrice: Option<Price>,
+    pub lowPrice: Option<Price>,
+    pub trendPrice: Option<Price>,
+    pub germanProLow: Option<Price>,
+    pub suggestedPrice: Option<Price>,
+    pub reverseHoloSell: Option<Price>,
+    pub reverseHoloLow: Option<Price>,
+    pub reverseHoloTrend: Option<Price>,
+    pub lowPriceExPlus: Option<Price>,
+    pub avg1: Option<Price>,
+    pub avg7: Option<Price>,
+    pub avg30: Option<Price>,
+    pub reverseHoloAvg1: Option<Price>,
+    pub reverseHoloAvg7: Option<Price>,
+    pub reverseHoloAvg30: Option<Price>, 
+}
+
+/// TCGPlayer prices for each printing variant of a card; most cards only list a couple of them.
+pub type TcgPlayerPrices = HashMap<PriceVariantKind, PriceVariant>;
+
+/// Printing variant TCGPlayer lists prices for, keyed as in the API ("holofoil",
+/// "1stEditionHolofoil", ...). Variants this crate doesn't know yet are kept as Other.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum PriceVariantKind {
+    Normal,
+    Holofoil,
+    ReverseHolofoil,
+    FirstEditionNormal,
+    FirstEditionHolofoil,
+    UnlimitedHolofoil,
+    Other(String),
+}
+
+impl PriceVariantKind {
+    /// Known variants from the most to the least common printing, the order
+    /// `Card::best_market_price` picks them in.
+    pub const PRECEDENCE: [PriceVariantKind; 6] = [
+        Self::Normal,
+        Self::Holofoil,
+        Self::ReverseHolofoil,
+        Self::UnlimitedHolofoil,
+        Self::FirstEditionHolofoil,
+        Self::FirstEditionNormal,
+    ];
+}
+
+impl FromStr for PriceVariantKind {
+    type Err = std::convert::Infallible;
+
+    fn from_str(val: &str) -> Result<Self, Self::Err> {
+        Ok(match val.to_lowercase().as_str() {
+            "normal" => Self::Normal,
+            "holofoil" => Self::Holofoil,
+            "reverseholofoil" => Self::ReverseHolofoil,
+            "1steditionnormal" => Self::FirstEditionNormal,
+            "1steditionholofoil" => Self::FirstEditionHolofoil,
+            "unlimitedholofoil" => Self::UnlimitedHolofoil,
+            _ => Self::Other(val.into()),
+        })
+    }
+}
+
+impl fmt::Display for PriceVariantKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let key = match self {
+            Self::Normal => "normal",
+            Self::Holofoil => "holofoil",
+            Self::ReverseHolofoil => "reverseHolofoil",
+            Self::FirstEditionNormal => "1stEditionNormal",
+            Self::FirstEditionHolofoil => "1stEditionHolofoil",
+            Self::UnlimitedHolofoil => "unlimitedHolofoil",
+            Self::Other(val) => val,
+        };
+
+        write!(f, "{key}")
+    }
+}
+
+impl Serialize for PriceVariantKind {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for PriceVariantKind {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let val = String::deserialize(deserializer)?;
+        let Ok(kind) = val.parse();
+        Ok(kind)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
+pub struct PriceVariant {
+    pub low: Option<Price>,
+    pub mid: Option<Price>,
+    pub high: Option<Price>,
+    pub market: Option<Price>,
+    pub directLow: Option<Price>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct CardToPrice {
     pub ptcgoCode: Option<String>,
     pub number: Option<u32>,
@@ -158,26 +828,581 @@ pub struct CardToPrice {
     pub setId: Option<String>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct SetMapping {
     name: Option<String>,
     pub id: Option<String>,
     pub code: Option<String>,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, PartialOrd)]
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Type(pub String);
 
-#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, PartialOrd)]
-pub struct Supertype(pub String);
+/// Energy types found on cards, attack costs, weaknesses and resistances. Parsed
+/// case-insensitively; types this crate doesn't know yet are kept as Other.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum EnergyType {
+    Colorless,
+    Darkness,
+    Dragon,
+    Fairy,
+    Fighting,
+    Fire,
+    Grass,
+    Lightning,
+    Metal,
+    Psychic,
+    Water,
+    Other(String),
+}
+
+impl FromStr for EnergyType {
+    type Err = std::convert::Infallible;
+
+    fn from_str(val: &str) -> Result<Self, Self::Err> {
+        Ok(match val.to_lowercase().as_str() {
+            "colorless" => Self::Colorless,
+            "darkness" => Self::Darkness,
+            "dragon" => Self::Dragon,
+            "fairy" => Self::Fairy,
+            "fighting" => Self::Fighting,
+            "fire" => Self::Fire,
+            "grass" => Self::Grass,
+            "lightning" => Self::Lightning,
+            "metal" => Self::Metal,
+            "psychic" => Self::Psychic,
+            "water" => Self::Water,
+            _ => Self::Other(val.into()),
+        })
+    }
+}
+
+impl fmt::Display for EnergyType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Self::Colorless => "Colorless",
+            Self::Darkness => "Darkness",
+            Self::Dragon => "Dragon",
+            Self::Fairy => "Fairy",
+            Self::Fighting => "Fighting",
+            Self::Fire => "Fire",
+            Self::Grass => "Grass",
+            Self::Lightning => "Lightning",
+            Self::Metal => "Metal",
+            Self::Psychic => "Psychic",
+            Self::Water => "Water",
+            Self::Other(val) => val,
+        };
+
+        write!(f, "{name}")
+    }
+}
+
+impl EnergyType {
+    /// Letter standing for the energy in card text, e.g. "R" for Fire and "C" for Colorless; the
+    /// name itself for energy this crate doesn't know.
+    pub fn symbol(&self) -> &str {
+        match self {
+            Self::Colorless => "C",
+            Self::Darkness => "D",
+            Self::Dragon => "N",
+            Self::Fairy => "Y",
+            Self::Fighting => "F",
+            Self::Fire => "R",
+            Self::Grass => "G",
+            Self::Lightning => "L",
+            Self::Metal => "M",
+            Self::Psychic => "P",
+            Self::Water => "W",
+            Self::Other(val) => val,
+        }
+    }
+}
+
+impl From<Type> for EnergyType {
+    fn from(val: Type) -> Self {
+        let Ok(energy) = val.0.parse();
+        energy
+    }
+}
+
+impl From<EnergyType> for Type {
+    fn from(val: EnergyType) -> Self {
+        Type(val.to_string())
+    }
+}
+
+impl Serialize for EnergyType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for EnergyType {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let val = String::deserialize(deserializer)?;
+        let Ok(energy) = val.parse();
+        Ok(energy)
+    }
+}
+
+/// Kind of card. Parsed ignoring case and accents, since the API writes "Pokémon"; supertypes
+/// this crate doesn't know yet are kept as Other.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Supertype {
+    Pokemon,
+    Trainer,
+    Energy,
+    Other(String),
+}
+
+impl FromStr for Supertype {
+    type Err = std::convert::Infallible;
+
+    fn from_str(val: &str) -> Result<Self, Self::Err> {
+        Ok(match val.to_lowercase().replace('é', "e").as_str() {
+            "pokemon" => Self::Pokemon,
+            "trainer" => Self::Trainer,
+            "energy" => Self::Energy,
+            _ => Self::Other(val.into()),
+        })
+    }
+}
+
+impl From<&str> for Supertype {
+    fn from(val: &str) -> Self {
+        let Ok(supertype) = val.parse();
+        supertype
+    }
+}
 
-#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, PartialOrd)]
+impl PartialEq<str> for Supertype {
+    fn eq(&self, other: &str) -> bool {
+        let Ok(other) = other.parse::<Supertype>();
+        *self == other
+    }
+}
+
+impl PartialEq<&str> for Supertype {
+    fn eq(&self, other: &&str) -> bool {
+        self == *other
+    }
+}
+
+impl fmt::Display for Supertype {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Self::Pokemon => "Pokémon",
+            Self::Trainer => "Trainer",
+            Self::Energy => "Energy",
+            Self::Other(val) => val,
+        };
+
+        write!(f, "{name}")
+    }
+}
+
+impl Serialize for Supertype {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for Supertype {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let val = String::deserialize(deserializer)?;
+        Ok(Supertype::from(val.as_str()))
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Subtype(pub String);
 
-#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, PartialOrd)]
+/// Subtypes printed on cards, e.g. "Stage 1" or "Supporter". Parsed ignoring case, apart from
+/// "ex" and "EX" which are different mechanics; subtypes this crate doesn't know yet are kept
+/// as Other.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum KnownSubtype {
+    AceSpec,
+    Ancient,
+    Baby,
+    Basic,
+    Break,
+    /// "EX", Pokémon-EX of the Black & White and XY eras.
+    Ex,
+    /// "ex", Pokémon ex of the Scarlet & Violet era; "EX" in any other casing is `Ex`.
+    ExLowercase,
+    Future,
+    FusionStrike,
+    GoldenrodGameCorner,
+    Gx,
+    Item,
+    Legend,
+    LevelUp,
+    Mega,
+    PokemonTool,
+    PokemonToolF,
+    PrismStar,
+    Radiant,
+    RapidStrike,
+    Restored,
+    RocketsSecretMachine,
+    SingleStrike,
+    Special,
+    Stadium,
+    Stage1,
+    Stage2,
+    Supporter,
+    TagTeam,
+    TechnicalMachine,
+    Tera,
+    V,
+    VUnion,
+    Vmax,
+    Vstar,
+    Other(String),
+}
+
+impl FromStr for KnownSubtype {
+    type Err = std::convert::Infallible;
+
+    fn from_str(val: &str) -> Result<Self, Self::Err> {
+        if val == "ex" {
+            return Ok(Self::ExLowercase);
+        }
+
+        Ok(match val.to_lowercase().as_str() {
+            "ace spec" => Self::AceSpec,
+            "ancient" => Self::Ancient,
+            "baby" => Self::Baby,
+            "basic" => Self::Basic,
+            "break" => Self::Break,
+            "ex" => Self::Ex,
+            "future" => Self::Future,
+            "fusion strike" => Self::FusionStrike,
+            "goldenrod game corner" => Self::GoldenrodGameCorner,
+            "gx" => Self::Gx,
+            "item" => Self::Item,
+            "legend" => Self::Legend,
+            "level-up" => Self::LevelUp,
+            "mega" => Self::Mega,
+            "pokémon tool" => Self::PokemonTool,
+            "pokémon tool f" => Self::PokemonToolF,
+            "prism star" => Self::PrismStar,
+            "radiant" => Self::Radiant,
+            "rapid strike" => Self::RapidStrike,
+            "restored" => Self::Restored,
+            "rocket's secret machine" => Self::RocketsSecretMachine,
+            "single strike" => Self::SingleStrike,
+            "special" => Self::Special,
+            "stadium" => Self::Stadium,
+            "stage 1" => Self::Stage1,
+            "stage 2" => Self::Stage2,
+            "supporter" => Self::Supporter,
+            "tag team" => Self::TagTeam,
+            "technical machine" => Self::TechnicalMachine,
+            "tera" => Self::Tera,
+            "v" => Self::V,
+            "v-union" => Self::VUnion,
+            "vmax" => Self::Vmax,
+            "vstar" => Self::Vstar,
+            _ => Self::Other(val.into()),
+        })
+    }
+}
+
+impl fmt::Display for KnownSubtype {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Self::AceSpec => "ACE SPEC",
+            Self::Ancient => "Ancient",
+            Self::Baby => "Baby",
+            Self::Basic => "Basic",
+            Self::Break => "BREAK",
+            Self::Ex => "EX",
+            Self::ExLowercase => "ex",
+            Self::Future => "Future",
+            Self::FusionStrike => "Fusion Strike",
+            Self::GoldenrodGameCorner => "Goldenrod Game Corner",
+            Self::Gx => "GX",
+            Self::Item => "Item",
+            Self::Legend => "LEGEND",
+            Self::LevelUp => "Level-Up",
+            Self::Mega => "MEGA",
+            Self::PokemonTool => "Pokémon Tool",
+            Self::PokemonToolF => "Pokémon Tool F",
+            Self::PrismStar => "Prism Star",
+            Self::Radiant => "Radiant",
+            Self::RapidStrike => "Rapid Strike",
+            Self::Restored => "Restored",
+            Self::RocketsSecretMachine => "Rocket's Secret Machine",
+            Self::SingleStrike => "Single Strike",
+            Self::Special => "Special",
+            Self::Stadium => "Stadium",
+            Self::Stage1 => "Stage 1",
+            Self::Stage2 => "Stage 2",
+            Self::Supporter => "Supporter",
+            Self::TagTeam => "TAG TEAM",
+            Self::TechnicalMachine => "Technical Machine",
+            Self::Tera => "Tera",
+            Self::V => "V",
+            Self::VUnion => "V-UNION",
+            Self::Vmax => "VMAX",
+            Self::Vstar => "VSTAR",
+            Self::Other(val) => val,
+        };
+
+        write!(f, "{name}")
+    }
+}
+
+impl From<Subtype> for KnownSubtype {
+    fn from(val: Subtype) -> Self {
+        let Ok(subtype) = val.0.parse();
+        subtype
+    }
+}
+
+impl From<KnownSubtype> for Subtype {
+    fn from(val: KnownSubtype) -> Self {
+        Subtype(val.to_string())
+    }
+}
+
+impl Serialize for KnownSubtype {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for KnownSubtype {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let val = String::deserialize(deserializer)?;
+        let Ok(subtype) = val.parse();
+        Ok(subtype)
+    }
+}
+
+/// Ordered by `rank`, rarities missing from `RARITY_RANKS` coming last in alphabetical order.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq, Hash)]
 pub struct Rarity(pub String);
 
-pub fn extract_card_market_price(c: Card) -> Option<f32> {
+/// Rarities from the most to the least common; a new rarity is ranked by adding it here.
+pub const RARITY_RANKS: &[&str] = &[
+    "Common",
+    "Uncommon",
+    "Rare",
+    "Rare Holo",
+    "Promo",
+    "Rare Shining",
+    "Rare Holo Star",
+    "Rare Holo EX",
+    "Rare Holo LV.X",
+    "LEGEND",
+    "Rare Prime",
+    "Rare BREAK",
+    "Rare ACE",
+    "ACE SPEC Rare",
+    "Rare Holo GX",
+    "Rare Prism Star",
+    "Rare Holo V",
+    "Rare Holo VMAX",
+    "Rare Holo VSTAR",
+    "Double Rare",
+    "Amazing Rare",
+    "Radiant Rare",
+    "Rare Ultra",
+    "Ultra Rare",
+    "Rare Shiny",
+    "Shiny Rare",
+    "Rare Shiny GX",
+    "Shiny Ultra Rare",
+    "Trainer Gallery Rare Holo",
+    "Illustration Rare",
+    "Rare Rainbow",
+    "Rare Secret",
+    "Special Illustration Rare",
+    "Hyper Rare",
+];
+
+impl Rarity {
+    /// Position in `RARITY_RANKS`, 0 for Common; None for rarities it doesn't list.
+    pub fn rank(&self) -> Option<u8> {
+        RARITY_RANKS.iter().position(|r| *r == self.0).map(|rank| rank as u8)
+    }
+}
+
+impl Ord for Rarity {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        let key = |r: &Self| r.rank().unwrap_or(u8::MAX);
+        key(self).cmp(&key(other)).then_with(|| self.0.cmp(&other.0))
+    }
+}
+
+impl PartialOrd for Rarity {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Conversions making the string newtypes usable like the strings they wrap, e.g.
+/// `rarity == "Rare Holo"` or `"Rare Holo".parse::<Rarity>()`.
+macro_rules! string_newtype {
+    ($($name:ident),*) => {$(
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "{}", self.0)
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = std::convert::Infallible;
+
+            fn from_str(val: &str) -> Result<Self, Self::Err> {
+                Ok(Self(val.into()))
+            }
+        }
+
+        impl AsRef<str> for $name {
+            fn as_ref(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl From<&str> for $name {
+            fn from(val: &str) -> Self {
+                Self(val.into())
+            }
+        }
+
+        impl From<String> for $name {
+            fn from(val: String) -> Self {
+                Self(val)
+            }
+        }
+
+        impl PartialEq<str> for $name {
+            fn eq(&self, other: &str) -> bool {
+                self.0 == other
+            }
+        }
+
+        impl PartialEq<&str> for $name {
+            fn eq(&self, other: &&str) -> bool {
+                self.0 == *other
+            }
+        }
+    )*};
+}
+
+string_newtype!(Type, Subtype, Rarity);
+
+/// Id of a card, `{setId}-{number}` e.g. "xy1-1". Parsing checks that shape; ids read from the
+/// API are taken as they come.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(transparent)]
+pub struct CardId(String);
+
+impl CardId {
+    /// Id of the card's set, the part before the first dash, e.g. "sv3pt5" for "sv3pt5-6".
+    pub fn set_id(&self) -> SetId {
+        let set = self.0.split_once('-').map_or(self.0.as_str(), |(set, _)| set);
+        SetId(set.into())
+    }
+}
+
+impl FromStr for CardId {
+    type Err = Error;
+
+    /// Fails with `InvalidId` unless val is a set id and a collector number joined by a dash.
+    fn from_str(val: &str) -> Result<Self, Self::Err> {
+        match val.split_once('-') {
+            Some((set, number)) if is_set_id(set) && is_id_part(number) => Ok(Self(val.into())),
+            _ => Err(Error::InvalidId { id: val.into() }),
+        }
+    }
+}
+
+/// Id of a set, e.g. "xy1" or "sv3pt5". Parsing checks it's a single word; ids read from the API
+/// are taken as they come.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(transparent)]
+pub struct SetId(String);
+
+impl FromStr for SetId {
+    type Err = Error;
+
+    /// Fails with `InvalidId` when val is empty or holds a dash, a space or URL punctuation.
+    fn from_str(val: &str) -> Result<Self, Self::Err> {
+        if !is_set_id(val) {
+            return Err(Error::InvalidId { id: val.into() });
+        }
+        Ok(Self(val.into()))
+    }
+}
+
+/// Non-empty and without characters that would change the URL of a lookup.
+fn is_id_part(val: &str) -> bool {
+    !val.is_empty() && !val.contains(|c: char| c.is_whitespace() || matches!(c, '/' | '?' | '#' | '%'))
+}
+
+fn is_set_id(val: &str) -> bool {
+    is_id_part(val) && !val.contains('-')
+}
+
+/// Conversions making the id newtypes usable like the strings they wrap, e.g. `id == "xy1-1"`
+/// or `&id[..3]`; they are only built by parsing, so there is no `From<&str>`.
+macro_rules! id_newtype {
+    ($($name:ident),*) => {$(
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "{}", self.0)
+            }
+        }
+
+        impl std::ops::Deref for $name {
+            type Target = str;
+
+            fn deref(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl AsRef<str> for $name {
+            fn as_ref(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl std::borrow::Borrow<str> for $name {
+            fn borrow(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl From<$name> for String {
+            fn from(id: $name) -> String {
+                id.0
+            }
+        }
+
+        impl PartialEq<str> for $name {
+            fn eq(&self, other: &str) -> bool {
+                self.0 == other
+            }
+        }
+
+        impl PartialEq<&str> for $name {
+            fn eq(&self, other: &&str) -> bool {
+                self.0 == *other
+            }
+        }
+    )*};
+}
+
+id_newtype!(CardId, SetId);
+
+pub fn extract_card_market_price(c: Card) -> Option<Price> {
     let mut res = None;
     
 
@@ -196,12 +1421,12 @@ pub fn extract_card_market_price(c: Card) -> Option<f32> {
     res
 }
 
-pub fn extract_tcg_player_price(c: Card) -> Option<f32> {
+pub fn extract_tcg_player_price(c: Card) -> Option<Price> {
     let mut res = None;
 
     if let Some(tcg_player) = c.tcgplayer {
         if let Some(tcg_player_prices) = tcg_player.prices {
-            if let Some(normal) = tcg_player_prices.normal {
+            if let Some(normal) = tcg_player_prices.get(&PriceVariantKind::Normal) {
                 if let Some(market) = normal.market {
                     res = Some(market);
                 }
@@ -211,7 +1436,7 @@ pub fn extract_tcg_player_price(c: Card) -> Option<f32> {
                 else if let Some(high) = normal.high {
                     res = Some(high);
                 }
-                else if let Some(low) = normal.high {
+                else if let Some(low) = normal.low {
                     res = Some(low);
                 }
             }
@@ -220,8 +1445,8 @@ pub fn extract_tcg_player_price(c: Card) -> Option<f32> {
     res
 }
 
-pub fn extract_price(c: Card) -> f32 {
-    let res = -99.9;
+pub fn extract_price(c: Card) -> Price {
+    let res = Price::from(-999) / Price::from(10); // -99.9
     if let Some(card_market) = c.cardmarket {
         if let Some(prices) = card_market.prices {
             if let Some(avg) = prices.averageSellPrice {
@@ -234,7 +1459,7 @@ pub fn extract_price(c: Card) -> f32 {
         }
     } else if let Some(tcg_player) = c.tcgplayer {
         if let Some(tcg_player_prices) = tcg_player.prices {
-            if let Some(normal) = tcg_player_prices.normal {
+            if let Some(normal) = tcg_player_prices.get(&PriceVariantKind::Normal) {
                 if let Some(market) = normal.market {
                     return market;
                 }
@@ -244,7 +1469,7 @@ pub fn extract_price(c: Card) -> f32 {
                 else if let Some(high) = normal.high {
                     return high;
                 }
-                else if let Some(low) = normal.high {
+                else if let Some(low) = normal.low {
                     return low;
                 }
             }
@@ -0,0 +1,87 @@
+use std::fmt;
+
+/// Server-side sort order, rendered as the comma separated `orderBy` parameter where
+/// descending fields are prefixed with `-`.
+///
+/// ```ignore
+/// let order = OrderBy::desc(OrderBy::SET_RELEASE_DATE).then_asc(OrderBy::NUMBER);
+/// assert_eq!(order.to_string(), "-set.releaseDate,number");
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct OrderBy(Vec<(String, bool)>);
+
+impl OrderBy {
+    /// Name of a card or a set.
+    pub const NAME: &'static str = "name";
+    /// Collector number of a card within its set.
+    pub const NUMBER: &'static str = "number";
+    /// Release date of a set.
+    pub const RELEASE_DATE: &'static str = "releaseDate";
+    /// Release date of the set a card belongs to.
+    pub const SET_RELEASE_DATE: &'static str = "set.releaseDate";
+    /// Hit points of a card.
+    pub const HP: &'static str = "hp";
+    /// Id of a card or a set, the order of bulk fetches unless `ClientBuilder::bulk_order_by`
+    /// says otherwise.
+    pub const ID: &'static str = "id";
+
+    pub fn asc(field: &str) -> OrderBy {
+        OrderBy(vec![(field.into(), false)])
+    }
+
+    pub fn desc(field: &str) -> OrderBy {
+        OrderBy(vec![(field.into(), true)])
+    }
+
+    /// Sorts results that compare equal so far by field, ascending.
+    pub fn then_asc(self, field: &str) -> OrderBy {
+        self.then(OrderBy::asc(field))
+    }
+
+    /// Sorts results that compare equal so far by field, descending.
+    pub fn then_desc(self, field: &str) -> OrderBy {
+        self.then(OrderBy::desc(field))
+    }
+
+    /// Whether no field is sorted on, as for `OrderBy::default()`.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Sorts results that compare equal so far by the fields of other.
+    pub fn then(mut self, other: OrderBy) -> OrderBy {
+        self.0.extend(other.0);
+        self
+    }
+}
+
+impl fmt::Display for OrderBy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let fields: Vec<String> = self.0.iter()
+            .map(|(field, desc)| if *desc { format!("-{field}") } else { field.clone() })
+            .collect();
+
+        write!(f, "{}", fields.join(","))
+    }
+}
+
+impl From<OrderBy> for String {
+    fn from(order: OrderBy) -> String {
+        order.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_order_by_renders_api_format() {
+        let order = OrderBy::desc(OrderBy::SET_RELEASE_DATE).then_asc(OrderBy::NUMBER);
+        assert!(order.to_string() == "-set.releaseDate,number");
+
+        let order = OrderBy::asc(OrderBy::NAME).then(OrderBy::desc(OrderBy::HP).then_asc("id"));
+        assert!(order.to_string() == "name,-hp,id");
+        assert!(String::from(OrderBy::desc(OrderBy::RELEASE_DATE)) == "-releaseDate");
+    }
+}
@@ -0,0 +1,112 @@
+use std::cmp::Ordering;
+
+use serde::{ Deserialize, Serialize };
+
+use crate::{ Client, Q };
+use crate::models::errors::Error;
+use crate::models::models::{ Card, Price, TcgPlayerPrices };
+
+/// Prices of a single printing; `tcgplayer_market` is in US Dollars, `cardmarket_trend` in Euros.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CardPrice {
+    pub id: Option<String>,
+    pub name: Option<String>,
+    pub set_name: Option<String>,
+    pub rarity: Option<String>,
+    /// Lowest market price among the printing's TCGPlayer variants (normal, holofoil, ...).
+    pub tcgplayer_market: Option<Price>,
+    pub cardmarket_trend: Option<Price>,
+}
+
+impl CardPrice {
+    pub fn from_card(card: &Card) -> CardPrice {
+        CardPrice {
+            id: card.id.clone().map(String::from),
+            name: card.name.clone(),
+            set_name: card.set.as_ref().and_then(|s| s.name.clone()),
+            rarity: card.rarity.clone(),
+            tcgplayer_market: card.tcgplayer.as_ref().and_then(|t| t.prices.as_ref()).and_then(lowest_market),
+            cardmarket_trend: card.cardmarket.as_ref().and_then(|c| c.prices.as_ref()).and_then(|p| p.trendPrice),
+        }
+    }
+
+    /// Price used to sort printings: the TCGPlayer market price, else the Cardmarket trend.
+    pub fn price(&self) -> Option<Price> {
+        self.tcgplayer_market.or(self.cardmarket_trend)
+    }
+}
+
+fn lowest_market(prices: &TcgPlayerPrices) -> Option<Price> {
+    prices.values()
+          .filter_map(|variant| variant.market)
+          .min_by(cmp_prices)
+}
+
+/// Prices parsed from JSON are never NaN, so partial_cmp always has an answer.
+fn cmp_prices(a: &Price, b: &Price) -> Ordering {
+    a.partial_cmp(b).unwrap_or(Ordering::Equal)
+}
+
+/// Cheapest first, printings without any price last.
+fn by_price(a: &CardPrice, b: &CardPrice) -> Ordering {
+    match (a.price(), b.price()) {
+        (Some(a), Some(b)) => cmp_prices(&a, &b),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+impl Client {
+    /// Prices of every printing of the card named name, cheapest first; printings without price
+    /// data come last.
+    pub async fn price_summary(&self, name: &str) -> Result<Vec<CardPrice>, Error> {
+        let cards = self.search_cards(Q::field("name").exact(name)).await?;
+
+        let mut prices: Vec<CardPrice> = cards.iter().map(CardPrice::from_card).collect();
+        prices.sort_by(by_price);
+
+        Ok(prices)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wiremock::{ Mock, ResponseTemplate };
+    use wiremock::matchers::{ method, path, query_param };
+
+    use crate::testing::MockApi;
+    use crate::tests::price;
+
+    #[tokio::test]
+    async fn test_price_summary_sorts_cheapest_first() {
+        let body = r#"{
+            "data": [
+                { "id": "a-1", "name": "Pikachu", "rarity": "Common" },
+                { "id": "b-1", "name": "Pikachu", "set": { "name": "Base" }, "cardmarket": { "prices": { "trendPrice": 3.5 } } },
+                { "id": "c-1", "name": "Pikachu", "tcgplayer": { "prices": {
+                    "normal": { "market": 1.25 },
+                    "reverseHolofoil": { "market": 0.75 }
+                } }, "cardmarket": { "prices": { "trendPrice": 9.0 } } }
+            ],
+            "page": 1, "pageSize": 250, "count": 3, "totalCount": 3
+        }"#;
+
+        let mock = MockApi::start().await;
+        Mock::given(method("GET"))
+            .and(path("/cards"))
+            .and(query_param("q", "!name:Pikachu"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(body, "application/json"))
+            .with_priority(1)
+            .mount(mock.server())
+            .await;
+
+        let prices = mock.client().price_summary("Pikachu").await.unwrap();
+        let ids: Vec<&str> = prices.iter().filter_map(|p| p.id.as_deref()).collect();
+
+        assert!(ids == vec!["c-1", "b-1", "a-1"]);
+        assert!(prices[0].tcgplayer_market == Some(price("0.75")) && prices[0].cardmarket_trend == Some(price("9.0")));
+        assert!(prices[1].set_name == Some("Base".into()));
+        assert!(prices[2].price().is_none() && prices[2].rarity == Some("Common".into()));
+    }
+}
@@ -0,0 +1,53 @@
+use std::fmt;
+use std::sync::{ Arc, Mutex };
+use std::sync::atomic::{ AtomicUsize, Ordering };
+
+use crate::VecContainer;
+
+/// Where a call paging through results stands, passed after each page to the callback given to
+/// `ClientBuilder::on_progress`, e.g. to drive a progress bar.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Progress {
+    /// Items received so far by the call.
+    pub fetched: usize,
+    /// Items matching the call as reported by the API; None for routes that are not paged.
+    pub total_count: Option<u32>,
+    pub current_page: u32,
+}
+
+/// Callback shared by the clones of a Client.
+#[derive(Clone)]
+pub(crate) struct ProgressCallback(Arc<Mutex<dyn FnMut(Progress) + Send>>);
+
+impl ProgressCallback {
+    pub(crate) fn new(callback: impl FnMut(Progress) + Send + 'static) -> Self {
+        Self(Arc::new(Mutex::new(callback)))
+    }
+}
+
+impl fmt::Debug for ProgressCallback {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "ProgressCallback")
+    }
+}
+
+/// Items fetched by a single call, reported to the callback as each page arrives.
+pub(crate) struct ProgressTracker {
+    callback: Option<ProgressCallback>,
+    fetched: AtomicUsize,
+}
+
+impl ProgressTracker {
+    pub(crate) fn new(callback: Option<ProgressCallback>) -> Self {
+        Self { callback, fetched: AtomicUsize::new(0) }
+    }
+
+    pub(crate) fn page<T>(&self, page: u32, container: &VecContainer<T>) {
+        let fetched = self.fetched.fetch_add(container.data.len(), Ordering::Relaxed) + container.data.len();
+
+        if let Some(ProgressCallback(callback)) = &self.callback {
+            let mut callback = callback.lock().unwrap_or_else(|e| e.into_inner());
+            callback(Progress { fetched, total_count: container.total_count, current_page: page });
+        }
+    }
+}
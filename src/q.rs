@@ -0,0 +1,240 @@
+use std::collections::HashMap;
+use std::fmt;
+
+/// Characters with a meaning in the Lucene-like syntax of the `q` parameter.
+const SPECIAL_CHARS: &[char] = &['+', '-', '&', '|', '!', '(', ')', '{', '}', '[', ']', '^', '"', '~', '*', '?', ':', '\\', '/'];
+
+/// Builds the Lucene-like `q` search parameter, taking care of quoting and escaping.
+///
+/// ```ignore
+/// let q = Q::field("name").eq("gardevoir ex")
+///          .and(Q::field("set.id").eq("sv4"))
+///          .and(Q::field("hp").at_least(100));
+/// assert_eq!(q.to_string(), r#"name:"gardevoir ex" set.id:sv4 hp:[100 TO *]"#);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Q {
+    /// Already rendered clause, e.g. `name:charizard`.
+    Term(String),
+    /// Every clause must match; rendered space separated.
+    And(Vec<Q>),
+    /// Any clause may match; rendered `(a OR b)`.
+    Or(Vec<Q>),
+    /// Clause must not match; rendered `-clause`.
+    Not(Box<Q>),
+}
+
+/// Field of the resource a clause applies to, e.g. `name` or `set.id`; obtained by `Q::field`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Field(String);
+
+impl Q {
+    pub fn field(name: &str) -> Field {
+        Field(name.into())
+    }
+
+    /// Clause used as is, for syntax this builder doesn't cover.
+    pub fn raw(q: impl Into<String>) -> Q {
+        Q::Term(q.into())
+    }
+
+    pub fn and(self, other: Q) -> Q {
+        match self {
+            Q::And(mut clauses) => {
+                clauses.push(other);
+                Q::And(clauses)
+            },
+            q => Q::And(vec![q, other]),
+        }
+    }
+
+    pub fn or(self, other: Q) -> Q {
+        match self {
+            Q::Or(mut clauses) => {
+                clauses.push(other);
+                Q::Or(clauses)
+            },
+            q => Q::Or(vec![q, other]),
+        }
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn not(self) -> Q {
+        Q::Not(Box::new(self))
+    }
+}
+
+impl Field {
+    /// Matches the value as a whole; values with whitespace are quoted as a phrase.
+    pub fn eq(self, value: impl fmt::Display) -> Q {
+        Q::Term(format!("{}:{}", self.0, quote(&value.to_string())))
+    }
+
+    /// Exact match, e.g. `!name:charizard` doesn't match "Charizard ex".
+    pub fn exact(self, value: impl fmt::Display) -> Q {
+        Q::Term(format!("!{}:{}", self.0, quote(&value.to_string())))
+    }
+
+    /// Pattern where `*` matches any amount of characters and `?` a single one; every other
+    /// special character, whitespace included, is escaped.
+    pub fn wildcard(self, pattern: &str) -> Q {
+        let escaped: String = pattern.chars().map(|c| match c {
+            '*' | '?' => c.to_string(),
+            c if c.is_whitespace() || SPECIAL_CHARS.contains(&c) => format!("\\{c}"),
+            c => c.to_string(),
+        }).collect();
+
+        Q::Term(format!("{}:{escaped}", self.0))
+    }
+
+    /// Values starting with prefix, which is matched literally: `*` and `?` are escaped too.
+    pub fn starts_with(self, prefix: &str) -> Q {
+        Q::Term(format!("{}:{}*", self.0, escape_all(prefix)))
+    }
+
+    /// Values containing part, which is matched literally like in `starts_with`.
+    pub fn contains(self, part: &str) -> Q {
+        Q::Term(format!("{}:*{}*", self.0, escape_all(part)))
+    }
+
+    /// Inclusive range, unbounded on the sides given as None, e.g. `hp:[100 TO *]`.
+    pub fn range<T: fmt::Display>(self, lower: Option<T>, upper: Option<T>) -> Q {
+        let bound = |b: Option<T>| b.map(|b| quote(&b.to_string())).unwrap_or(String::from("*"));
+        Q::Term(format!("{}:[{} TO {}]", self.0, bound(lower), bound(upper)))
+    }
+
+    pub fn at_least(self, lower: impl fmt::Display) -> Q {
+        self.range(Some(lower.to_string()), None)
+    }
+
+    pub fn at_most(self, upper: impl fmt::Display) -> Q {
+        self.range(None, Some(upper.to_string()))
+    }
+}
+
+/// Escapes whitespace and every special character, for values that can't be quoted such as the
+/// literal part of a wildcard pattern.
+fn escape_all(value: &str) -> String {
+    value.chars().map(|c| if c.is_whitespace() || SPECIAL_CHARS.contains(&c) { format!("\\{c}") } else { c.to_string() }).collect()
+}
+
+/// Quotes values containing whitespace and escapes special characters in the rest.
+fn quote(value: &str) -> String {
+    if value.chars().any(char::is_whitespace) {
+        let escaped = value.replace('\\', "\\\\").replace('"', "\\\"");
+        return format!("\"{escaped}\"");
+    }
+
+    value.chars().map(|c| if SPECIAL_CHARS.contains(&c) { format!("\\{c}") } else { c.to_string() }).collect()
+}
+
+/// How `Client::cards_named` compares card names; every mode ignores case.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NameMatch {
+    /// Whole name, e.g. "Charizard" doesn't match "Charizard ex".
+    Exact,
+    /// Names starting with the given one, e.g. "Charizard" matches "Charizard ex".
+    Prefix,
+    /// Names containing the given one, e.g. "Charizard" matches "Dark Charizard".
+    Contains,
+}
+
+impl NameMatch {
+    /// Clause matching name on the `name` field.
+    pub fn q(self, name: &str) -> Q {
+        match self {
+            NameMatch::Exact => Q::field("name").exact(name),
+            NameMatch::Prefix => Q::field("name").starts_with(name),
+            NameMatch::Contains => Q::field("name").contains(name),
+        }
+    }
+}
+
+impl fmt::Display for Q {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Q::Term(term) => write!(f, "{term}"),
+            Q::And(clauses) => {
+                let clauses: Vec<String> = clauses.iter().map(|c| c.to_string()).collect();
+                write!(f, "{}", clauses.join(" "))
+            },
+            Q::Or(clauses) => {
+                let clauses: Vec<String> = clauses.iter()
+                                                  .map(|c| match c {
+                                                      Q::And(_) => format!("({c})"),
+                                                      _ => c.to_string(),
+                                                  })
+                                                  .collect();
+                write!(f, "({})", clauses.join(" OR "))
+            },
+            Q::Not(clause) => match clause.as_ref() {
+                Q::And(_) => write!(f, "-({clause})"),
+                _ => write!(f, "-{clause}"),
+            },
+        }
+    }
+}
+
+impl From<Q> for String {
+    fn from(q: Q) -> String {
+        q.to_string()
+    }
+}
+
+/// Arguments for `query` searching for q.
+impl From<Q> for HashMap<String, String> {
+    fn from(q: Q) -> HashMap<String, String> {
+        HashMap::from([(String::from("q"), q.to_string())])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_q_quotes_and_combines() {
+        let q = Q::field("name").eq("gardevoir ex")
+                 .and(Q::field("set.id").eq("sv4"))
+                 .and(Q::field("hp").at_least(100));
+
+        assert!(q.to_string() == r#"name:"gardevoir ex" set.id:sv4 hp:[100 TO *]"#);
+    }
+
+    #[test]
+    fn test_q_escapes_special_characters() {
+        assert!(Q::field("name").eq("Type: Null").to_string() == r#"name:"Type: Null""#);
+        assert!(Q::field("name").eq("Farfetch'd").to_string() == "name:Farfetch'd");
+        assert!(Q::field("name").eq("Porygon-Z").to_string() == r"name:Porygon\-Z");
+        assert!(Q::field("name").eq(r#"say "hi""#).to_string() == r#"name:"say \"hi\"""#);
+        assert!(Q::field("name").wildcard("gardevoir ex*").to_string() == r"name:gardevoir\ ex*");
+        assert!(Q::field("name").exact("charizard").to_string() == "!name:charizard");
+    }
+
+    #[test]
+    fn test_q_or_not_and_ranges() {
+        let q = Q::field("types").eq("fire").or(Q::field("types").eq("water"))
+                 .and(Q::field("subtypes").eq("mega").not())
+                 .and(Q::field("hp").range(Some(60), Some(120)));
+
+        assert!(q.to_string() == "(types:fire OR types:water) -subtypes:mega hp:[60 TO 120]");
+        assert!(Q::field("nationalPokedexNumbers").at_most(151).to_string() == "nationalPokedexNumbers:[* TO 151]");
+
+        let mixed = Q::field("types").eq("fire").and(Q::field("hp").at_least(100)).or(Q::field("name").eq("pikachu"));
+        assert!(mixed.to_string() == "((types:fire hp:[100 TO *]) OR name:pikachu)");
+        let nested = Q::field("set.id").eq("sv4").and(mixed);
+        assert!(nested.to_string() == "set.id:sv4 ((types:fire hp:[100 TO *]) OR name:pikachu)");
+
+        let args: HashMap<String, String> = Q::raw("set.id:sv4").into();
+        assert!(args["q"] == "set.id:sv4");
+    }
+
+    #[test]
+    fn test_name_match_escapes_names() {
+        assert!(NameMatch::Exact.q("Type: Null").to_string() == r#"!name:"Type: Null""#);
+        assert!(NameMatch::Prefix.q("Type: Null").to_string() == r"name:Type\:\ Null*");
+        assert!(NameMatch::Contains.q("Farfetch'd").to_string() == "name:*Farfetch'd*");
+        assert!(NameMatch::Prefix.q("Who?").to_string() == r"name:Who\?*");
+        assert!(NameMatch::Exact.q("Porygon-Z").to_string() == r"!name:Porygon\-Z");
+    }
+}
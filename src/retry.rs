@@ -0,0 +1,245 @@
+use std::sync::atomic::{ AtomicU32, Ordering };
+use std::time::{ Duration, SystemTime, UNIX_EPOCH };
+use reqwest::header::{ HeaderMap, RETRY_AFTER };
+
+use crate::models::errors::Error;
+
+/// Wait used when a 429 response carries neither `Retry-After` nor `X-RateLimit-Reset`;
+/// doubled on every further attempt.
+pub(crate) const DEFAULT_RATE_LIMIT_WAIT: Duration = Duration::from_secs(1);
+
+/// Longest wait before a retry unless configured, see `ClientBuilder::max_retry_delay`.
+pub(crate) const DEFAULT_MAX_RETRY_DELAY: Duration = Duration::from_secs(60);
+
+/// `X-RateLimit-Reset` values from this one on are unix timestamps (September 2001), smaller
+/// ones amounts of seconds.
+const RESET_TIMESTAMP_FROM: u64 = 1_000_000_000;
+
+/// Controls how the Client reacts to requests rejected by the API or lost in transit.
+/// By default only rate limited requests are retried.
+#[derive(Clone, Debug)]
+pub struct RetryPolicy {
+    pub(crate) max_retries: u32,
+    pub(crate) retry_on_rate_limit: bool,
+    pub(crate) retry_transient: bool,
+    pub(crate) base_delay: Duration,
+    /// Longest wait before a retry, whatever the backoff or the API asks for.
+    pub(crate) max_delay: Duration,
+    /// Retries shared by every request of a bulk fetch, see `ClientBuilder::retry_budget`.
+    pub(crate) retry_budget: Option<u32>,
+    /// Failures in a row stopping a bulk fetch, see `ClientBuilder::circuit_breaker`.
+    pub(crate) circuit_breaker: Option<u32>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            retry_on_rate_limit: true,
+            retry_transient: false,
+            base_delay: Duration::from_millis(250),
+            max_delay: DEFAULT_MAX_RETRY_DELAY,
+            retry_budget: None,
+            circuit_breaker: None,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Retries connection errors, timeouts and 5xx responses up to max_retries times, waiting
+    /// base_delay * 2^attempt plus some random jitter in between. Rate limited requests are
+    /// retried as well.
+    pub fn exponential(max_retries: u32, base_delay: Duration) -> Self {
+        Self {
+            max_retries,
+            retry_on_rate_limit: true,
+            retry_transient: true,
+            base_delay,
+            max_delay: DEFAULT_MAX_RETRY_DELAY,
+            retry_budget: None,
+            circuit_breaker: None,
+        }
+    }
+
+    /// Never sends a request again.
+    pub fn none() -> Self {
+        Self {
+            max_retries: 0,
+            retry_on_rate_limit: false,
+            retry_transient: false,
+            base_delay: Duration::ZERO,
+            max_delay: DEFAULT_MAX_RETRY_DELAY,
+            retry_budget: None,
+            circuit_breaker: None,
+        }
+    }
+
+    /// Wait before retry number `attempt` (starting at 0) of a transient failure.
+    pub(crate) fn backoff(&self, attempt: u32) -> Duration {
+        let delay = self.base_delay.saturating_mul(2u32.saturating_pow(attempt));
+        (delay + jitter(delay / 2)).min(self.max_delay)
+    }
+
+    /// Wait before retry number `attempt` of a rate limited request: the one the headers ask
+    /// for, or `default_rate_limit_wait`, at most max_delay.
+    pub(crate) fn rate_limit_delay(&self, headers: &HeaderMap, attempt: u32) -> Duration {
+        rate_limit_wait(headers).unwrap_or(default_rate_limit_wait(attempt)).min(self.max_delay)
+    }
+
+    /// State of the budget and breaker for a new bulk fetch, None when neither is set.
+    pub(crate) fn operation(&self) -> Option<Operation> {
+        (self.retry_budget.is_some() || self.circuit_breaker.is_some()).then(|| Operation {
+            retry_budget: self.retry_budget,
+            circuit_breaker: self.circuit_breaker,
+            retries: AtomicU32::new(0),
+            failures: AtomicU32::new(0),
+            pages: AtomicU32::new(0),
+        })
+    }
+}
+
+/// Retries taken and failures in a row across the requests of a single bulk fetch, concurrent
+/// pages included.
+#[derive(Debug)]
+pub(crate) struct Operation {
+    retry_budget: Option<u32>,
+    circuit_breaker: Option<u32>,
+    retries: AtomicU32,
+    failures: AtomicU32,
+    pages: AtomicU32,
+}
+
+impl Operation {
+    /// Takes a retry from the budget; false once it is spent.
+    pub(crate) fn take_retry(&self) -> bool {
+        let Some(budget) = self.retry_budget else { return true };
+        self.retries.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |taken| (taken < budget).then_some(taken + 1)).is_ok()
+    }
+
+    /// Fails with `CircuitOpen` once the breaker tripped, so that no further request is sent.
+    pub(crate) fn check(&self) -> Result<(), Error> {
+        let failures = self.failures.load(Ordering::SeqCst);
+        match self.circuit_breaker {
+            Some(threshold) if failures >= threshold => Err(Error::CircuitOpen { failures, pages_fetched: self.pages.load(Ordering::SeqCst) }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Counts a connection error, timeout or 5xx response, then `check`s the breaker.
+    pub(crate) fn failure(&self) -> Result<(), Error> {
+        self.failures.fetch_add(1, Ordering::SeqCst);
+        self.check()
+    }
+
+    /// Any other response closes the streak of failures.
+    pub(crate) fn success(&self) {
+        self.failures.store(0, Ordering::SeqCst);
+    }
+
+    pub(crate) fn page_fetched(&self) {
+        self.pages.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+/// Random wait in `[0, max)` spreading out the retries of concurrent requests.
+fn jitter(max: Duration) -> Duration {
+    if max.is_zero() {
+        return Duration::ZERO;
+    }
+
+    rand::random_range(Duration::ZERO..max)
+}
+
+/// Reads how long the API asks us to wait before sending the next request.
+/// `Retry-After` is expressed in seconds; `X-RateLimit-Reset` may either be a unix timestamp,
+/// no wait once it passed, or an amount of seconds.
+pub(crate) fn rate_limit_wait(headers: &HeaderMap) -> Option<Duration> {
+    let seconds = |name| -> Option<u64> {
+        headers.get(name)?.to_str().ok()?.trim().parse().ok()
+    };
+
+    if let Some(retry_after) = seconds(RETRY_AFTER.as_str()) {
+        return Some(Duration::from_secs(retry_after));
+    }
+
+    let reset = seconds("x-ratelimit-reset")?;
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).ok()?.as_secs();
+
+    if reset >= RESET_TIMESTAMP_FROM {
+        Some(Duration::from_secs(reset.saturating_sub(now)))
+    } else {
+        Some(Duration::from_secs(reset))
+    }
+}
+
+/// Wait before retrying attempt number `attempt` (starting at 0) when the API gave no hint.
+pub(crate) fn default_rate_limit_wait(attempt: u32) -> Duration {
+    DEFAULT_RATE_LIMIT_WAIT * 2u32.saturating_pow(attempt)
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::header::HeaderValue;
+
+    use super::*;
+
+    #[test]
+    fn test_retry_after_seconds() {
+        let mut headers = HeaderMap::new();
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("7"));
+
+        assert!(rate_limit_wait(&headers) == Some(Duration::from_secs(7)));
+    }
+
+    #[test]
+    fn test_ratelimit_reset_timestamp() {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+        let mut headers = HeaderMap::new();
+        headers.insert("x-ratelimit-reset", HeaderValue::from_str(&format!("{}", now + 30)).unwrap());
+
+        let wait = rate_limit_wait(&headers).unwrap();
+        assert!(wait <= Duration::from_secs(30) && wait >= Duration::from_secs(29));
+    }
+
+    #[test]
+    fn test_ratelimit_reset_in_the_past_and_in_seconds() {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+        let reset = |value: String| {
+            let mut headers = HeaderMap::new();
+            headers.insert("x-ratelimit-reset", HeaderValue::from_str(&value).unwrap());
+            headers
+        };
+
+        assert!(rate_limit_wait(&reset(format!("{}", now - 5))) == Some(Duration::ZERO));
+        assert!(rate_limit_wait(&reset(String::from("45"))) == Some(Duration::from_secs(45)));
+
+        let policy = RetryPolicy::default();
+        assert!(policy.rate_limit_delay(&reset(format!("{}", now + 3600)), 0) == DEFAULT_MAX_RETRY_DELAY);
+        assert!(policy.rate_limit_delay(&reset(format!("{}", now - 5)), 0) == Duration::ZERO);
+        assert!(policy.rate_limit_delay(&HeaderMap::new(), 2) == Duration::from_secs(4));
+
+        let capped = RetryPolicy { max_delay: Duration::from_secs(10), ..RetryPolicy::default() };
+        assert!(capped.rate_limit_delay(&reset(String::from("45")), 0) == Duration::from_secs(10));
+        assert!(capped.backoff(10) == Duration::from_secs(10));
+    }
+
+    #[test]
+    fn test_no_rate_limit_headers() {
+        assert!(rate_limit_wait(&HeaderMap::new()).is_none());
+        assert!(default_rate_limit_wait(0) == Duration::from_secs(1));
+        assert!(default_rate_limit_wait(2) == Duration::from_secs(4));
+    }
+
+    #[test]
+    fn test_exponential_backoff() {
+        let policy = RetryPolicy::exponential(3, Duration::from_millis(100));
+
+        for attempt in 0..3 {
+            let delay = policy.backoff(attempt);
+            let base = Duration::from_millis(100 * 2u64.pow(attempt));
+
+            assert!(delay >= base && delay < base + base / 2);
+        }
+        assert!(RetryPolicy::none().backoff(5) == Duration::ZERO);
+    }
+}
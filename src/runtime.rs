@@ -0,0 +1,25 @@
+//! What differs between native targets and wasm32 in the browser, where there is no tokio timer
+//! and `std::time::Instant` panics.
+
+use std::time::Duration;
+
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) use std::time::Instant;
+#[cfg(target_arch = "wasm32")]
+pub(crate) use web_time::Instant;
+
+/// Whether the request of e failed before reaching the server, so it can be retried.
+pub(crate) fn is_unreachable(e: &reqwest::Error) -> bool {
+    #[cfg(not(target_arch = "wasm32"))]
+    return e.is_connect() || e.is_timeout();
+    // fetch doesn't tell why it failed
+    #[cfg(target_arch = "wasm32")]
+    return e.is_request() || e.is_timeout();
+}
+
+pub(crate) async fn sleep(duration: Duration) {
+    #[cfg(not(target_arch = "wasm32"))]
+    tokio::time::sleep(duration).await;
+    #[cfg(target_arch = "wasm32")]
+    gloo_timers::future::sleep(duration).await;
+}
@@ -0,0 +1,151 @@
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::marker::PhantomData;
+use futures::Stream;
+use serde::de::DeserializeOwned;
+
+use crate::{ Client, Resource };
+use crate::models::errors::Error;
+
+/// Typed alternative to the raw `HashMap<String, String>` accepted by `query`.
+/// Each supported query parameter of the Pokemon TCG v2 API has its own method, so a misspelled
+/// parameter name is a compile error instead of a silently ignored argument.
+///
+/// ```ignore
+/// let cards = client.search::<Card>()
+///                   .query("name:charizard supertype:pokemon")
+///                   .page_size(50)
+///                   .order_by("-set.releaseDate")
+///                   .execute()
+///                   .await;
+/// ```
+#[derive(Debug)]
+pub struct SearchBuilder<'a, T> {
+    client: &'a Client,
+    params: HashMap<String, String>,
+    prefetch: usize,
+    _resource: PhantomData<T>,
+}
+
+impl<'a, T: Resource + DeserializeOwned + Clone + Debug> SearchBuilder<'a, T> {
+    pub(crate) fn new(client: &'a Client) -> Self {
+        Self {
+            client,
+            params: HashMap::new(),
+            prefetch: 0,
+            _resource: PhantomData,
+        }
+    }
+
+    /// Search query, e.g. `name:charizard supertype:pokemon`, sent as `q`.
+    pub fn query(mut self, q: impl Into<String>) -> Self {
+        self.params.insert(String::from("q"), q.into());
+        self
+    }
+
+    /// Page of results to start fetching from, sent as `page`.
+    pub fn page(mut self, page: u32) -> Self {
+        self.params.insert(String::from("page"), format!("{page}"));
+        self
+    }
+
+    /// Maximum amount of results per page, sent as `pageSize`.
+    pub fn page_size(mut self, page_size: u32) -> Self {
+        self.params.insert(String::from("pageSize"), format!("{page_size}"));
+        self
+    }
+
+    /// Field(s) to sort by, either an [`crate::OrderBy`] or a raw string such as
+    /// `-set.releaseDate,number`, sent as `orderBy`.
+    pub fn order_by(mut self, order_by: impl Into<String>) -> Self {
+        self.params.insert(String::from("orderBy"), order_by.into());
+        self
+    }
+
+    /// Fields that should be returned for each result, sent as a comma separated `select`.
+    pub fn select(mut self, fields: &[&str]) -> Self {
+        self.params.insert(String::from("select"), fields.join(","));
+        self
+    }
+
+    /// Query parameters assembled so far.
+    pub fn params(&self) -> &HashMap<String, String> {
+        &self.params
+    }
+
+    /// Pages `stream` requests ahead of the one being consumed, so the next page downloads while
+    /// the current one is drained; at most [`crate::MAX_PREFETCH`], none by default.
+    pub fn prefetch(mut self, pages: usize) -> Self {
+        self.prefetch = pages.min(crate::MAX_PREFETCH);
+        self
+    }
+
+    /// Runs the search through the same request path as `query`.
+    pub async fn execute(self) -> Result<Vec<T>, Error> {
+        self.client.query::<T>(self.params).await
+    }
+
+    /// Lazily yields the results like `Client::stream`, requesting pages ahead as `prefetch` set.
+    pub fn stream(self) -> impl Stream<Item = Result<T, Error>> + 'a where T: 'a {
+        self.client.prefetching_stream::<T>(self.params, self.prefetch)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::StreamExt;
+
+    use crate::models::models::Card;
+    use crate::testing::MockApi;
+    use crate::OrderBy;
+
+    use super::*;
+
+    #[test]
+    fn test_search_builder_params() {
+        let api = Client::new(String::new());
+
+        let search = api.search::<Card>()
+                        .query("name:charizard supertype:pokemon")
+                        .page(2)
+                        .page_size(50)
+                        .order_by("-set.releaseDate")
+                        .select(&["id", "name"]);
+        let params = search.params();
+
+        assert!(params.len() == 5);
+        assert!(params["q"] == "name:charizard supertype:pokemon");
+        assert!(params["page"] == "2");
+        assert!(params["pageSize"] == "50");
+        assert!(params["orderBy"] == "-set.releaseDate");
+        assert!(params["select"] == "id,name");
+
+        let search = api.search::<Card>().order_by(OrderBy::desc(OrderBy::SET_RELEASE_DATE).then_asc(OrderBy::NUMBER));
+        assert!(search.params()["orderBy"] == "-set.releaseDate,number");
+    }
+
+    #[tokio::test]
+    async fn test_search_stream_prefetches_next_page() {
+        let mock = MockApi::start().await;
+        let api = mock.client();
+        let requested_pages = || async {
+            let requests = mock.server().received_requests().await.unwrap();
+            let mut pages: Vec<String> = requests.iter()
+                                                 .filter_map(|r| r.url.query_pairs().find(|(k, _)| k == "page").map(|(_, v)| v.into_owned()))
+                                                 .collect();
+            pages.sort();
+            pages
+        };
+
+        let mut cards = std::pin::pin!(api.search::<Card>().page_size(2).prefetch(1).stream());
+        let first = cards.next().await.unwrap().unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+
+        assert!(first.id.as_deref() == Some("xy1-1"));
+        assert!(requested_pages().await == vec!["1", "2"]);
+
+        let rest: Vec<Card> = cards.map(|card| card.unwrap()).collect().await;
+        assert!(rest.len() == 3);
+        assert!(requested_pages().await == vec!["1", "2", "3"]);
+    }
+}
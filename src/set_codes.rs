@@ -0,0 +1,126 @@
+//! Set codes printed on cards and used by decklists, e.g. "OBF", mapped to the set ids of the API.
+//!
+//! ```ignore
+//! // fetches the sets once, then every line of the list reuses the mapping
+//! for (code, number) in [("OBF", "125"), ("PAL", "185")] {
+//!     let card = api.card_by_set_code(code, number).await?;
+//! }
+//! ```
+
+use std::collections::HashMap;
+use std::sync::{ Arc, RwLock };
+
+use crate::Client;
+use crate::models::errors::Error;
+use crate::models::models::{ sv_sets, swsh_sets, Set };
+
+/// Set ids by upper-case set code.
+type Mapping = Arc<HashMap<String, String>>;
+
+/// Set ids by set code, built from the `ptcgoCode` of every set the first time a code is looked
+/// up. Sets the API has no code for fall back on the mappings bundled with the crate. Clones
+/// share the same mapping, so that the lookups of every line of a decklist, and of the clones of a
+/// Client, fetch the sets a single time.
+#[derive(Clone, Debug, Default)]
+pub struct SetCodes {
+    ids: Arc<RwLock<Option<Mapping>>>,
+}
+
+impl SetCodes {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Id of the set with code, e.g. "sv3" for "OBF" or "obf", fetching the sets when the mapping
+    /// isn't built yet; None for a code no set has.
+    pub async fn set_id(&self, client: &Client, code: &str) -> Result<Option<String>, Error> {
+        let ids = match self.cached() {
+            Some(ids) => ids,
+            None => self.refresh(client).await?,
+        };
+        Ok(ids.get(&code.trim().to_uppercase()).cloned())
+    }
+
+    /// Builds the mapping again from the sets of the API, e.g. after a new expansion came out.
+    pub async fn refresh(&self, client: &Client) -> Result<Mapping, Error> {
+        let ids = Arc::new(mapping(&client.all::<Set>().await?));
+        *self.ids.write().unwrap_or_else(|e| e.into_inner()) = Some(ids.clone());
+        Ok(ids)
+    }
+
+    /// Code of the set with id, e.g. "OBF" for "sv3", from the mapping once built and from the
+    /// mappings bundled with the crate before; never sends a request.
+    pub fn code(&self, set_id: &str) -> Option<String> {
+        let ids = self.cached().unwrap_or_else(|| Arc::new(mapping(&[])));
+        ids.iter().filter(|(_, id)| *id == set_id).map(|(code, _)| code).min().cloned()
+    }
+
+    /// Whether the mapping was built, i.e. looking a code up won't send a request.
+    pub fn is_loaded(&self) -> bool {
+        self.cached().is_some()
+    }
+
+    fn cached(&self) -> Option<Mapping> {
+        self.ids.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+}
+
+fn mapping(sets: &[Set]) -> HashMap<String, String> {
+    let mut ids: HashMap<String, String> = sv_sets().into_iter().chain(swsh_sets()).collect();
+    for set in sets {
+        if let (Some(code), Some(id)) = (&set.ptcgoCode, &set.id) {
+            ids.insert(code.to_uppercase(), id.to_string());
+        }
+    }
+    ids
+}
+
+/// Collector number as part of a card id: "025" is card 25, "TG01" stays as is.
+pub(crate) fn id_number(number: &str) -> &str {
+    let number = number.trim();
+    match number.trim_start_matches('0') {
+        trimmed if number.bytes().all(|b| b.is_ascii_digit()) && !trimmed.is_empty() => trimmed,
+        _ => number,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::MockApi;
+
+    use super::*;
+
+    async fn set_requests(mock: &MockApi) -> usize {
+        mock.server().received_requests().await.unwrap().iter().filter(|r| r.url.path() == "/sets").count()
+    }
+
+    #[tokio::test]
+    async fn test_card_by_set_code() {
+        let mock = MockApi::start().await;
+        let api = mock.client();
+        assert!(!api.set_codes().is_loaded());
+
+        let venusaur = api.card_by_set_code("XY", "1").await.unwrap().unwrap();
+        assert!(venusaur.id.as_deref() == Some("xy1-1"));
+
+        // VIV has no ptcgoCode in the fixtures, the bundled mapping knows it
+        let charizard = api.clone().card_by_set_code("viv", "025").await.unwrap().unwrap();
+        assert!(charizard.id.as_deref() == Some("swsh4-25"));
+
+        assert!(api.card_by_set_code("XY", "999").await.unwrap().is_none());
+        assert!(matches!(api.card_by_set_code("ZZZ", "1").await, Err(Error::MissingSetMapping { set }) if set == "ZZZ"));
+        assert!(set_requests(&mock).await == 1 && api.set_codes().is_loaded());
+
+        assert!(api.set_codes().refresh(&api).await.unwrap().get("FLF") == Some(&String::from("xy2")));
+        assert!(set_requests(&mock).await == 2);
+
+        assert!(api.set_codes().code("xy2").as_deref() == Some("FLF") && api.set_codes().code("xy99").is_none());
+        assert!(SetCodes::new().code("swsh4").as_deref() == Some("VIV") && SetCodes::new().code("xy2").is_none());
+    }
+
+    #[test]
+    fn test_id_number() {
+        assert!(id_number("125") == "125" && id_number("025") == "25" && id_number(" 7 ") == "7");
+        assert!(id_number("TG01") == "TG01" && id_number("0") == "0" && id_number("SV001") == "SV001");
+    }
+}
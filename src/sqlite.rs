@@ -0,0 +1,198 @@
+//! SQLite persistence of fetched cards and sets, enabled by the `sqlite` feature.
+//!
+//! ```ignore
+//! let store = SqliteStore::open("collection.db")?;
+//! store.upsert_cards(&api.search_cards("set.id:sv4").await?)?;
+//! let charizard = store.get_card("sv4-54")?;
+//! ```
+
+use std::path::Path;
+
+use rusqlite::{ params, Connection, OptionalExtension };
+use serde::de::DeserializeOwned;
+
+use crate::models::errors::Error;
+use crate::models::models::{ Card, Set };
+
+/// Schema changes, applied in order; `schema_version` holds how many of them a database has had.
+/// Append a new entry for every change instead of editing the existing ones.
+const MIGRATIONS: &[&str] = &[
+    "CREATE TABLE cards (
+        id TEXT PRIMARY KEY NOT NULL,
+        name TEXT,
+        set_id TEXT,
+        supertype TEXT,
+        rarity TEXT,
+        json TEXT NOT NULL
+    );
+    CREATE INDEX cards_name ON cards (name);
+    CREATE INDEX cards_set_id ON cards (set_id);
+    CREATE INDEX cards_supertype ON cards (supertype);
+    CREATE INDEX cards_rarity ON cards (rarity);
+    CREATE TABLE sets (
+        id TEXT PRIMARY KEY NOT NULL,
+        name TEXT,
+        series TEXT,
+        json TEXT NOT NULL
+    );",
+];
+
+/// Cards and sets stored as their full JSON, next to indexed columns (id, name, set id,
+/// supertype and rarity) used to look them up.
+#[derive(Debug)]
+pub struct SqliteStore {
+    conn: Connection,
+}
+
+impl SqliteStore {
+    /// Opens or creates the database at path and brings its schema up to date.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, Error> {
+        Self::migrate(Connection::open(path).map_err(failed)?)
+    }
+
+    /// Database living only as long as the store, e.g. for tests.
+    pub fn open_in_memory() -> Result<Self, Error> {
+        Self::migrate(Connection::open_in_memory().map_err(failed)?)
+    }
+
+    fn migrate(mut conn: Connection) -> Result<Self, Error> {
+        let tx = conn.transaction().map_err(failed)?;
+        tx.execute("CREATE TABLE IF NOT EXISTS schema_version (version INTEGER NOT NULL)", []).map_err(failed)?;
+
+        let latest = MIGRATIONS.len() as u32;
+        let version: Option<u32> = tx.query_row("SELECT version FROM schema_version", [], |row| row.get(0))
+                                     .optional()
+                                     .map_err(failed)?;
+        let version = version.unwrap_or(0);
+        if version > latest {
+            return Err(Error::UnsupportedSchemaVersion { found: version, expected: latest });
+        }
+
+        for migration in &MIGRATIONS[version as usize..] {
+            tx.execute_batch(migration).map_err(failed)?;
+        }
+        tx.execute("DELETE FROM schema_version", []).map_err(failed)?;
+        tx.execute("INSERT INTO schema_version (version) VALUES (?1)", [latest]).map_err(failed)?;
+        tx.commit().map_err(failed)?;
+
+        Ok(Self { conn })
+    }
+
+    /// Inserts the cards, replacing the stored ones with the same id, in a single transaction.
+    ///
+    /// # Errors
+    /// `MissingArgument` when a card has no id, in which case none of the cards are stored.
+    pub fn upsert_cards(&self, cards: &[Card]) -> Result<(), Error> {
+        let tx = self.conn.unchecked_transaction().map_err(failed)?;
+        {
+            let mut insert = tx.prepare_cached(
+                "INSERT OR REPLACE INTO cards (id, name, set_id, supertype, rarity, json) VALUES (?1, ?2, ?3, ?4, ?5, ?6)"
+            ).map_err(failed)?;
+
+            for card in cards {
+                let id = card.id.as_deref().ok_or(Error::MissingArgument { arg: "id".into() })?;
+                let set_id = card.set.as_ref().and_then(|s| s.id.as_deref());
+                insert.execute(params![id, card.name, set_id, card.supertype, card.rarity, to_json(card)?]).map_err(failed)?;
+            }
+        }
+        tx.commit().map_err(failed)
+    }
+
+    /// Inserts the sets, replacing the stored ones with the same id, in a single transaction.
+    ///
+    /// # Errors
+    /// `MissingArgument` when a set has no id, in which case none of the sets are stored.
+    pub fn upsert_sets(&self, sets: &[Set]) -> Result<(), Error> {
+        let tx = self.conn.unchecked_transaction().map_err(failed)?;
+        {
+            let mut insert = tx.prepare_cached(
+                "INSERT OR REPLACE INTO sets (id, name, series, json) VALUES (?1, ?2, ?3, ?4)"
+            ).map_err(failed)?;
+
+            for set in sets {
+                let id = set.id.as_deref().ok_or(Error::MissingArgument { arg: "id".into() })?;
+                insert.execute(params![id, set.name, set.series, to_json(set)?]).map_err(failed)?;
+            }
+        }
+        tx.commit().map_err(failed)
+    }
+
+    pub fn get_card(&self, id: &str) -> Result<Option<Card>, Error> {
+        self.select_one("SELECT json FROM cards WHERE id = ?1", id)
+    }
+
+    pub fn get_set(&self, id: &str) -> Result<Option<Set>, Error> {
+        self.select_one("SELECT json FROM sets WHERE id = ?1", id)
+    }
+
+    /// Stored cards of the set with id set_id, ordered by id.
+    pub fn cards_in_set(&self, set_id: &str) -> Result<Vec<Card>, Error> {
+        let mut select = self.conn.prepare_cached("SELECT json FROM cards WHERE set_id = ?1 ORDER BY id").map_err(failed)?;
+        let rows = select.query_map([set_id], |row| row.get::<_, String>(0)).map_err(failed)?;
+
+        rows.map(|json| from_json(&json.map_err(failed)?)).collect()
+    }
+
+    fn select_one<T: DeserializeOwned>(&self, sql: &str, id: &str) -> Result<Option<T>, Error> {
+        let json: Option<String> = self.conn.query_row(sql, [id], |row| row.get(0)).optional().map_err(failed)?;
+        json.map(|json| from_json(&json)).transpose()
+    }
+}
+
+fn failed(e: rusqlite::Error) -> Error {
+    Error::FailedQueryingDatabase { reason: e.to_string() }
+}
+
+fn to_json<T: serde::Serialize>(value: &T) -> Result<String, Error> {
+    serde_json::to_string(value).map_err(|e| Error::FailedWritingFile { reason: e.to_string() })
+}
+
+fn from_json<T: DeserializeOwned>(json: &str) -> Result<T, Error> {
+    serde_json::from_str(json).map_err(Error::parsing_file)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::MockApi;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_sqlite_store_round_trip() {
+        let mock = MockApi::start().await;
+        let api = mock.client();
+        let store = SqliteStore::open_in_memory().unwrap();
+
+        store.upsert_cards(&api.all::<Card>().await.unwrap()).unwrap();
+        store.upsert_sets(&api.all::<Set>().await.unwrap()).unwrap();
+
+        assert!(store.get_card("xy1-3").unwrap().unwrap().name == Some("Weedle".into()));
+        assert!(store.get_card("xy1-999").unwrap().is_none());
+        assert!(store.get_set("xy2").unwrap().unwrap().ptcgoCode == Some("FLF".into()));
+
+        // the paged fixtures carry no set, unlike the single xy1-1 one which replaces its copy
+        assert!(store.cards_in_set("xy1").unwrap().is_empty());
+        store.upsert_cards(&[api.find_card(&"xy1-1".parse().unwrap()).await.unwrap().unwrap()]).unwrap();
+
+        let ids: Vec<String> = store.cards_in_set("xy1").unwrap().into_iter().filter_map(|c| c.id.map(String::from)).collect();
+        assert!(ids == vec!["xy1-1"]);
+        assert!(store.get_card("xy1-1").unwrap().unwrap().hp == Some("180".into()));
+        assert!(store.cards_in_set("xy2").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_sqlite_store_migrations() {
+        let path = std::env::temp_dir().join(format!("pokemon_tcg_sdk_store_{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let card: Card = serde_json::from_str(r#"{"id": "sv4-1", "name": "Pikachu"}"#).unwrap();
+        SqliteStore::open(&path).unwrap().upsert_cards(&[card]).unwrap();
+        assert!(SqliteStore::open(&path).unwrap().get_card("sv4-1").unwrap().is_some());
+
+        Connection::open(&path).unwrap().execute("UPDATE schema_version SET version = 99", []).unwrap();
+        let newer = SqliteStore::open(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(matches!(newer, Err(Error::UnsupportedSchemaVersion { found: 99, .. })));
+    }
+}
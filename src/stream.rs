@@ -0,0 +1,210 @@
+use std::collections::VecDeque;
+use std::future::Future;
+use std::task::Poll;
+use futures::stream::{ self, FuturesOrdered, Stream, StreamExt };
+
+use crate::VecContainer;
+use crate::models::errors::Error;
+
+/// Most pages a stream requests ahead of the one being consumed.
+pub const MAX_PREFETCH: usize = 2;
+
+/// Internal state of a lazily paged stream.
+struct PageState<T, F, Fut: Future> {
+    fetch: F,
+    prefetch: u32,
+    /// Next page to request.
+    next_page: u32,
+    /// Last page received.
+    received: u32,
+    /// Last page of the results, once a page told the total count.
+    last_page: Option<u32>,
+    in_flight: FuturesOrdered<Fut>,
+    /// Items not yielded yet of the pages received, the page being consumed first.
+    pages: VecDeque<VecDeque<T>>,
+    error: Option<Error>,
+    done: bool,
+}
+
+impl<T, F, Fut> PageState<T, F, Fut>
+where
+    F: FnMut(u32) -> Fut,
+    Fut: Future<Output = Result<VecContainer<T>, Error>>,
+{
+    /// Requests the page the consumer is at and up to prefetch pages past it, never past the last
+    /// page; until the total count is known only the page the consumer is at.
+    fn request_ahead(&mut self) {
+        if self.done {
+            return;
+        }
+
+        let current = self.received + 1 - self.pages.len() as u32;
+        let ahead = match self.last_page {
+            Some(last) => (current + self.prefetch).min(last),
+            None => current,
+        };
+        while self.next_page <= ahead {
+            self.in_flight.push_back((self.fetch)(self.next_page));
+            self.next_page += 1;
+        }
+    }
+
+    fn receive(&mut self, page: Result<VecContainer<T>, Error>) {
+        match page {
+            Ok(container) => {
+                self.received += 1;
+                self.done = container.is_last_page();
+                if let (Some(page_size), Some(total_count)) = (container.page_size, container.total_count) {
+                    self.last_page = Some(total_count.div_ceil(page_size.max(1)));
+                }
+                if !container.data.is_empty() {
+                    self.pages.push_back(container.data.into());
+                }
+            },
+            Err(e) => {
+                self.error = Some(e);
+                self.done = true;
+            },
+        }
+
+        if self.done {
+            self.in_flight = FuturesOrdered::new();
+        }
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        let page = self.pages.front_mut()?;
+        let item = page.pop_front();
+        if page.is_empty() {
+            self.pages.pop_front();
+        }
+        item
+    }
+}
+
+/// Yields the items of each page one by one. Without prefetch the next page is only requested
+/// once the buffered items have been drained; with it, up to prefetch pages (at most
+/// `MAX_PREFETCH`) past the one being drained are requested while it is. Stops after the last
+/// page or after the first error, yielded once the items of the pages before it are.
+pub(crate) fn page_stream<T, F, Fut>(first_page: u32, prefetch: usize, fetch: F) -> impl Stream<Item = Result<T, Error>>
+where
+    F: FnMut(u32) -> Fut,
+    Fut: Future<Output = Result<VecContainer<T>, Error>>,
+{
+    let mut state = PageState {
+        fetch,
+        prefetch: prefetch.min(MAX_PREFETCH) as u32,
+        next_page: first_page,
+        received: first_page.saturating_sub(1),
+        last_page: None,
+        in_flight: FuturesOrdered::new(),
+        pages: VecDeque::new(),
+        error: None,
+        done: false,
+    };
+
+    stream::poll_fn(move |cx| loop {
+        state.request_ahead();
+
+        // Polling the pages in flight before handing out a buffered item keeps them downloading
+        // while the consumer drains the buffer.
+        if let Poll::Ready(Some(page)) = state.in_flight.poll_next_unpin(cx) {
+            state.receive(page);
+            continue;
+        }
+
+        if let Some(item) = state.pop() {
+            return Poll::Ready(Some(Ok(item)));
+        }
+        if let Some(e) = state.error.take() {
+            return Poll::Ready(Some(Err(e)));
+        }
+        if state.in_flight.is_empty() {
+            return Poll::Ready(None);
+        }
+        return Poll::Pending;
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::pin::pin;
+    use futures::StreamExt;
+    use ntest::timeout;
+
+    use crate::models::models::Card;
+
+    use super::*;
+
+    fn mock_page(page: u32) -> Result<VecContainer<Card>, Error> {
+        let cwd = std::env::current_dir().unwrap();
+        let mocks = cwd.join(format!("src/mock/cards_page_{page}.json"));
+        let mock_data_string = std::fs::read_to_string(mocks).map_err(|_| Error::FailedOpeningFile)?;
+        serde_json::from_str(mock_data_string.as_str()).map_err(Error::parsing_file)
+    }
+
+    #[tokio::test]
+    #[timeout(1000)]
+    async fn test_stream_drains_every_page() {
+        let requested = RefCell::new(Vec::new());
+
+        let cards: Vec<Card> = page_stream(1, 0, |page| {
+            requested.borrow_mut().push(page);
+            async move { mock_page(page) }
+        }).map(|card| card.unwrap()).collect().await;
+
+        assert!(cards.len() == 4);
+        assert!(*requested.borrow() == vec![1, 2, 3]);
+    }
+
+    #[tokio::test]
+    #[timeout(1000)]
+    async fn test_stream_fetches_lazily() {
+        let requested = RefCell::new(Vec::new());
+
+        let mut cards = pin!(page_stream(1, 0, |page| {
+            requested.borrow_mut().push(page);
+            async move { mock_page(page) }
+        }));
+
+        let first = cards.next().await.unwrap().unwrap();
+        let second = cards.next().await.unwrap().unwrap();
+
+        assert!(first.id.as_deref() == Some("xy1-1"));
+        assert!(second.id.as_deref() == Some("xy1-2"));
+        assert!(*requested.borrow() == vec![1]);
+
+        cards.next().await.unwrap().unwrap();
+        assert!(*requested.borrow() == vec![1, 2]);
+    }
+
+    #[tokio::test]
+    #[timeout(1000)]
+    async fn test_stream_prefetches_next_pages() {
+        let requested = RefCell::new(Vec::new());
+
+        let mut cards = pin!(page_stream(1, 1, |page| {
+            requested.borrow_mut().push(page);
+            async move { mock_page(page) }
+        }));
+
+        cards.next().await.unwrap().unwrap();
+        assert!(*requested.borrow() == vec![1, 2]);
+
+        let rest: Vec<Card> = cards.map(|card| card.unwrap()).collect().await;
+        assert!(rest.len() == 3);
+        assert!(*requested.borrow() == vec![1, 2, 3]);
+    }
+
+    #[tokio::test]
+    #[timeout(1000)]
+    async fn test_prefetched_error_follows_earlier_items() {
+        let items: Vec<Result<Card, Error>> = page_stream(1, 2, |page| async move {
+            if page == 2 { Err(Error::FailedOpeningFile) } else { mock_page(page) }
+        }).collect().await;
+
+        assert!(items.len() == 3);
+        assert!(items[0].is_ok() && items[1].is_ok() && matches!(items[2], Err(Error::FailedOpeningFile)));
+    }
+}
@@ -0,0 +1,123 @@
+//! Hermetic test harness, enabled by the `testing` feature: a local mock of the Pokemon TCG API
+//! serving the fixtures under `src/mock`, so tests need neither an API Key nor a network.
+//!
+//! ```ignore
+//! let mock = MockApi::start().await;
+//! let card = mock.client().find_card(&"xy1-1".parse()?).await?;
+//! ```
+
+use wiremock::{ Mock, MockServer, ResponseTemplate };
+use wiremock::matchers::{ method, path, query_param };
+
+use crate::{ Client, ClientBuilder };
+
+const CARD_XY1_1: &str = include_str!("mock/xy1-1.json");
+const CARD_SWSH4_25: &str = include_str!("mock/card.json");
+const CARD_SV3PT5_6: &str = include_str!("mock/sv3pt5-6.json");
+const CARDS_PAGES: [&str; 3] = [
+    include_str!("mock/cards_page_1.json"),
+    include_str!("mock/cards_page_2.json"),
+    include_str!("mock/cards_page_3.json"),
+];
+const SET_XY1: &str = include_str!("mock/xy1.json");
+const SETS: &str = include_str!("mock/sets.json");
+const TYPES: &str = include_str!("mock/types.json");
+const SUBTYPES: &str = include_str!("mock/subtypes.json");
+const SUPERTYPES: &str = include_str!("mock/supertypes.json");
+const RARITIES: &str = include_str!("mock/rarities.json");
+
+/// Mock server answering like the API:
+///
+/// - `/cards/xy1-1`, `/cards/swsh4-25` and `/cards/sv3pt5-6`
+/// - `/cards?page=1..3` with xy1-1 to xy1-4, two per page, whatever the search query
+/// - `/sets/xy1`, `/sets`, `/types`, `/subtypes`, `/supertypes` and `/rarities`
+///
+/// Anything else answers 404. Further routes can be mounted on `server()`.
+#[derive(Debug)]
+pub struct MockApi {
+    server: MockServer,
+}
+
+impl MockApi {
+    /// Starts the server on a random local port with every fixture mounted.
+    pub async fn start() -> MockApi {
+        let server = MockServer::start().await;
+
+        let wrapped_card = format!("{{\"data\": {CARD_SWSH4_25}}}");
+        for (route, body) in [
+            ("/cards/xy1-1", CARD_XY1_1),
+            ("/cards/swsh4-25", wrapped_card.as_str()),
+            ("/cards/sv3pt5-6", CARD_SV3PT5_6),
+            ("/sets/xy1", SET_XY1),
+            ("/sets", SETS),
+            ("/types", TYPES),
+            ("/subtypes", SUBTYPES),
+            ("/supertypes", SUPERTYPES),
+            ("/rarities", RARITIES),
+        ] {
+            Mock::given(method("GET"))
+                .and(path(route))
+                .respond_with(json(body))
+                .mount(&server)
+                .await;
+        }
+
+        for (page, body) in CARDS_PAGES.iter().enumerate() {
+            Mock::given(method("GET"))
+                .and(path("/cards"))
+                .and(query_param("page", format!("{}", page + 1)))
+                .respond_with(json(body))
+                .mount(&server)
+                .await;
+        }
+
+        MockApi { server }
+    }
+
+    /// Base URL of the mock, to be passed to `ClientBuilder::base_url`.
+    pub fn uri(&self) -> String {
+        self.server.uri()
+    }
+
+    /// Builder already pointed at the mock, for tests that need further configuration.
+    pub fn builder(&self) -> ClientBuilder {
+        Client::builder().base_url(self.uri())
+    }
+
+    /// Client with the default configuration pointed at the mock.
+    ///
+    /// # Panics
+    /// When the HTTP client cannot be initialized, like `Client::new`.
+    pub fn client(&self) -> Client {
+        self.builder().build().expect("failed to initialize the HTTP client")
+    }
+
+    /// Underlying wiremock server, to mount extra routes or inspect the received requests.
+    pub fn server(&self) -> &MockServer {
+        &self.server
+    }
+}
+
+fn json(body: &str) -> ResponseTemplate {
+    ResponseTemplate::new(200).set_body_raw(body.to_owned(), "application/json")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::models::{ Card, Set };
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_mock_api_serves_fixtures() {
+        let mock = MockApi::start().await;
+        let api = mock.client();
+
+        assert!(api.find_card(&"swsh4-25".parse().unwrap()).await.unwrap().unwrap().name == Some("Charizard".into()));
+        assert!(api.find_set(&"xy1".parse().unwrap()).await.unwrap().unwrap().name == Some("XY".into()));
+        assert!(api.find::<Card>("xy1-999").await.unwrap().is_none());
+        assert!(api.all::<Card>().await.unwrap().len() == 4);
+        assert!(api.all::<Set>().await.unwrap().len() == 2);
+        assert!(api.all_rarities().await.unwrap().len() == 23);
+    }
+}
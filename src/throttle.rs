@@ -0,0 +1,47 @@
+use std::sync::{ Arc, Mutex };
+use std::time::Duration;
+
+use crate::runtime::Instant;
+
+/// Token bucket holding up to one second worth of requests, shared by the clones of a Client so
+/// that they collectively stay under the rate. Waiters reserve their token before sleeping, which
+/// lets them through in the order they arrived.
+#[derive(Clone, Debug)]
+pub(crate) struct RateLimiter {
+    per_second: f64,
+    bucket: Arc<Mutex<Bucket>>,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    /// Negative while requests are waiting for tokens not yet refilled.
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+impl RateLimiter {
+    pub(crate) fn new(per_second: f64) -> Self {
+        let bucket = Bucket { tokens: capacity(per_second), refilled_at: Instant::now() };
+        Self { per_second, bucket: Arc::new(Mutex::new(bucket)) }
+    }
+
+    /// Takes a token, returning how long to wait before sending the request it allows.
+    pub(crate) fn reserve(&self) -> Duration {
+        let mut bucket = self.bucket.lock().unwrap_or_else(|e| e.into_inner());
+
+        let now = Instant::now();
+        let refill = now.duration_since(bucket.refilled_at).as_secs_f64() * self.per_second;
+        bucket.tokens = (bucket.tokens + refill).min(capacity(self.per_second)) - 1.0;
+        bucket.refilled_at = now;
+
+        if bucket.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-bucket.tokens / self.per_second)
+        }
+    }
+}
+
+fn capacity(per_second: f64) -> f64 {
+    per_second.max(1.0)
+}
@@ -0,0 +1,25 @@
+//! Instrumentation emitted under the `pokemon_tcg_sdk` target with the `tracing` feature, e.g.
+//! `RUST_LOG=pokemon_tcg_sdk=debug`; without the feature every call compiles to nothing.
+//! The API Key is never recorded, only whether one was sent.
+
+/// Target of every span and event, independent of the module they come from.
+#[cfg(feature = "tracing")]
+pub(crate) const TARGET: &str = "pokemon_tcg_sdk";
+
+macro_rules! debug {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "tracing")]
+        tracing::debug!(target: $crate::trace::TARGET, $($arg)*);
+    };
+}
+
+pub(crate) use debug;
+
+/// Span covering a request to url, from the cache lookup until the body has been read.
+#[cfg(feature = "tracing")]
+pub(crate) fn request_span(url: &str, query: &std::collections::HashMap<String, String>) -> tracing::Span {
+    let path = reqwest::Url::parse(url).map(|u| u.path().to_string()).unwrap_or(url.into());
+    let page = query.get("page").map(String::as_str).unwrap_or("-");
+
+    tracing::debug_span!(target: TARGET, "request", method = "GET", path = %path, page = %page)
+}
@@ -0,0 +1,461 @@
+use std::collections::{ HashMap, HashSet };
+
+use crate::{ count_args, Client, NameMatch, Q };
+use crate::set_codes::id_number;
+use crate::models::errors::Error;
+use crate::models::models::{ sort_by_number, Card, CardId, CardSummary, Era, Set, SetId, Rarity, Subtype, Supertype, Type };
+
+/// Amount of ids looked up per request by `find_cards`, keeping the URL well under length limits.
+const FIND_CARDS_CHUNK: usize = 50;
+
+/// Typed shortcuts over the generic `Query` methods, so newcomers don't need the turbofish and
+/// only lookups the API actually supports are offered.
+impl Client {
+    /// Finds a card by id, e.g. `xy1-1`.
+    pub async fn find_card(&self, id: &CardId) -> Result<Option<Card>, Error> {
+        self.find::<Card>(id).await
+    }
+
+    /// Finds a set by id, e.g. `xy1`.
+    pub async fn find_set(&self, id: &SetId) -> Result<Option<Set>, Error> {
+        self.find::<Set>(id).await
+    }
+
+    /// Finds a card by the set code and collector number players and decklists name it with,
+    /// e.g. `card_by_set_code("OBF", "125")` for sv3-125. The codes are mapped to set ids by
+    /// `set_codes()`, which fetches every set on first use only.
+    ///
+    /// # Errors
+    /// `MissingSetMapping` when no set has the code, and `InvalidId` when number can't be part of
+    /// a card id.
+    pub async fn card_by_set_code(&self, code: &str, number: &str) -> Result<Option<Card>, Error> {
+        let Some(set_id) = self.set_codes().set_id(self, code).await? else {
+            return Err(Error::MissingSetMapping { set: code.into() });
+        };
+        self.find_card(&format!("{set_id}-{}", id_number(number)).parse()?).await
+    }
+
+    /// Finds the cards with the given ids, e.g. the cards of a decklist, requesting up to 50 of them
+    /// at a time with an `id:a OR id:b ...` query. Cards come in the order of ids; unknown and
+    /// repeated ids are skipped.
+    pub async fn find_cards(&self, ids: &[&str]) -> Result<Vec<Card>, Error> {
+        self.find_cards_with_missing(ids).await.map(|(cards, _)| cards)
+    }
+
+    /// Same as `find_cards`, also returning the ids the API doesn't know.
+    pub async fn find_cards_with_missing(&self, ids: &[&str]) -> Result<(Vec<Card>, Vec<String>), Error> {
+        let mut seen = HashSet::new();
+        let ids: Vec<&str> = ids.iter().copied().filter(|id| seen.insert(*id)).collect();
+        let mut found = HashMap::<String, Card>::new();
+
+        for chunk in ids.chunks(FIND_CARDS_CHUNK) {
+            let q = chunk.iter()
+                         .map(|id| Q::field("id").eq(id))
+                         .reduce(Q::or)
+                         .expect("chunks are never empty");
+            let args = HashMap::from([
+                (String::from("q"), q.to_string()),
+                (String::from("pageSize"), format!("{FIND_CARDS_CHUNK}")),
+            ]);
+
+            for card in self.query::<Card>(args).await? {
+                if let Some(id) = card.id.clone() {
+                    found.insert(id.into(), card);
+                }
+            }
+        }
+
+        let mut cards = Vec::new();
+        let mut missing = Vec::new();
+        for id in ids {
+            match found.remove(id) {
+                Some(card) => cards.push(card),
+                None => missing.push(id.to_string()),
+            }
+        }
+
+        Ok((cards, missing))
+    }
+
+    /// Every card matching the search query `q`, either a raw string such as
+    /// `name:charizard supertype:pokemon` or a [`crate::Q`].
+    pub async fn search_cards(&self, q: impl Into<String>) -> Result<Vec<Card>, Error> {
+        self.query::<Card>(HashMap::from([(String::from("q"), q.into())])).await
+    }
+
+    /// Id, name, images and set id of every card matching the search query `q`; only these fields
+    /// are requested, which makes paging through large searches much cheaper.
+    pub async fn search_card_summaries(&self, q: impl Into<String>) -> Result<Vec<CardSummary>, Error> {
+        self.query::<CardSummary>(HashMap::from([
+            (String::from("q"), q.into()),
+            (String::from("select"), CardSummary::SELECT.join(",")),
+        ])).await
+    }
+
+    /// Every card whose name matches name the way match_mode says, ignoring case, e.g.
+    /// `cards_named("Type: Null", NameMatch::Exact)`; special characters in name are escaped.
+    pub async fn cards_named(&self, name: &str, match_mode: NameMatch) -> Result<Vec<Card>, Error> {
+        self.search_cards(match_mode.q(name)).await
+    }
+
+    /// Every printing of the Pokémon with National Pokédex number n, e.g. 6 for Charizard.
+    pub async fn cards_by_pokedex_number(&self, n: u32) -> Result<Vec<Card>, Error> {
+        self.search_cards(Q::field("nationalPokedexNumbers").eq(n)).await
+    }
+
+    /// Every card whose ability texts, attack texts or rules mention phrase, e.g. "discard your
+    /// hand"; phrase is quoted and escaped. See `CardDatabase::cards_with_text` to search offline.
+    pub async fn cards_with_text(&self, phrase: &str) -> Result<Vec<Card>, Error> {
+        let cards = self.search_cards(text_query(phrase)).await?;
+        Ok(crate::cards::dedup_by_id(cards))
+    }
+
+    /// A card picked uniformly at random among those matching filter, or among every card without
+    /// one. The API has no random endpoint: this counts the matches, then requests the single card
+    /// at a random position, pages being one card long.
+    ///
+    /// # Errors
+    /// `NoMatchingCards` when the filter matches no card.
+    pub async fn random_card(&self, filter: Option<&Q>) -> Result<Card, Error> {
+        let q = filter.map(Q::to_string).unwrap_or_default();
+        let total = self.count::<Card>(&q).await?;
+        if total == 0 {
+            return Err(Error::NoMatchingCards { q });
+        }
+
+        let mut args = count_args(&q);
+        args.insert(String::from("page"), format!("{}", rand::random_range(1..=total)));
+
+        let page = self.search_page::<Card>(args).await?;
+        page.data.into_iter().next().ok_or(Error::NoMatchingCards { q })
+    }
+
+    /// Every card of the set with id set_id, e.g. "sv4", in collector number order.
+    ///
+    /// # Errors
+    /// `SetNotFound` when the API doesn't know the set, rather than an empty list, and
+    /// `InvalidId` when set_id can't be one.
+    pub async fn cards_in_set(&self, set_id: &str) -> Result<Vec<Card>, Error> {
+        if self.find_set(&set_id.parse()?).await?.is_none() {
+            return Err(Error::SetNotFound { id: set_id.into() });
+        }
+
+        let mut cards = self.search_cards(Q::field("set.id").eq(set_id)).await?;
+        sort_by_number(&mut cards);
+        Ok(cards)
+    }
+
+    /// Every set of the series, e.g. "Scarlet & Violet", oldest first.
+    ///
+    /// # Errors
+    /// `SeriesNotFound` when no set belongs to the series.
+    pub async fn sets_by_series(&self, series: &str) -> Result<Vec<Set>, Error> {
+        let mut sets = self.search_sets(Q::field("series").eq(series)).await?;
+        if sets.is_empty() {
+            return Err(Error::SeriesNotFound { series: series.into() });
+        }
+
+        // releaseDate is formatted year first, see RELEASE_DATE_FORMAT
+        sets.sort_by(|a, b| a.releaseDate.cmp(&b.releaseDate));
+        Ok(sets)
+    }
+
+    /// Every set of the era, oldest first, e.g. `sets_in_era(Era::ScarletViolet)`.
+    ///
+    /// # Errors
+    /// `SeriesNotFound` when no set belongs to the era's series.
+    pub async fn sets_in_era(&self, era: Era) -> Result<Vec<Set>, Error> {
+        self.sets_by_series(era.series()).await
+    }
+
+    /// Every set matching the search query `q`, e.g. `series:xy` or a [`crate::Q`].
+    pub async fn search_sets(&self, q: impl Into<String>) -> Result<Vec<Set>, Error> {
+        self.query::<Set>(HashMap::from([(String::from("q"), q.into())])).await
+    }
+
+    pub async fn all_types(&self) -> Result<Vec<Type>, Error> {
+        self.all::<Type>().await
+    }
+
+    pub async fn all_subtypes(&self) -> Result<Vec<Subtype>, Error> {
+        self.all::<Subtype>().await
+    }
+
+    pub async fn all_supertypes(&self) -> Result<Vec<Supertype>, Error> {
+        self.all::<Supertype>().await
+    }
+
+    pub async fn all_rarities(&self) -> Result<Vec<Rarity>, Error> {
+        self.all::<Rarity>().await
+    }
+}
+
+/// Fields holding the text printed on cards.
+const TEXT_FIELDS: [&str; 3] = ["abilities.text", "attacks.text", "rules"];
+
+fn text_query(phrase: &str) -> Q {
+    TEXT_FIELDS.iter()
+               .map(|field| Q::field(field).eq(phrase))
+               .reduce(Q::or)
+               .expect("TEXT_FIELDS isn't empty")
+}
+
+#[cfg(test)]
+mod tests {
+    use wiremock::{ Mock, MockServer, ResponseTemplate };
+    use wiremock::matchers::{ method, path, query_param, query_param_is_missing };
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_search_cards_sends_query() {
+        let cwd = std::env::current_dir().unwrap();
+        let mocks = cwd.join("src/mock/cards_page_1.json");
+        let mock_data_string = std::fs::read_to_string(mocks).unwrap().replace("\"totalCount\": 6", "\"totalCount\": 2");
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/cards"))
+            .and(query_param("q", "name:venusaur*"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(mock_data_string, "application/json"))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let api = Client::builder().base_url(server.uri()).build().unwrap();
+        let cards = api.search_cards("name:venusaur*").await.unwrap();
+
+        assert!(cards.len() == 2);
+        assert!(cards[0].name == Some("Venusaur-EX".into()));
+    }
+
+    #[tokio::test]
+    async fn test_search_card_summaries_selects_fields() {
+        let body = r#"{
+            "data": [
+                { "id": "xy1-1", "name": "Venusaur-EX", "images": { "small": "https://images.pokemontcg.io/xy1/1.png" }, "set": { "id": "xy1", "name": "XY" } },
+                { "id": "xy1-2", "name": "M Venusaur-EX" }
+            ],
+            "page": 1, "pageSize": 250, "count": 2, "totalCount": 2
+        }"#;
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/cards"))
+            .and(query_param("select", "id,name,images,set"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(body, "application/json"))
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        let api = Client::builder().base_url(server.uri()).build().unwrap();
+        let cards = api.search_card_summaries("set.id:xy1").await.unwrap();
+
+        assert!(cards.len() == 2);
+        let id: &CardId = cards[0].id.as_ref().unwrap();
+        assert!(id.set_id() == "xy1" && cards[0].setId == Some("xy1".into()));
+        assert!(cards[0].images.as_ref().unwrap().large.is_none());
+        assert!(cards[1].setId.is_none() && cards[1].images.is_none());
+
+        let partial: Vec<Card> = api.query(HashMap::from([(String::from("select"), String::from("id,name,images,set"))])).await.unwrap();
+        assert!(partial[1].name == Some("M Venusaur-EX".into()) && partial[1].set.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_find_cards_keeps_input_order_and_reports_missing() {
+        let cwd = std::env::current_dir().unwrap();
+        let mocks = cwd.join("src/mock/cards_page_1.json");
+        let mock_data_string = std::fs::read_to_string(mocks).unwrap().replace("\"totalCount\": 6", "\"totalCount\": 2");
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/cards"))
+            .and(query_param("q", r"(id:xy1\-2 OR id:xy1\-999 OR id:xy1\-1)"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(mock_data_string, "application/json"))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let api = Client::builder().base_url(server.uri()).build().unwrap();
+        let (cards, missing) = api.find_cards_with_missing(&["xy1-2", "xy1-999", "xy1-1", "xy1-2"]).await.unwrap();
+        let ids: Vec<String> = cards.into_iter().filter_map(|c| c.id.map(String::from)).collect();
+
+        assert!(ids == vec!["xy1-2", "xy1-1"]);
+        assert!(missing == vec!["xy1-999"]);
+    }
+
+    #[tokio::test]
+    async fn test_cards_by_pokedex_number() {
+        let mock = crate::testing::MockApi::start().await;
+        let api = mock.client();
+
+        let cards = api.cards_by_pokedex_number(3).await.unwrap();
+        let requests = mock.server().received_requests().await.unwrap();
+
+        assert!(cards.len() == 4);
+        assert!(requests.iter().all(|r| r.url.query_pairs().any(|(k, v)| k == "q" && v == "nationalPokedexNumbers:3")));
+        assert!(cards[0].nationalPokedexNumbers.is_empty());
+        assert!(api.find_card(&"xy1-1".parse().unwrap()).await.unwrap().unwrap().nationalPokedexNumbers == vec![3]);
+    }
+
+    #[tokio::test]
+    async fn test_random_card_requests_a_single_card() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/cards"))
+            .and(query_param("q", "supertype:trainer"))
+            .and(query_param_is_missing("page"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(
+                r#"{"data": [{"id": "c-1"}], "page": 1, "pageSize": 1, "count": 1, "totalCount": 3}"#,
+                "application/json",
+            ))
+            .mount(&server)
+            .await;
+        for page in 1..=3 {
+            Mock::given(method("GET"))
+                .and(path("/cards"))
+                .and(query_param("pageSize", "1"))
+                .and(query_param("page", format!("{page}")))
+                .respond_with(ResponseTemplate::new(200).set_body_raw(
+                    format!(r#"{{"data": [{{"id": "c-{page}"}}], "page": {page}, "pageSize": 1, "count": 1, "totalCount": 3}}"#),
+                    "application/json",
+                ))
+                .mount(&server)
+                .await;
+        }
+        Mock::given(method("GET"))
+            .and(path("/cards"))
+            .and(query_param("q", "name:nobody"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(
+                r#"{"data": [], "page": 1, "pageSize": 1, "count": 0, "totalCount": 0}"#,
+                "application/json",
+            ))
+            .mount(&server)
+            .await;
+
+        let api = Client::builder().base_url(server.uri()).build().unwrap();
+        let trainers = Q::field("supertype").eq("trainer");
+        for _ in 0..10 {
+            let card = api.random_card(Some(&trainers)).await.unwrap();
+            assert!(["c-1", "c-2", "c-3"].contains(&card.id.as_deref().unwrap()));
+        }
+        assert!(server.received_requests().await.unwrap().len() == 20);
+
+        let nobody = Q::field("name").eq("nobody");
+        assert!(matches!(api.random_card(Some(&nobody)).await, Err(Error::NoMatchingCards { q }) if q == "name:nobody"));
+    }
+
+    #[tokio::test]
+    async fn test_cards_named_type_null() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/cards"))
+            .and(query_param("q", r#"!name:"Type: Null""#))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(
+                r#"{"data": [{"id": "sm2-117", "name": "Type: Null"}], "page": 1, "pageSize": 250, "count": 1, "totalCount": 1}"#,
+                "application/json",
+            ))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/cards"))
+            .and(query_param("q", r"name:Type\:\ Null*"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(
+                r#"{"data": [{"id": "sm2-117", "name": "Type: Null"}, {"id": "swsh12-1", "name": "Type: Null V"}], "page": 1, "pageSize": 250, "count": 2, "totalCount": 2}"#,
+                "application/json",
+            ))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let api = Client::builder().base_url(server.uri()).build().unwrap();
+
+        assert!(api.cards_named("Type: Null", NameMatch::Exact).await.unwrap().len() == 1);
+        assert!(api.cards_named("Type: Null", NameMatch::Prefix).await.unwrap().len() == 2);
+    }
+
+    #[tokio::test]
+    async fn test_cards_in_set_sorted_by_number() {
+        let mock = crate::testing::MockApi::start().await;
+        Mock::given(method("GET"))
+            .and(path("/cards"))
+            .and(query_param("q", "set.id:xy1"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(
+                r#"{"data": [{"id": "xy1-112", "number": "112"}, {"id": "xy1-2", "number": "2"}, {"id": "xy1-12", "number": "12"}], "page": 1, "pageSize": 250, "count": 3, "totalCount": 3}"#,
+                "application/json",
+            ))
+            .with_priority(1)
+            .mount(mock.server())
+            .await;
+        let api = mock.client();
+
+        let ids: Vec<String> = api.cards_in_set("xy1").await.unwrap().into_iter().filter_map(|c| c.id.map(String::from)).collect();
+        assert!(ids == vec!["xy1-2", "xy1-12", "xy1-112"]);
+        assert!(matches!(api.cards_in_set("xy99").await, Err(Error::SetNotFound { id }) if id == "xy99"));
+    }
+
+    #[tokio::test]
+    async fn test_sets_by_series_oldest_first() {
+        let mock = crate::testing::MockApi::start().await;
+        Mock::given(method("GET"))
+            .and(path("/sets"))
+            .and(query_param("q", "series:Gym"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(
+                r#"{"data": [], "page": 1, "pageSize": 250, "count": 0, "totalCount": 0}"#,
+                "application/json",
+            ))
+            .with_priority(1)
+            .mount(mock.server())
+            .await;
+        let api = mock.client();
+
+        let sets: Vec<String> = api.sets_by_series("XY").await.unwrap().into_iter().filter_map(|s| s.id.map(String::from)).collect();
+        assert!(sets == vec!["xy1", "xy2"]);
+        assert!(matches!(api.sets_by_series("Gym").await, Err(Error::SeriesNotFound { series }) if series == "Gym"));
+    }
+
+    #[tokio::test]
+    async fn test_sets_in_era() {
+        let mock = crate::testing::MockApi::start().await;
+        Mock::given(method("GET"))
+            .and(path("/sets"))
+            .and(query_param("q", Q::field("series").eq("Mega Evolution").to_string()))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(
+                r#"{"data": [], "page": 1, "pageSize": 250, "count": 0, "totalCount": 0}"#,
+                "application/json",
+            ))
+            .with_priority(1)
+            .mount(mock.server())
+            .await;
+        let api = mock.client();
+
+        let sets = api.sets_in_era(Era::Xy).await.unwrap();
+        assert!(sets.iter().all(|s| s.era() == Era::Xy) && sets.len() == 2);
+        assert!(mock.server().received_requests().await.unwrap()[0].url.query_pairs().any(|(k, v)| k == "q" && v == "series:XY"));
+        assert!(matches!(api.sets_in_era(Era::MegaEvolution).await, Err(Error::SeriesNotFound { series }) if series == "Mega Evolution"));
+    }
+
+    #[tokio::test]
+    async fn test_cards_with_text_searches_every_text_field() {
+        let q = r#"(abilities.text:"discard your \"hand\"" OR attacks.text:"discard your \"hand\"" OR rules:"discard your \"hand\"")"#;
+        assert!(text_query(r#"discard your "hand""#).to_string() == q);
+        assert!(text_query("Lost").to_string() == "(abilities.text:Lost OR attacks.text:Lost OR rules:Lost)");
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/cards"))
+            .and(query_param("q", "(abilities.text:\"discard your hand\" OR attacks.text:\"discard your hand\" OR rules:\"discard your hand\")"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "data": [{ "id": "sv1-1" }, { "id": "sv2-5" }, { "id": "sv1-1" }],
+                "page": 1, "pageSize": 250, "count": 3, "totalCount": 3,
+            })))
+            .mount(&server)
+            .await;
+
+        let api = Client::builder().base_url(server.uri()).build().unwrap();
+        let cards = api.cards_with_text("discard your hand").await.unwrap();
+        let ids: Vec<&str> = cards.iter().filter_map(|c| c.id.as_deref()).collect();
+        assert!(ids == vec!["sv1-1", "sv2-5"]);
+    }
+}